@@ -21,18 +21,23 @@ use super::{
     presentation::{
         AlignmentBaseline, BaselineShift, ClipPathValue, ClipRule, ColorInterpolation,
         ColorInterpolationFilter, CursorValue, Display, DominantBaseline, EllipsisRadius, Fill,
-        FillRule, FilterValue, FontSize, FontSizeAdjust, FontStyle, ImageRendering,
-        LengthOrPercentageOrNumber, LetterSpacing, LightingColor, Marker, MaskType, Opacity,
-        Overflow, PointerEvents, Rotate, ShapeRendering, StopColor, StrokeLinecap, StrokeLinejoin,
-        StrokeOpacity, TextAnchor, TextDirection, TextOverflow, TextRendering, UnicodeBidi,
-        VectorEffect, Visibility, WhiteSpace, WordSpacing, WritingMode,
+        FillRule, FilterValue, FontSize, FontSizeAdjust, FontStyle, ImageRendering, LetterSpacing,
+        LightingColor, Marker, MaskType, Opacity, Overflow, PointerEvents, Rotate, ShapeRendering,
+        StopColor, StrokeLinecap, StrokeLinejoin, StrokeOpacity, TextAnchor, TextDirection,
+        TextOverflow, TextRendering, UnicodeBidi, VectorEffect, Visibility, WhiteSpace,
+        WordSpacing, WritingMode,
     },
+    xml::XmlSpace,
 };
 use crate::element::{
     ElementType,
     lang::LanguageTag,
-    types::{AbsoluteLength, Color, Length, LengthOrPercentage, Paint, Percentage, Url},
+    types::{Color, Paint, Url},
 };
+use crate::entity::escape_attribute;
+use crate::namespace::QualifiedName;
+use crate::serialize::round_numbers;
+use crate::svg::types::{Length, LengthOrPercentage, Percentage, TransformList, TransformOrigin};
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum Attribute {
@@ -76,7 +81,7 @@ pub enum Attribute {
     FontStyle(FontStyle),
     FontVariant(String),
     FontWeight(crate::element::types::FontWeight),
-    Height(LengthOrPercentageOrNumber),
+    Height(LengthOrPercentage),
     ImageRendering(ImageRendering),
     LetterSpacing(LetterSpacing),
     LightingColor(LightingColor),
@@ -88,7 +93,7 @@ pub enum Attribute {
     Opacity(Opacity),
     Overflow(Overflow),
     PointerEvents(PointerEvents),
-    R(LengthOrPercentageOrNumber),
+    R(LengthOrPercentage),
     Rx(EllipsisRadius),
     Ry(EllipsisRadius),
     ShapeRendering(ShapeRendering),
@@ -106,17 +111,17 @@ pub enum Attribute {
     TextDecoration(String),
     TextOverflow(TextOverflow),
     TextRendering(TextRendering),
-    Transform(String),
-    TransformOrigin(String),
+    Transform(TransformList),
+    TransformOrigin(TransformOrigin),
     UnicodeBidi(UnicodeBidi),
     VectorEffect(VectorEffect),
     Visibility(Visibility),
-    Width(LengthOrPercentageOrNumber),
+    Width(LengthOrPercentage),
     WhiteSpace(WhiteSpace),
     WordSpacing(WordSpacing),
     WritingMode(WritingMode),
-    X(LengthOrPercentageOrNumber),
-    Y(LengthOrPercentageOrNumber),
+    X(LengthOrPercentage),
+    Y(LengthOrPercentage),
 
     // Transfer Function Attributes
     Type(FeFuncType),
@@ -235,10 +240,10 @@ pub enum Attribute {
     PathLength(f64),
 
     // Gradient/Shape coordinates
-    X1(LengthOrPercentageOrNumber),
-    Y1(LengthOrPercentageOrNumber),
-    X2(LengthOrPercentageOrNumber),
-    Y2(LengthOrPercentageOrNumber),
+    X1(LengthOrPercentage),
+    Y1(LengthOrPercentage),
+    X2(LengthOrPercentage),
+    Y2(LengthOrPercentage),
     Points(Vec<Point>),
 
     // Link attributes
@@ -267,7 +272,7 @@ pub enum Attribute {
     // Pattern attributes
     PatternContentUnits(PatternContentUnits),
     PatternUnits(PatternUnits),
-    PatternTransform(String),
+    PatternTransform(TransformList),
 
     // Filter attributes
     Result(String),
@@ -314,7 +319,7 @@ pub enum Attribute {
 
     // Gradient attributes
     GradientUnits(GradientUnits),
-    GradientTransform(String),
+    GradientTransform(TransformList),
     SpreadMethod(SpreadMethod),
 
     // Radial gradient attributes
@@ -329,7 +334,7 @@ pub enum Attribute {
     Method(Method),
     Side(Side),
     Spacing(Spacing),
-    StartOffset(LengthOrPercentageOrNumber),
+    StartOffset(LengthOrPercentage),
 
     // SVG attributes
     FilterUnits(FilterUnits),
@@ -342,16 +347,27 @@ pub enum Attribute {
 
     // Data attributes
     Data(String, String),
+
+    // Namespaced attributes
+    /// `xlink:href`, the SVG 1.1 form of `href`.
+    XlinkHref(String),
+    /// `xml:space`.
+    XmlSpace(XmlSpace),
+    /// Any other prefixed attribute, including `xmlns:*` declarations.
+    Foreign(QualifiedName, String),
+
+    /// An attribute this crate does not know, such as a vendor extension.
+    Unknown(String, String),
 }
 
 use super::filter::{MaskContentUnits, PatternContentUnits, PatternUnits};
 
-impl TryFrom<(&String, &String)> for Attribute {
+impl TryFrom<(&str, &str)> for Attribute {
     type Error = ();
 
-    fn try_from((key, value): (&String, &String)) -> Result<Self, Self::Error> {
-        match key.as_str() {
-            "xmlns" => Ok(Attribute::Xmlns(value.clone())),
+    fn try_from((key, value): (&str, &str)) -> Result<Self, Self::Error> {
+        match key {
+            "xmlns" => Ok(Attribute::Xmlns(value.to_string())),
             "version" => Ok(Attribute::Version(value.parse().map_err(|_| ())?)),
             "autofocus" => {
                 if value.is_empty() || value.eq_ignore_ascii_case("autofocus") {
@@ -360,14 +376,14 @@ impl TryFrom<(&String, &String)> for Attribute {
                     Ok(Attribute::Autofocus(false))
                 }
             }
-            "id" => Ok(Attribute::Id(value.clone())),
+            "id" => Ok(Attribute::Id(value.to_string())),
             "class" => Ok(Attribute::Class(
                 value
                     .split_whitespace()
                     .map(|class| class.to_string())
                     .collect(),
             )),
-            "style" => Ok(Attribute::Style(value.clone())),
+            "style" => Ok(Attribute::Style(value.to_string())),
             "lang" => Ok(Attribute::Lang(value.parse()?)),
             "tabindex" => Ok(Attribute::Tabindex(value.parse().unwrap_or(0))),
             "requiredExtensions" => {
@@ -405,22 +421,24 @@ impl TryFrom<(&String, &String)> for Attribute {
             "filter" => Ok(Attribute::Filter(value.parse()?)),
             "flood-color" => Ok(Attribute::FloodColor(value.parse()?)),
             "flood-opacity" => Ok(Attribute::FloodOpacity(value.parse().map_err(|_| ())?)),
-            "font-family" => Ok(Attribute::FontFamily(value.clone())),
+            "font-family" => Ok(Attribute::FontFamily(value.to_string())),
             "font-size" => Ok(Attribute::FontSize(value.parse().map_err(|_| ())?)),
             "font-size-adjust" => Ok(Attribute::FontSizeAdjust(value.parse()?)),
             "font-style" => Ok(Attribute::FontStyle(value.parse()?)),
-            "font-variant" => Ok(Attribute::FontVariant(value.clone())),
+            "font-variant" => Ok(Attribute::FontVariant(value.to_string())),
             "font-weight" => Ok(Attribute::FontWeight(value.parse()?)),
-            "height" => Ok(Attribute::Height(value.parse().unwrap_or(
-                LengthOrPercentageOrNumber::Length(Length::Absolute(AbsoluteLength::Px(1.0))),
-            ))),
+            "height" => {
+                Ok(Attribute::Height(value.parse().unwrap_or(
+                    LengthOrPercentage::Length(Length::number(1.0)),
+                )))
+            }
             "image-rendering" => Ok(Attribute::ImageRendering(value.parse()?)),
             "letter-spacing" => Ok(Attribute::LetterSpacing(value.parse()?)),
             "lighting-color" => Ok(Attribute::LightingColor(value.parse()?)),
             "marker-end" => Ok(Attribute::MarkerEnd(value.parse()?)),
             "marker-mid" => Ok(Attribute::MarkerMid(value.parse()?)),
             "marker-start" => Ok(Attribute::MarkerStart(value.parse()?)),
-            "mask" => Ok(Attribute::Mask(value.clone())),
+            "mask" => Ok(Attribute::Mask(value.to_string())),
             "mask-type" => Ok(Attribute::MaskType(value.parse()?)),
             "opacity" => Ok(Attribute::Opacity(value.parse()?)),
             "overflow" => Ok(Attribute::Overflow(value.parse()?)),
@@ -449,27 +467,33 @@ impl TryFrom<(&String, &String)> for Attribute {
             "stroke-opacity" => Ok(Attribute::StrokeOpacity(value.parse()?)),
             "stroke-width" => Ok(Attribute::StrokeWidth(value.parse()?)),
             "text-anchor" => Ok(Attribute::TextAnchor(value.parse()?)),
-            "text-decoration" => Ok(Attribute::TextDecoration(value.clone())),
+            "text-decoration" => Ok(Attribute::TextDecoration(value.to_string())),
             "text-overflow" => Ok(Attribute::TextOverflow(value.parse()?)),
             "text-rendering" => Ok(Attribute::TextRendering(value.parse()?)),
-            "transform" => Ok(Attribute::Transform(value.clone())),
-            "transform-origin" => Ok(Attribute::TransformOrigin(value.clone())),
+            "transform" => Ok(Attribute::Transform(value.parse()?)),
+            "transform-origin" => Ok(Attribute::TransformOrigin(value.parse()?)),
             "unicode-bidi" => Ok(Attribute::UnicodeBidi(value.parse()?)),
             "vector-effect" => Ok(Attribute::VectorEffect(value.parse()?)),
             "viewBox" => Ok(Attribute::ViewBox(value.parse()?)),
             "visibility" => Ok(Attribute::Visibility(value.parse()?)),
-            "width" => Ok(Attribute::Width(value.parse().unwrap_or(
-                LengthOrPercentageOrNumber::Length(Length::Absolute(AbsoluteLength::Px(1.0))),
-            ))),
+            "width" => {
+                Ok(Attribute::Width(value.parse().unwrap_or(
+                    LengthOrPercentage::Length(Length::number(1.0)),
+                )))
+            }
             "white-space" => Ok(Attribute::WhiteSpace(value.parse()?)),
             "word-spacing" => Ok(Attribute::WordSpacing(value.parse()?)),
             "writing-mode" => Ok(Attribute::WritingMode(value.parse()?)),
-            "x" => Ok(Attribute::X(value.parse().unwrap_or(
-                LengthOrPercentageOrNumber::Length(Length::Absolute(AbsoluteLength::Px(0.0))),
-            ))),
-            "y" => Ok(Attribute::Y(value.parse().unwrap_or(
-                LengthOrPercentageOrNumber::Length(Length::Absolute(AbsoluteLength::Px(0.0))),
-            ))),
+            "x" => {
+                Ok(Attribute::X(value.parse().unwrap_or(
+                    LengthOrPercentage::Length(Length::number(0.0)),
+                )))
+            }
+            "y" => {
+                Ok(Attribute::Y(value.parse().unwrap_or(
+                    LengthOrPercentage::Length(Length::number(0.0)),
+                )))
+            }
             "type" => Ok(Attribute::Type(value.parse()?)),
             "tableValues" => Ok(Attribute::TableValues(
                 value
@@ -482,10 +506,10 @@ impl TryFrom<(&String, &String)> for Attribute {
             "amplitude" => Ok(Attribute::Amplitude(value.parse::<f64>().map_err(|_| ())?)),
             "exponent" => Ok(Attribute::Exponent(value.parse::<f64>().map_err(|_| ())?)),
             "offset" => Ok(Attribute::Offset(value.parse::<f64>().map_err(|_| ())?)),
-            "href" => Ok(Attribute::Href(value.clone())),
+            "href" => Ok(Attribute::Href(value.to_string())),
             "hreflang" => Ok(Attribute::HrefLang(value.parse()?)),
             "attributeType" => Ok(Attribute::AttributeType(value.parse()?)),
-            "attributeName" => Ok(Attribute::AttributeName(value.clone())),
+            "attributeName" => Ok(Attribute::AttributeName(value.to_string())),
             "begin" => Ok(Attribute::Begin(
                 value
                     .split(';')
@@ -506,78 +530,78 @@ impl TryFrom<(&String, &String)> for Attribute {
             "repeatDur" => Ok(Attribute::RepeatDur(value.parse()?)),
             "additive" => Ok(Attribute::Additive(value.parse()?)),
             "accumulate" => Ok(Attribute::Accumulate(value.parse()?)),
-            "onAfterPrint" => Ok(Attribute::OnAfterPrint(value.clone())),
-            "onBeforePrint" => Ok(Attribute::OnBeforePrint(value.clone())),
-            "onBeforeUnload" => Ok(Attribute::OnBeforeUnload(value.clone())),
-            "onError" => Ok(Attribute::OnError(value.clone())),
-            "onHashChange" => Ok(Attribute::OnHashChange(value.clone())),
-            "onLoad" => Ok(Attribute::OnLoad(value.clone())),
-            "onMessage" => Ok(Attribute::OnMessage(value.clone())),
-            "onOffline" => Ok(Attribute::OnOffline(value.clone())),
-            "onOnline" => Ok(Attribute::OnOnline(value.clone())),
-            "onPageHide" => Ok(Attribute::OnPageHide(value.clone())),
-            "onPageShow" => Ok(Attribute::OnPageShow(value.clone())),
-            "onPopState" => Ok(Attribute::OnPopState(value.clone())),
-            "onResize" => Ok(Attribute::OnResize(value.clone())),
-            "onStorage" => Ok(Attribute::OnStorage(value.clone())),
-            "onUnload" => Ok(Attribute::OnUnload(value.clone())),
-            "onChange" => Ok(Attribute::OnChange(value.clone())),
-            "onContextMenu" => Ok(Attribute::OnContextMenu(value.clone())),
-            "onFocus" => Ok(Attribute::OnFocus(value.clone())),
-            "onInput" => Ok(Attribute::OnInput(value.clone())),
-            "onInvalid" => Ok(Attribute::OnInvalid(value.clone())),
-            "onReset" => Ok(Attribute::OnReset(value.clone())),
-            "onSearch" => Ok(Attribute::OnSearch(value.clone())),
-            "onSelect" => Ok(Attribute::OnSelect(value.clone())),
-            "onSubmit" => Ok(Attribute::OnSubmit(value.clone())),
-            "onKeyDown" => Ok(Attribute::OnKeyDown(value.clone())),
-            "onKeyPress" => Ok(Attribute::OnKeyPress(value.clone())),
-            "onKeyUp" => Ok(Attribute::OnKeyUp(value.clone())),
-            "onClick" => Ok(Attribute::OnClick(value.clone())),
-            "onDoubleClick" => Ok(Attribute::OnDoubleClick(value.clone())),
-            "onMouseDown" => Ok(Attribute::OnMouseDown(value.clone())),
-            "onMouseMove" => Ok(Attribute::OnMouseMove(value.clone())),
-            "onMouseOut" => Ok(Attribute::OnMouseOut(value.clone())),
-            "onMouseOver" => Ok(Attribute::OnMouseOver(value.clone())),
-            "onMouseUp" => Ok(Attribute::OnMouseUp(value.clone())),
-            "onWheel" => Ok(Attribute::OnWheel(value.clone())),
-            "onDrag" => Ok(Attribute::OnDrag(value.clone())),
-            "onDragEnd" => Ok(Attribute::OnDragEnd(value.clone())),
-            "onDragEnter" => Ok(Attribute::OnDragEnter(value.clone())),
-            "onDragLeave" => Ok(Attribute::OnDragLeave(value.clone())),
-            "onDragOver" => Ok(Attribute::OnDragOver(value.clone())),
-            "onDragStart" => Ok(Attribute::OnDragStart(value.clone())),
-            "onDrop" => Ok(Attribute::OnDrop(value.clone())),
-            "onScroll" => Ok(Attribute::OnScroll(value.clone())),
-            "onCopy" => Ok(Attribute::OnCopy(value.clone())),
-            "onCut" => Ok(Attribute::OnCut(value.clone())),
-            "onPaste" => Ok(Attribute::OnPaste(value.clone())),
-            "onAbort" => Ok(Attribute::OnAbort(value.clone())),
-            "onCanPlay" => Ok(Attribute::OnCanPlay(value.clone())),
-            "onCanPlayThrough" => Ok(Attribute::OnCanPlayThrough(value.clone())),
-            "onCueChange" => Ok(Attribute::OnCueChange(value.clone())),
-            "onDurationChange" => Ok(Attribute::OnDurationChange(value.clone())),
-            "onEmptied" => Ok(Attribute::OnEmptied(value.clone())),
-            "onEnded" => Ok(Attribute::OnEnded(value.clone())),
-            "onLoadedData" => Ok(Attribute::OnLoadedData(value.clone())),
-            "onLoadedMetadata" => Ok(Attribute::OnLoadedMetadata(value.clone())),
-            "onLoadStart" => Ok(Attribute::OnLoadStart(value.clone())),
-            "onPause" => Ok(Attribute::OnPause(value.clone())),
-            "onPlay" => Ok(Attribute::OnPlay(value.clone())),
-            "onPlaying" => Ok(Attribute::OnPlaying(value.clone())),
-            "onProgress" => Ok(Attribute::OnProgress(value.clone())),
-            "onRateChange" => Ok(Attribute::OnRateChange(value.clone())),
-            "onSeeked" => Ok(Attribute::OnSeeked(value.clone())),
-            "onSeeking" => Ok(Attribute::OnSeeking(value.clone())),
-            "onStalled" => Ok(Attribute::OnStalled(value.clone())),
-            "onSuspend" => Ok(Attribute::OnSuspend(value.clone())),
-            "onTimeUpdate" => Ok(Attribute::OnTimeUpdate(value.clone())),
-            "onVolumeChange" => Ok(Attribute::OnVolumeChange(value.clone())),
-            "onWaiting" => Ok(Attribute::OnWaiting(value.clone())),
-            "onToggle" => Ok(Attribute::OnToggle(value.clone())),
-            "onbegin" => Ok(Attribute::OnBegin(value.clone())),
-            "onend" => Ok(Attribute::OnEnd(value.clone())),
-            "onrepeat" => Ok(Attribute::OnRepeat(value.clone())),
+            "onAfterPrint" => Ok(Attribute::OnAfterPrint(value.to_string())),
+            "onBeforePrint" => Ok(Attribute::OnBeforePrint(value.to_string())),
+            "onBeforeUnload" => Ok(Attribute::OnBeforeUnload(value.to_string())),
+            "onError" => Ok(Attribute::OnError(value.to_string())),
+            "onHashChange" => Ok(Attribute::OnHashChange(value.to_string())),
+            "onLoad" => Ok(Attribute::OnLoad(value.to_string())),
+            "onMessage" => Ok(Attribute::OnMessage(value.to_string())),
+            "onOffline" => Ok(Attribute::OnOffline(value.to_string())),
+            "onOnline" => Ok(Attribute::OnOnline(value.to_string())),
+            "onPageHide" => Ok(Attribute::OnPageHide(value.to_string())),
+            "onPageShow" => Ok(Attribute::OnPageShow(value.to_string())),
+            "onPopState" => Ok(Attribute::OnPopState(value.to_string())),
+            "onResize" => Ok(Attribute::OnResize(value.to_string())),
+            "onStorage" => Ok(Attribute::OnStorage(value.to_string())),
+            "onUnload" => Ok(Attribute::OnUnload(value.to_string())),
+            "onChange" => Ok(Attribute::OnChange(value.to_string())),
+            "onContextMenu" => Ok(Attribute::OnContextMenu(value.to_string())),
+            "onFocus" => Ok(Attribute::OnFocus(value.to_string())),
+            "onInput" => Ok(Attribute::OnInput(value.to_string())),
+            "onInvalid" => Ok(Attribute::OnInvalid(value.to_string())),
+            "onReset" => Ok(Attribute::OnReset(value.to_string())),
+            "onSearch" => Ok(Attribute::OnSearch(value.to_string())),
+            "onSelect" => Ok(Attribute::OnSelect(value.to_string())),
+            "onSubmit" => Ok(Attribute::OnSubmit(value.to_string())),
+            "onKeyDown" => Ok(Attribute::OnKeyDown(value.to_string())),
+            "onKeyPress" => Ok(Attribute::OnKeyPress(value.to_string())),
+            "onKeyUp" => Ok(Attribute::OnKeyUp(value.to_string())),
+            "onClick" => Ok(Attribute::OnClick(value.to_string())),
+            "onDoubleClick" => Ok(Attribute::OnDoubleClick(value.to_string())),
+            "onMouseDown" => Ok(Attribute::OnMouseDown(value.to_string())),
+            "onMouseMove" => Ok(Attribute::OnMouseMove(value.to_string())),
+            "onMouseOut" => Ok(Attribute::OnMouseOut(value.to_string())),
+            "onMouseOver" => Ok(Attribute::OnMouseOver(value.to_string())),
+            "onMouseUp" => Ok(Attribute::OnMouseUp(value.to_string())),
+            "onWheel" => Ok(Attribute::OnWheel(value.to_string())),
+            "onDrag" => Ok(Attribute::OnDrag(value.to_string())),
+            "onDragEnd" => Ok(Attribute::OnDragEnd(value.to_string())),
+            "onDragEnter" => Ok(Attribute::OnDragEnter(value.to_string())),
+            "onDragLeave" => Ok(Attribute::OnDragLeave(value.to_string())),
+            "onDragOver" => Ok(Attribute::OnDragOver(value.to_string())),
+            "onDragStart" => Ok(Attribute::OnDragStart(value.to_string())),
+            "onDrop" => Ok(Attribute::OnDrop(value.to_string())),
+            "onScroll" => Ok(Attribute::OnScroll(value.to_string())),
+            "onCopy" => Ok(Attribute::OnCopy(value.to_string())),
+            "onCut" => Ok(Attribute::OnCut(value.to_string())),
+            "onPaste" => Ok(Attribute::OnPaste(value.to_string())),
+            "onAbort" => Ok(Attribute::OnAbort(value.to_string())),
+            "onCanPlay" => Ok(Attribute::OnCanPlay(value.to_string())),
+            "onCanPlayThrough" => Ok(Attribute::OnCanPlayThrough(value.to_string())),
+            "onCueChange" => Ok(Attribute::OnCueChange(value.to_string())),
+            "onDurationChange" => Ok(Attribute::OnDurationChange(value.to_string())),
+            "onEmptied" => Ok(Attribute::OnEmptied(value.to_string())),
+            "onEnded" => Ok(Attribute::OnEnded(value.to_string())),
+            "onLoadedData" => Ok(Attribute::OnLoadedData(value.to_string())),
+            "onLoadedMetadata" => Ok(Attribute::OnLoadedMetadata(value.to_string())),
+            "onLoadStart" => Ok(Attribute::OnLoadStart(value.to_string())),
+            "onPause" => Ok(Attribute::OnPause(value.to_string())),
+            "onPlay" => Ok(Attribute::OnPlay(value.to_string())),
+            "onPlaying" => Ok(Attribute::OnPlaying(value.to_string())),
+            "onProgress" => Ok(Attribute::OnProgress(value.to_string())),
+            "onRateChange" => Ok(Attribute::OnRateChange(value.to_string())),
+            "onSeeked" => Ok(Attribute::OnSeeked(value.to_string())),
+            "onSeeking" => Ok(Attribute::OnSeeking(value.to_string())),
+            "onStalled" => Ok(Attribute::OnStalled(value.to_string())),
+            "onSuspend" => Ok(Attribute::OnSuspend(value.to_string())),
+            "onTimeUpdate" => Ok(Attribute::OnTimeUpdate(value.to_string())),
+            "onVolumeChange" => Ok(Attribute::OnVolumeChange(value.to_string())),
+            "onWaiting" => Ok(Attribute::OnWaiting(value.to_string())),
+            "onToggle" => Ok(Attribute::OnToggle(value.to_string())),
+            "onbegin" => Ok(Attribute::OnBegin(value.to_string())),
+            "onend" => Ok(Attribute::OnEnd(value.to_string())),
+            "onrepeat" => Ok(Attribute::OnRepeat(value.to_string())),
             "keyPoints" => Ok(Attribute::KeyPoints(
                 value
                     .split(';')
@@ -600,11 +624,11 @@ impl TryFrom<(&String, &String)> for Attribute {
                     .map(|s| s.parse())
                     .collect::<Result<_, _>>()?,
             )),
-            "from" => Ok(Attribute::From(value.clone())),
-            "to" => Ok(Attribute::To(value.clone())),
-            "by" => Ok(Attribute::By(value.clone())),
+            "from" => Ok(Attribute::From(value.to_string())),
+            "to" => Ok(Attribute::To(value.to_string())),
+            "by" => Ok(Attribute::By(value.to_string())),
             "blur" => Ok(Attribute::FloodColor(value.parse()?)),
-            "interestFor" => Ok(Attribute::InterestFor(value.clone())),
+            "interestFor" => Ok(Attribute::InterestFor(value.to_string())),
             "ping" => Ok(Attribute::Ping(
                 value
                     .split_whitespace()
@@ -612,7 +636,7 @@ impl TryFrom<(&String, &String)> for Attribute {
                     .collect::<Result<_, _>>()?,
             )),
             "referrerPolicy" => Ok(Attribute::ReferrerPolicy(
-                ReferrerPolicy::try_from(value.as_str()).map_err(|_| ())?,
+                ReferrerPolicy::try_from(value).map_err(|_| ())?,
             )),
             "rel" => Ok(Attribute::Rel(
                 value
@@ -620,9 +644,7 @@ impl TryFrom<(&String, &String)> for Attribute {
                     .map(|s| s.parse::<RelType>().map_err(|_| ()))
                     .collect::<Result<_, _>>()?,
             )),
-            "target" => Ok(Attribute::Target(
-                Target::try_from(value.as_str()).map_err(|_| ())?,
-            )),
+            "target" => Ok(Attribute::Target(Target::try_from(value).map_err(|_| ())?)),
             "markerHeight" => Ok(Attribute::MarkerHeight(value.parse()?)),
             "markerUnits" => Ok(Attribute::MarkerUnits(value.parse()?)),
             "markerWidth" => Ok(Attribute::MarkerWidth(value.parse()?)),
@@ -634,8 +656,8 @@ impl TryFrom<(&String, &String)> for Attribute {
             "maskUnits" => Ok(Attribute::MaskUnits(value.parse()?)),
             "patternContentUnits" => Ok(Attribute::PatternContentUnits(value.parse()?)),
             "patternUnits" => Ok(Attribute::PatternUnits(value.parse()?)),
-            "patternTransform" => Ok(Attribute::PatternTransform(value.clone())),
-            "result" => Ok(Attribute::Result(value.clone())),
+            "patternTransform" => Ok(Attribute::PatternTransform(value.parse()?)),
+            "result" => Ok(Attribute::Result(value.to_string())),
             "in" => Ok(Attribute::In(value.parse().map_err(|_| ())?)),
             "in2" => Ok(Attribute::In2(value.parse().map_err(|_| ())?)),
             "mode" => Ok(Attribute::Mode(value.parse()?)),
@@ -739,7 +761,7 @@ impl TryFrom<(&String, &String)> for Attribute {
             "seed" => Ok(Attribute::Seed(value.parse().unwrap_or(0.0))),
             "stitchTiles" => Ok(Attribute::StitchTiles(value.parse()?)),
             "gradientUnits" => Ok(Attribute::GradientUnits(value.parse()?)),
-            "gradientTransform" => Ok(Attribute::GradientTransform(value.clone())),
+            "gradientTransform" => Ok(Attribute::GradientTransform(value.parse()?)),
             "spreadMethod" => Ok(Attribute::SpreadMethod(value.parse()?)),
             "fx" => Ok(Attribute::Fx(value.parse()?)),
             "fy" => Ok(Attribute::Fy(value.parse()?)),
@@ -756,11 +778,9 @@ impl TryFrom<(&String, &String)> for Attribute {
             "filterUnits" => Ok(Attribute::FilterUnits(value.parse()?)),
             "primitiveUnits" => Ok(Attribute::PrimitiveUnits(value.parse()?)),
             _ if key.starts_with("data-") => {
-                Ok(Attribute::Data(key.to_string(), value.clone()))
-            }
-            _ => {
-                return Err(());
+                Ok(Attribute::Data(key.to_string(), value.to_string()))
             }
+            _ => Ok(Attribute::Unknown(key.to_string(), value.to_string())),
         }
     }
 }
@@ -1033,6 +1053,10 @@ impl Attribute {
             Attribute::PrimitiveUnits(_) => "primitiveUnits",
             Attribute::Version(_) => "version",
             Attribute::Data(name, _) => name,
+            Attribute::XlinkHref(_) => "xlink:href",
+            Attribute::XmlSpace(_) => "xml:space",
+            Attribute::Foreign(name, _) => &name.name,
+            Attribute::Unknown(name, _) => name,
         }
     }
 
@@ -1046,6 +1070,10 @@ impl Attribute {
                 | Attribute::Style(_)
                 | Attribute::Lang(_)
                 | Attribute::Tabindex(_)
+                | Attribute::XlinkHref(_)
+                | Attribute::XmlSpace(_)
+                | Attribute::Foreign(..)
+                | Attribute::Unknown(..)
         )
     }
 
@@ -1216,6 +1244,7 @@ impl Attribute {
         _element: &crate::element::Element,
     ) -> bool {
         match element_type {
+            ElementType::Foreign(_) | ElementType::Unknown(_) => true,
             ElementType::Animate => self.is_global(),
             ElementType::AnimateMotion => {
                 self.is_global()
@@ -1728,102 +1757,102 @@ impl Attribute {
 
     fn write_value(&self, f: &mut impl fmt::Write) -> fmt::Result {
         match self {
-            Attribute::Xmlns(s) => write!(f, "=\"{}\"", s),
-            Attribute::Autofocus(v) => write!(f, "=\"{}\"", if *v { "1" } else { "0" }),
-            Attribute::Id(s) => write!(f, "=\"{}\"", s),
+            Attribute::Xmlns(s) => write!(f, "{}", s),
+            Attribute::Autofocus(v) => write!(f, "{}", if *v { "1" } else { "0" }),
+            Attribute::Id(s) => write!(f, "{}", s),
             Attribute::Class(items) => write_space_separated(f, items.iter()),
-            Attribute::Style(s) => write!(f, "=\"{}\"", s),
-            Attribute::Lang(s) => write!(f, "=\"{}\"", s),
-            Attribute::Tabindex(n) => write!(f, "=\"{}\"", n),
+            Attribute::Style(s) => write!(f, "{}", s),
+            Attribute::Lang(s) => write!(f, "{}", s),
+            Attribute::Tabindex(n) => write!(f, "{}", n),
             Attribute::RequiredExtensions(items) => write_space_separated(f, items.iter()),
             Attribute::SystemLanguage(v) => write_comma_separated(f, v.iter()),
-            Attribute::AlignmentBaseline(v) => write!(f, "=\"{}\"", v.as_str()),
-            Attribute::BaselineShift(v) => write!(f, "=\"{}\"", v),
-            Attribute::ClipPath(v) => write!(f, "=\"{}\"", v),
-            Attribute::ClipRule(v) => write!(f, "=\"{}\"", v.as_str()),
-            Attribute::Color(v) => write!(f, "=\"{}\"", v),
-            Attribute::ColorInterpolation(v) => write!(f, "=\"{}\"", v.as_str()),
-            Attribute::ColorInterpolationFilters(v) => write!(f, "=\"{}\"", v.as_str()),
-            Attribute::Cursor(v) => write!(f, "=\"{}\"", v),
-            Attribute::Cx(v) => write!(f, "=\"{}\"", v),
-            Attribute::Cy(v) => write!(f, "=\"{}\"", v),
+            Attribute::AlignmentBaseline(v) => write!(f, "{}", v.as_str()),
+            Attribute::BaselineShift(v) => write!(f, "{}", v),
+            Attribute::ClipPath(v) => write!(f, "{}", v),
+            Attribute::ClipRule(v) => write!(f, "{}", v.as_str()),
+            Attribute::Color(v) => write!(f, "{}", v),
+            Attribute::ColorInterpolation(v) => write!(f, "{}", v.as_str()),
+            Attribute::ColorInterpolationFilters(v) => write!(f, "{}", v.as_str()),
+            Attribute::Cursor(v) => write!(f, "{}", v),
+            Attribute::Cx(v) => write!(f, "{}", v),
+            Attribute::Cy(v) => write!(f, "{}", v),
             Attribute::D(paths) => write_space_separated(f, paths.0.iter()),
-            Attribute::Direction(v) => write!(f, "=\"{}\"", v),
-            Attribute::Display(v) => write!(f, "=\"{}\"", v),
-            Attribute::DominantBaseline(v) => write!(f, "=\"{}\"", v),
-            Attribute::Fill(v) => write!(f, "=\"{}\"", v),
-            Attribute::FillOpacity(v) => write!(f, "=\"{}\"", v),
-            Attribute::FillRule(v) => write!(f, "=\"{}\"", v),
-            Attribute::Filter(s) => write!(f, "=\"{}\"", s),
-            Attribute::FloodColor(v) => write!(f, "=\"{}\"", v),
-            Attribute::FloodOpacity(v) => write!(f, "=\"{}\"", v),
-            Attribute::FontFamily(v) => write!(f, "=\"{}\"", v),
-            Attribute::FontSize(v) => write!(f, "=\"{}\"", v),
-            Attribute::FontSizeAdjust(v) => write!(f, "=\"{}\"", v),
-            Attribute::FontStyle(v) => write!(f, "=\"{}\"", v),
-            Attribute::FontVariant(v) => write!(f, "=\"{}\"", v),
-            Attribute::FontWeight(v) => write!(f, "=\"{}\"", v),
-            Attribute::Height(v) => write!(f, "=\"{}\"", v),
-            Attribute::ImageRendering(v) => write!(f, "=\"{}\"", v),
-            Attribute::LetterSpacing(v) => write!(f, "=\"{}\"", v),
-            Attribute::LightingColor(v) => write!(f, "=\"{}\"", v.0),
-            Attribute::MarkerEnd(v) => write!(f, "=\"{}\"", v),
-            Attribute::MarkerMid(v) => write!(f, "=\"{}\"", v),
-            Attribute::MarkerStart(v) => write!(f, "=\"{}\"", v),
-            Attribute::Mask(v) => write!(f, "=\"{}\"", v),
-            Attribute::MaskType(v) => write!(f, "=\"{}\"", v),
-            Attribute::Opacity(v) => write!(f, "=\"{}\"", v),
-            Attribute::Overflow(v) => write!(f, "=\"{}\"", v),
-            Attribute::PointerEvents(v) => write!(f, "=\"{}\"", v),
-            Attribute::R(v) => write!(f, "=\"{}\"", v),
-            Attribute::Rx(v) => write!(f, "=\"{}\"", v),
-            Attribute::Ry(v) => write!(f, "=\"{}\"", v),
-            Attribute::ShapeRendering(v) => write!(f, "=\"{}\"", v),
-            Attribute::StopColor(v) => write!(f, "=\"{}\"", v),
-            Attribute::StopOpacity(v) => write!(f, "=\"{}\"", v),
-            Attribute::Stroke(v) => write!(f, "=\"{}\"", v),
+            Attribute::Direction(v) => write!(f, "{}", v),
+            Attribute::Display(v) => write!(f, "{}", v),
+            Attribute::DominantBaseline(v) => write!(f, "{}", v),
+            Attribute::Fill(v) => write!(f, "{}", v),
+            Attribute::FillOpacity(v) => write!(f, "{}", v),
+            Attribute::FillRule(v) => write!(f, "{}", v),
+            Attribute::Filter(s) => write!(f, "{}", s),
+            Attribute::FloodColor(v) => write!(f, "{}", v),
+            Attribute::FloodOpacity(v) => write!(f, "{}", v),
+            Attribute::FontFamily(v) => write!(f, "{}", v),
+            Attribute::FontSize(v) => write!(f, "{}", v),
+            Attribute::FontSizeAdjust(v) => write!(f, "{}", v),
+            Attribute::FontStyle(v) => write!(f, "{}", v),
+            Attribute::FontVariant(v) => write!(f, "{}", v),
+            Attribute::FontWeight(v) => write!(f, "{}", v),
+            Attribute::Height(v) => write!(f, "{}", v),
+            Attribute::ImageRendering(v) => write!(f, "{}", v),
+            Attribute::LetterSpacing(v) => write!(f, "{}", v),
+            Attribute::LightingColor(v) => write!(f, "{}", v.0),
+            Attribute::MarkerEnd(v) => write!(f, "{}", v),
+            Attribute::MarkerMid(v) => write!(f, "{}", v),
+            Attribute::MarkerStart(v) => write!(f, "{}", v),
+            Attribute::Mask(v) => write!(f, "{}", v),
+            Attribute::MaskType(v) => write!(f, "{}", v),
+            Attribute::Opacity(v) => write!(f, "{}", v),
+            Attribute::Overflow(v) => write!(f, "{}", v),
+            Attribute::PointerEvents(v) => write!(f, "{}", v),
+            Attribute::R(v) => write!(f, "{}", v),
+            Attribute::Rx(v) => write!(f, "{}", v),
+            Attribute::Ry(v) => write!(f, "{}", v),
+            Attribute::ShapeRendering(v) => write!(f, "{}", v),
+            Attribute::StopColor(v) => write!(f, "{}", v),
+            Attribute::StopOpacity(v) => write!(f, "{}", v),
+            Attribute::Stroke(v) => write!(f, "{}", v),
             Attribute::StrokeDasharray(items) => write_space_separated(f, items.iter()),
-            Attribute::StrokeDashoffset(v) => write!(f, "=\"{}\"", v),
-            Attribute::StrokeLinecap(v) => write!(f, "=\"{}\"", v),
-            Attribute::StrokeLinejoin(v) => write!(f, "=\"{}\"", v),
-            Attribute::StrokeMiterlimit(v) => write!(f, "=\"{}\"", v),
-            Attribute::StrokeOpacity(v) => write!(f, "=\"{}\"", v),
-            Attribute::StrokeWidth(v) => write!(f, "=\"{}\"", v),
-            Attribute::TextAnchor(v) => write!(f, "=\"{}\"", v),
-            Attribute::TextDecoration(v) => write!(f, "=\"{}\"", v),
-            Attribute::TextOverflow(v) => write!(f, "=\"{}\"", v),
-            Attribute::TextRendering(v) => write!(f, "=\"{}\"", v),
-            Attribute::Transform(v) => write!(f, "=\"{}\"", v),
-            Attribute::TransformOrigin(v) => write!(f, "=\"{}\"", v),
-            Attribute::UnicodeBidi(v) => write!(f, "=\"{}\"", v),
-            Attribute::VectorEffect(v) => write!(f, "=\"{}\"", v),
-            Attribute::Visibility(v) => write!(f, "=\"{}\"", v),
-            Attribute::Width(v) => write!(f, "=\"{}\"", v),
-            Attribute::WhiteSpace(v) => write!(f, "=\"{}\"", v),
-            Attribute::WordSpacing(v) => write!(f, "=\"{}\"", v),
-            Attribute::WritingMode(v) => write!(f, "=\"{}\"", v),
-            Attribute::X(v) => write!(f, "=\"{}\"", v),
-            Attribute::Y(v) => write!(f, "=\"{}\"", v),
-            Attribute::Type(v) => write!(f, "=\"{}\"", v),
+            Attribute::StrokeDashoffset(v) => write!(f, "{}", v),
+            Attribute::StrokeLinecap(v) => write!(f, "{}", v),
+            Attribute::StrokeLinejoin(v) => write!(f, "{}", v),
+            Attribute::StrokeMiterlimit(v) => write!(f, "{}", v),
+            Attribute::StrokeOpacity(v) => write!(f, "{}", v),
+            Attribute::StrokeWidth(v) => write!(f, "{}", v),
+            Attribute::TextAnchor(v) => write!(f, "{}", v),
+            Attribute::TextDecoration(v) => write!(f, "{}", v),
+            Attribute::TextOverflow(v) => write!(f, "{}", v),
+            Attribute::TextRendering(v) => write!(f, "{}", v),
+            Attribute::Transform(v) => write!(f, "{}", v),
+            Attribute::TransformOrigin(v) => write!(f, "{}", v),
+            Attribute::UnicodeBidi(v) => write!(f, "{}", v),
+            Attribute::VectorEffect(v) => write!(f, "{}", v),
+            Attribute::Visibility(v) => write!(f, "{}", v),
+            Attribute::Width(v) => write!(f, "{}", v),
+            Attribute::WhiteSpace(v) => write!(f, "{}", v),
+            Attribute::WordSpacing(v) => write!(f, "{}", v),
+            Attribute::WritingMode(v) => write!(f, "{}", v),
+            Attribute::X(v) => write!(f, "{}", v),
+            Attribute::Y(v) => write!(f, "{}", v),
+            Attribute::Type(v) => write!(f, "{}", v),
             Attribute::TableValues(v) => write_space_separated(f, v.iter()),
-            Attribute::Slope(v) => write!(f, "=\"{}\"", v),
-            Attribute::Intercept(v) => write!(f, "=\"{}\"", v),
-            Attribute::Amplitude(v) => write!(f, "=\"{}\"", v),
-            Attribute::Exponent(v) => write!(f, "=\"{}\"", v),
-            Attribute::Offset(v) => write!(f, "=\"{}\"", v),
-            Attribute::Href(v) => write!(f, "=\"{}\"", v),
-            Attribute::AttributeType(v) => write!(f, "=\"{}\"", v),
-            Attribute::AttributeName(v) => write!(f, "=\"{}\"", v),
+            Attribute::Slope(v) => write!(f, "{}", v),
+            Attribute::Intercept(v) => write!(f, "{}", v),
+            Attribute::Amplitude(v) => write!(f, "{}", v),
+            Attribute::Exponent(v) => write!(f, "{}", v),
+            Attribute::Offset(v) => write!(f, "{}", v),
+            Attribute::Href(v) => write!(f, "{}", v),
+            Attribute::AttributeType(v) => write!(f, "{}", v),
+            Attribute::AttributeName(v) => write!(f, "{}", v),
             Attribute::Begin(v) => write_semicolon_separated(f, v.iter()),
-            Attribute::Dur(v) => write!(f, "=\"{}\"", v),
+            Attribute::Dur(v) => write!(f, "{}", v),
             Attribute::End(v) => write_semicolon_separated(f, v.iter()),
-            Attribute::Min(v) => write!(f, "=\"{}\"", v),
-            Attribute::Max(v) => write!(f, "=\"{}\"", v),
-            Attribute::Restart(v) => write!(f, "=\"{}\"", v),
-            Attribute::RepeatCount(v) => write!(f, "=\"{}\"", v),
-            Attribute::RepeatDur(v) => write!(f, "=\"{}\"", v),
-            Attribute::Additive(v) => write!(f, "=\"{}\"", v),
-            Attribute::Accumulate(v) => write!(f, "=\"{}\"", v),
+            Attribute::Min(v) => write!(f, "{}", v),
+            Attribute::Max(v) => write!(f, "{}", v),
+            Attribute::Restart(v) => write!(f, "{}", v),
+            Attribute::RepeatCount(v) => write!(f, "{}", v),
+            Attribute::RepeatDur(v) => write!(f, "{}", v),
+            Attribute::Additive(v) => write!(f, "{}", v),
+            Attribute::Accumulate(v) => write!(f, "{}", v),
             Attribute::OnAfterPrint(v)
             | Attribute::OnBeforePrint(v)
             | Attribute::OnBeforeUnload(v)
@@ -1896,112 +1925,268 @@ impl Attribute {
             | Attribute::OnToggle(v)
             | Attribute::OnBegin(v)
             | Attribute::OnEnd(v)
-            | Attribute::OnRepeat(v) => write!(f, "=\"{}\"", v),
+            | Attribute::OnRepeat(v) => write!(f, "{}", v),
             Attribute::KeyPoints(v) => write_semicolon_separated(f, v.iter()),
             Attribute::Path(paths) => write_space_separated(f, paths.0.iter()),
-            Attribute::Rotate(v) => write!(f, "=\"{}\"", v),
-            Attribute::CalcMode(v) => write!(f, "=\"{}\"", v),
+            Attribute::Rotate(v) => write!(f, "{}", v),
+            Attribute::CalcMode(v) => write!(f, "{}", v),
             Attribute::Values(v) => write_semicolon_separated(f, v.iter()),
             Attribute::KeyTimes(v) => write_semicolon_separated(f, v.iter()),
             Attribute::KeySplines(v) => write_semicolon_separated(f, v.iter()),
-            Attribute::From(v) => write!(f, "=\"{}\"", v),
-            Attribute::To(v) => write!(f, "=\"{}\"", v),
-            Attribute::By(v) => write!(f, "=\"{}\"", v),
-            Attribute::PathLength(v) => write!(f, "=\"{}\"", v),
-            Attribute::X1(v) => write!(f, "=\"{}\"", v),
-            Attribute::Y1(v) => write!(f, "=\"{}\"", v),
-            Attribute::X2(v) => write!(f, "=\"{}\"", v),
-            Attribute::Y2(v) => write!(f, "=\"{}\"", v),
+            Attribute::From(v) => write!(f, "{}", v),
+            Attribute::To(v) => write!(f, "{}", v),
+            Attribute::By(v) => write!(f, "{}", v),
+            Attribute::PathLength(v) => write!(f, "{}", v),
+            Attribute::X1(v) => write!(f, "{}", v),
+            Attribute::Y1(v) => write!(f, "{}", v),
+            Attribute::X2(v) => write!(f, "{}", v),
+            Attribute::Y2(v) => write!(f, "{}", v),
             Attribute::Points(points) => write_space_separated(f, points.iter()),
-            Attribute::Download(v) => write!(f, "=\"{}\"", v),
-            Attribute::HrefLang(v) => write!(f, "=\"{}\"", v),
-            Attribute::InterestFor(v) => write!(f, "=\"{}\"", v),
+            Attribute::Download(v) => write!(f, "{}", v),
+            Attribute::HrefLang(v) => write!(f, "{}", v),
+            Attribute::InterestFor(v) => write!(f, "{}", v),
             Attribute::Ping(urls) => write_space_separated(f, urls.iter()),
-            Attribute::ReferrerPolicy(v) => write!(f, "=\"{}\"", v),
+            Attribute::ReferrerPolicy(v) => write!(f, "{}", v),
             Attribute::Rel(items) => write_space_separated(f, items.iter()),
-            Attribute::Target(v) => write!(f, "=\"{}\"", v),
-            Attribute::MarkerHeight(v) => write!(f, "=\"{}\"", v),
-            Attribute::MarkerUnits(v) => write!(f, "=\"{}\"", v),
-            Attribute::MarkerWidth(v) => write!(f, "=\"{}\"", v),
-            Attribute::Orient(v) => write!(f, "=\"{}\"", v),
-            Attribute::PreserveAspectRatio(v) => write!(f, "=\"{}\"", v),
-            Attribute::RefX(v) => write!(f, "=\"{}\"", v),
-            Attribute::RefY(v) => write!(f, "=\"{}\"", v),
+            Attribute::Target(v) => write!(f, "{}", v),
+            Attribute::MarkerHeight(v) => write!(f, "{}", v),
+            Attribute::MarkerUnits(v) => write!(f, "{}", v),
+            Attribute::MarkerWidth(v) => write!(f, "{}", v),
+            Attribute::Orient(v) => write!(f, "{}", v),
+            Attribute::PreserveAspectRatio(v) => write!(f, "{}", v),
+            Attribute::RefX(v) => write!(f, "{}", v),
+            Attribute::RefY(v) => write!(f, "{}", v),
             Attribute::ViewBox(view_box) => write_space_separated(f, view_box.0.iter()),
-            Attribute::MaskContentUnits(v) => write!(f, "=\"{}\"", v),
-            Attribute::MaskUnits(v) => write!(f, "=\"{}\"", v),
-            Attribute::PatternContentUnits(v) => write!(f, "=\"{}\"", v),
-            Attribute::PatternUnits(v) => write!(f, "=\"{}\"", v),
-            Attribute::PatternTransform(v) => write!(f, "=\"{}\"", v),
-            Attribute::Result(v) => write!(f, "=\"{}\"", v),
-            Attribute::In(v) => write!(f, "=\"{}\"", v),
-            Attribute::In2(v) => write!(f, "=\"{}\"", v),
-            Attribute::Mode(v) => write!(f, "=\"{}\"", v),
-            Attribute::Operator(v) => write!(f, "=\"{}\"", v),
-            Attribute::K1(v) => write!(f, "=\"{}\"", v),
-            Attribute::K2(v) => write!(f, "=\"{}\"", v),
-            Attribute::K3(v) => write!(f, "=\"{}\"", v),
-            Attribute::K4(v) => write!(f, "=\"{}\"", v),
-            Attribute::Order(v) => write!(f, "=\"{}\"", v),
+            Attribute::MaskContentUnits(v) => write!(f, "{}", v),
+            Attribute::MaskUnits(v) => write!(f, "{}", v),
+            Attribute::PatternContentUnits(v) => write!(f, "{}", v),
+            Attribute::PatternUnits(v) => write!(f, "{}", v),
+            Attribute::PatternTransform(v) => write!(f, "{}", v),
+            Attribute::Result(v) => write!(f, "{}", v),
+            Attribute::In(v) => write!(f, "{}", v),
+            Attribute::In2(v) => write!(f, "{}", v),
+            Attribute::Mode(v) => write!(f, "{}", v),
+            Attribute::Operator(v) => write!(f, "{}", v),
+            Attribute::K1(v) => write!(f, "{}", v),
+            Attribute::K2(v) => write!(f, "{}", v),
+            Attribute::K3(v) => write!(f, "{}", v),
+            Attribute::K4(v) => write!(f, "{}", v),
+            Attribute::Order(v) => write!(f, "{}", v),
             Attribute::KernelMatrix(items) => write_space_separated(f, items.iter()),
-            Attribute::Divisor(v) => write!(f, "=\"{}\"", v),
-            Attribute::Bias(v) => write!(f, "=\"{}\"", v),
-            Attribute::TargetX(v) => write!(f, "=\"{}\"", v),
-            Attribute::TargetY(v) => write!(f, "=\"{}\"", v),
-            Attribute::EdgeMode(v) => write!(f, "=\"{}\"", v),
-            Attribute::KernelUnitLength(a, None) => write!(f, "=\"{}\"", a),
-            Attribute::KernelUnitLength(a, Some(b)) => write!(f, "=\"{} {}\"", a, b),
-            Attribute::PreserveAlpha(v) => write!(f, "=\"{}\"", if *v { "1" } else { "0" }),
-            Attribute::SurfaceScale(v) => write!(f, "=\"{}\"", v),
-            Attribute::DiffuseConstant(v) => write!(f, "=\"{}\"", v),
-            Attribute::Scale(v) => write!(f, "=\"{}\"", v),
-            Attribute::XChannelSelector(v) => write!(f, "=\"{}\"", v),
-            Attribute::YChannelSelector(v) => write!(f, "=\"{}\"", v),
-            Attribute::Dx(v) => write!(f, "=\"{}\"", v),
-            Attribute::Dy(v) => write!(f, "=\"{}\"", v),
-            Attribute::StdDeviation(a, None) => write!(f, "=\"{}\"", a),
-            Attribute::StdDeviation(a, Some(b)) => write!(f, "=\"{} {}\"", a, b),
-            Attribute::CrossOrigin(v) => write!(f, "=\"{}\"", v),
-            Attribute::Radius(a, None) => write!(f, "=\"{}\"", a),
-            Attribute::Radius(a, Some(b)) => write!(f, "=\"{} {}\"", a, b),
-            Attribute::SpecularConstant(v) => write!(f, "=\"{}\"", v),
-            Attribute::SpecularExponent(v) => write!(f, "=\"{}\"", v),
-            Attribute::Azimuth(v) => write!(f, "=\"{}\"", v),
-            Attribute::Elevation(v) => write!(f, "=\"{}\"", v),
-            Attribute::Z(v) => write!(f, "=\"{}\"", v),
-            Attribute::PointsAtX(v) => write!(f, "=\"{}\"", v),
-            Attribute::PointsAtY(v) => write!(f, "=\"{}\"", v),
-            Attribute::PointsAtZ(v) => write!(f, "=\"{}\"", v),
-            Attribute::LimitingConeAngle(v) => write!(f, "=\"{}\"", v),
-            Attribute::BaseFrequency(a, None) => write!(f, "=\"{}\"", a),
-            Attribute::BaseFrequency(a, Some(b)) => write!(f, "=\"{} {}\"", a, b),
-            Attribute::NumOctaves(v) => write!(f, "=\"{}\"", v),
-            Attribute::Seed(v) => write!(f, "=\"{}\"", v),
-            Attribute::StitchTiles(v) => write!(f, "=\"{}\"", v),
-            Attribute::GradientUnits(v) => write!(f, "=\"{}\"", v),
-            Attribute::GradientTransform(v) => write!(f, "=\"{}\"", v),
-            Attribute::SpreadMethod(v) => write!(f, "=\"{}\"", v),
-            Attribute::Fx(v) => write!(f, "=\"{}\"", v),
-            Attribute::Fy(v) => write!(f, "=\"{}\"", v),
-            Attribute::Fr(v) => write!(f, "=\"{}\"", v),
-            Attribute::Decoding(v) => write!(f, "=\"{}\"", v),
-            Attribute::FetchPriority(v) => write!(f, "=\"{}\"", v),
-            Attribute::LengthAdjust(v) => write!(f, "=\"{}\"", v),
-            Attribute::TextLength(v) => write!(f, "=\"{}\"", v),
-            Attribute::ClipPathUnits(v) => write!(f, "=\"{}\"", v),
-            Attribute::Method(v) => write!(f, "=\"{}\"", v),
-            Attribute::Side(v) => write!(f, "=\"{}\"", v),
-            Attribute::Spacing(v) => write!(f, "=\"{}\"", v),
-            Attribute::StartOffset(v) => write!(f, "=\"{}\"", v),
-            Attribute::FilterUnits(v) => write!(f, "=\"{}\"", v),
-            Attribute::PrimitiveUnits(v) => write!(f, "=\"{}\"", v),
-            Attribute::Version(v) => write!(f, "=\"{}\"", v),
-            Attribute::Data(_, value) => write!(f, "=\"{}\"", value),
+            Attribute::Divisor(v) => write!(f, "{}", v),
+            Attribute::Bias(v) => write!(f, "{}", v),
+            Attribute::TargetX(v) => write!(f, "{}", v),
+            Attribute::TargetY(v) => write!(f, "{}", v),
+            Attribute::EdgeMode(v) => write!(f, "{}", v),
+            Attribute::KernelUnitLength(a, None) => write!(f, "{}", a),
+            Attribute::KernelUnitLength(a, Some(b)) => write!(f, "{} {}", a, b),
+            Attribute::PreserveAlpha(v) => write!(f, "{}", if *v { "1" } else { "0" }),
+            Attribute::SurfaceScale(v) => write!(f, "{}", v),
+            Attribute::DiffuseConstant(v) => write!(f, "{}", v),
+            Attribute::Scale(v) => write!(f, "{}", v),
+            Attribute::XChannelSelector(v) => write!(f, "{}", v),
+            Attribute::YChannelSelector(v) => write!(f, "{}", v),
+            Attribute::Dx(v) => write!(f, "{}", v),
+            Attribute::Dy(v) => write!(f, "{}", v),
+            Attribute::StdDeviation(a, None) => write!(f, "{}", a),
+            Attribute::StdDeviation(a, Some(b)) => write!(f, "{} {}", a, b),
+            Attribute::CrossOrigin(v) => write!(f, "{}", v),
+            Attribute::Radius(a, None) => write!(f, "{}", a),
+            Attribute::Radius(a, Some(b)) => write!(f, "{} {}", a, b),
+            Attribute::SpecularConstant(v) => write!(f, "{}", v),
+            Attribute::SpecularExponent(v) => write!(f, "{}", v),
+            Attribute::Azimuth(v) => write!(f, "{}", v),
+            Attribute::Elevation(v) => write!(f, "{}", v),
+            Attribute::Z(v) => write!(f, "{}", v),
+            Attribute::PointsAtX(v) => write!(f, "{}", v),
+            Attribute::PointsAtY(v) => write!(f, "{}", v),
+            Attribute::PointsAtZ(v) => write!(f, "{}", v),
+            Attribute::LimitingConeAngle(v) => write!(f, "{}", v),
+            Attribute::BaseFrequency(a, None) => write!(f, "{}", a),
+            Attribute::BaseFrequency(a, Some(b)) => write!(f, "{} {}", a, b),
+            Attribute::NumOctaves(v) => write!(f, "{}", v),
+            Attribute::Seed(v) => write!(f, "{}", v),
+            Attribute::StitchTiles(v) => write!(f, "{}", v),
+            Attribute::GradientUnits(v) => write!(f, "{}", v),
+            Attribute::GradientTransform(v) => write!(f, "{}", v),
+            Attribute::SpreadMethod(v) => write!(f, "{}", v),
+            Attribute::Fx(v) => write!(f, "{}", v),
+            Attribute::Fy(v) => write!(f, "{}", v),
+            Attribute::Fr(v) => write!(f, "{}", v),
+            Attribute::Decoding(v) => write!(f, "{}", v),
+            Attribute::FetchPriority(v) => write!(f, "{}", v),
+            Attribute::LengthAdjust(v) => write!(f, "{}", v),
+            Attribute::TextLength(v) => write!(f, "{}", v),
+            Attribute::ClipPathUnits(v) => write!(f, "{}", v),
+            Attribute::Method(v) => write!(f, "{}", v),
+            Attribute::Side(v) => write!(f, "{}", v),
+            Attribute::Spacing(v) => write!(f, "{}", v),
+            Attribute::StartOffset(v) => write!(f, "{}", v),
+            Attribute::FilterUnits(v) => write!(f, "{}", v),
+            Attribute::PrimitiveUnits(v) => write!(f, "{}", v),
+            Attribute::Version(v) => write!(f, "{}", v),
+            Attribute::Data(_, value) => write!(f, "{}", value),
+            Attribute::XlinkHref(v) => write!(f, "{}", v),
+            Attribute::XmlSpace(v) => write!(f, "{}", v),
+            Attribute::Foreign(_, value) => write!(f, "{}", value),
+            Attribute::Unknown(_, value) => write!(f, "{}", value),
         }
     }
 
+    /// Returns `true` for attributes whose value is made of numbers, such as
+    /// coordinates, lengths, opacities, transforms and path data.
+    pub fn has_numeric_value(&self) -> bool {
+        matches!(
+            self,
+            Attribute::Cx(_)
+                | Attribute::Cy(_)
+                | Attribute::D(_)
+                | Attribute::FillOpacity(_)
+                | Attribute::FloodOpacity(_)
+                | Attribute::FontSize(_)
+                | Attribute::Height(_)
+                | Attribute::LetterSpacing(_)
+                | Attribute::Opacity(_)
+                | Attribute::R(_)
+                | Attribute::Rx(_)
+                | Attribute::Ry(_)
+                | Attribute::StopOpacity(_)
+                | Attribute::StrokeDashoffset(_)
+                | Attribute::StrokeMiterlimit(_)
+                | Attribute::StrokeOpacity(_)
+                | Attribute::StrokeWidth(_)
+                | Attribute::Transform(_)
+                | Attribute::TransformOrigin(_)
+                | Attribute::Width(_)
+                | Attribute::WordSpacing(_)
+                | Attribute::X(_)
+                | Attribute::Y(_)
+                | Attribute::TableValues(_)
+                | Attribute::Slope(_)
+                | Attribute::Intercept(_)
+                | Attribute::Amplitude(_)
+                | Attribute::Exponent(_)
+                | Attribute::Offset(_)
+                | Attribute::KeyPoints(_)
+                | Attribute::Path(_)
+                | Attribute::KeyTimes(_)
+                | Attribute::KeySplines(_)
+                | Attribute::PathLength(_)
+                | Attribute::X1(_)
+                | Attribute::Y1(_)
+                | Attribute::X2(_)
+                | Attribute::Y2(_)
+                | Attribute::Points(_)
+                | Attribute::MarkerHeight(_)
+                | Attribute::MarkerWidth(_)
+                | Attribute::RefX(_)
+                | Attribute::RefY(_)
+                | Attribute::ViewBox(_)
+                | Attribute::PatternTransform(_)
+                | Attribute::K1(_)
+                | Attribute::K2(_)
+                | Attribute::K3(_)
+                | Attribute::K4(_)
+                | Attribute::KernelMatrix(_)
+                | Attribute::Divisor(_)
+                | Attribute::Bias(_)
+                | Attribute::KernelUnitLength(..)
+                | Attribute::SurfaceScale(_)
+                | Attribute::DiffuseConstant(_)
+                | Attribute::Scale(_)
+                | Attribute::Dx(_)
+                | Attribute::Dy(_)
+                | Attribute::StdDeviation(..)
+                | Attribute::Radius(..)
+                | Attribute::SpecularConstant(_)
+                | Attribute::SpecularExponent(_)
+                | Attribute::Azimuth(_)
+                | Attribute::Elevation(_)
+                | Attribute::Z(_)
+                | Attribute::PointsAtX(_)
+                | Attribute::PointsAtY(_)
+                | Attribute::PointsAtZ(_)
+                | Attribute::LimitingConeAngle(_)
+                | Attribute::BaseFrequency(..)
+                | Attribute::Seed(_)
+                | Attribute::GradientTransform(_)
+                | Attribute::Fx(_)
+                | Attribute::Fy(_)
+                | Attribute::Fr(_)
+                | Attribute::TextLength(_)
+        )
+    }
+
+    /// Writes the attribute as `name="value"`.
     pub fn write_svg(&self, f: &mut impl fmt::Write) -> fmt::Result {
-        write!(f, "{}", self.name())?;
-        self.write_value(f)
+        self.write_svg_quoted(f, Quote::Double)
+    }
+
+    /// Writes the attribute with its value delimited by `quote`.
+    ///
+    /// Escapes `&`, `<` and occurrences of `quote` in the value.
+    pub fn write_svg_quoted(&self, f: &mut impl fmt::Write, quote: Quote) -> fmt::Result {
+        self.write_svg_rounded(f, quote, None)
+    }
+
+    /// Writes the attribute like [`write_svg_quoted`](Attribute::write_svg_quoted),
+    /// rounding the numbers of a numeric value to `precision` decimal places.
+    pub(crate) fn write_svg_rounded(
+        &self,
+        f: &mut impl fmt::Write,
+        quote: Quote,
+        precision: Option<usize>,
+    ) -> fmt::Result {
+        let mut value = String::new();
+        self.write_value(&mut value)?;
+        if let Some(precision) = precision.filter(|_| self.has_numeric_value()) {
+            value = round_numbers(&value, precision);
+        }
+
+        let quote = quote.as_char();
+        write!(
+            f,
+            "{}={}{}{}",
+            self.name(),
+            quote,
+            escape_attribute(&value, quote),
+            quote
+        )
+    }
+}
+
+/// The character delimiting an attribute value.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Quote {
+    /// `"value"`
+    #[default]
+    Double,
+    /// `'value'`
+    Single,
+}
+
+impl Quote {
+    /// Returns the quote for the given delimiter byte.
+    pub const fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            b'"' => Some(Quote::Double),
+            b'\'' => Some(Quote::Single),
+            _ => None,
+        }
+    }
+
+    pub const fn as_char(self) -> char {
+        match self {
+            Quote::Double => '"',
+            Quote::Single => '\'',
+        }
+    }
+
+    /// Returns the other quote character.
+    pub const fn other(self) -> Self {
+        match self {
+            Quote::Double => Quote::Single,
+            Quote::Single => Quote::Double,
+        }
     }
 }
@@ -1,7 +1,7 @@
 use std::fmt;
 use std::str::FromStr;
 
-use crate::element::types::{AbsoluteLength, Length, LengthOrPercentage};
+use crate::svg::types::LengthOrPercentage;
 
 #[derive(Clone, Debug, PartialEq, Eq, Default)]
 pub enum In {
@@ -452,9 +452,7 @@ impl fmt::Display for RefX {
 
 impl Default for RefX {
     fn default() -> Self {
-        Self::Coordinate(LengthOrPercentage::Length(Length::Absolute(
-            AbsoluteLength::Px(0.0),
-        )))
+        Self::Coordinate(LengthOrPercentage::default())
     }
 }
 
@@ -492,9 +490,7 @@ impl fmt::Display for RefY {
 
 impl Default for RefY {
     fn default() -> Self {
-        Self::Coordinate(LengthOrPercentage::Length(Length::Absolute(
-            AbsoluteLength::Px(0.0),
-        )))
+        Self::Coordinate(LengthOrPercentage::default())
     }
 }
 
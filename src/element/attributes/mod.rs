@@ -7,12 +7,14 @@ mod gradient;
 mod link_media;
 mod path;
 mod presentation;
+mod xml;
 
 // Re-export everything that was previously public from attributes.rs
 pub use animation::*;
-pub use attribute::Attribute;
+pub use attribute::{Attribute, Quote};
 pub use filter::*;
 pub use gradient::*;
 pub use link_media::*;
 pub use path::*;
 pub use presentation::*;
+pub use xml::*;
@@ -0,0 +1,388 @@
+//! Writing path data in as few characters as possible.
+
+use crate::svg::types::basic::format_number;
+
+use super::{Path, Segment};
+
+/// An argument of a path command.
+#[derive(Clone, Copy)]
+enum Arg {
+    X(f64),
+    Y(f64),
+    /// A number that is the same in absolute and relative commands, such as
+    /// a radius.
+    Number(f64),
+    Flag(bool),
+}
+
+/// Writes path data one segment at a time, keeping track of the points a
+/// reader of the output computes, so that rounding errors do not add up.
+struct Encoder {
+    output: String,
+    precision: Option<usize>,
+    /// The letter of the last command written.
+    command: Option<char>,
+    current: (f64, f64),
+    start: (f64, f64),
+    /// The last control point of the last segment, if it was a cubic Bézier.
+    cubic_control: Option<(f64, f64)>,
+    /// The control point of the last segment, if it was a quadratic Bézier.
+    quadratic_control: Option<(f64, f64)>,
+}
+
+impl Encoder {
+    fn round(&self, value: f64) -> f64 {
+        match self.precision {
+            Some(precision) => {
+                let scale = 10f64.powi(precision as i32);
+                (value * scale).round() / scale
+            }
+            None => value,
+        }
+    }
+
+    /// Returns whether `a` and `b` are written as the same number.
+    fn same(&self, a: (f64, f64), b: (f64, f64)) -> bool {
+        let epsilon = match self.precision {
+            Some(precision) => 0.5 * 10f64.powi(-(precision as i32)),
+            None => 1e-12,
+        };
+        (a.0 - b.0).abs() < epsilon && (a.1 - b.1).abs() < epsilon
+    }
+
+    /// Returns the text that writes the command `letter` with `args` after
+    /// the output so far, and the absolute values a reader computes from it.
+    fn command(&self, letter: char, args: &[Arg]) -> (String, Vec<f64>) {
+        let relative = letter.is_ascii_lowercase();
+        let mut text = String::new();
+        let mut values = Vec::with_capacity(args.len());
+
+        // a command repeats without its letter, and a moveto continues as a lineto
+        let implicit = match self.command {
+            Some('M') => Some('L'),
+            Some('m') => Some('l'),
+            command => command,
+        };
+        if implicit != Some(letter) {
+            text.push(letter);
+        }
+
+        let mut previous: Option<String> = match text.is_empty() {
+            true => last_number(&self.output).map(str::to_string),
+            false => None,
+        };
+        let mut after_flag = false;
+
+        for &arg in args {
+            let number = match arg {
+                Arg::Flag(flag) => {
+                    if previous.is_some() && !after_flag {
+                        text.push(' ');
+                    }
+                    text.push(if flag { '1' } else { '0' });
+                    values.push(flag as u8 as f64);
+                    previous = None;
+                    after_flag = true;
+                    continue;
+                }
+                Arg::X(x) if relative => {
+                    let number = self.round(x - self.current.0);
+                    values.push(self.current.0 + number);
+                    number
+                }
+                Arg::Y(y) if relative => {
+                    let number = self.round(y - self.current.1);
+                    values.push(self.current.1 + number);
+                    number
+                }
+                Arg::X(value) | Arg::Y(value) | Arg::Number(value) => {
+                    let number = self.round(value);
+                    values.push(number);
+                    number
+                }
+            };
+
+            let number = format_number(number);
+            if let Some(previous) = &previous
+                && needs_separator(previous, &number)
+            {
+                text.push(' ');
+            }
+            text.push_str(&number);
+            previous = Some(number);
+            after_flag = false;
+        }
+
+        (text, values)
+    }
+
+    /// Writes the shorter of the absolute and relative forms of a command,
+    /// and returns the absolute values of its arguments.
+    fn write(&mut self, letter: char, args: &[Arg]) -> Vec<f64> {
+        let absolute = self.command(letter.to_ascii_uppercase(), args);
+        let relative = self.command(letter.to_ascii_lowercase(), args);
+        // the first command is absolute unless relative is shorter, and any
+        // other one relative unless absolute is
+        let prefer_relative = match self.command {
+            Some(_) => relative.0.len() <= absolute.0.len(),
+            None => relative.0.len() < absolute.0.len(),
+        };
+        let ((text, values), letter) = match prefer_relative {
+            true => (relative, letter.to_ascii_lowercase()),
+            false => (absolute, letter.to_ascii_uppercase()),
+        };
+
+        self.output.push_str(&text);
+        self.command = Some(letter);
+        self.cubic_control = None;
+        self.quadratic_control = None;
+        values
+    }
+
+    fn segment(&mut self, segment: &Segment) {
+        match *segment {
+            Segment::MoveTo { to } => {
+                let values = self.write('M', &[Arg::X(to.0), Arg::Y(to.1)]);
+                self.current = (values[0], values[1]);
+                self.start = self.current;
+            }
+            Segment::LineTo { to, .. } => {
+                let values = if self.same((to.0, self.current.1), to) {
+                    let values = self.write('H', &[Arg::X(to.0)]);
+                    vec![values[0], self.current.1]
+                } else if self.same((self.current.0, to.1), to) {
+                    let values = self.write('V', &[Arg::Y(to.1)]);
+                    vec![self.current.0, values[0]]
+                } else {
+                    self.write('L', &[Arg::X(to.0), Arg::Y(to.1)])
+                };
+                self.current = (values[0], values[1]);
+            }
+            Segment::CubicTo {
+                control1,
+                control2,
+                to,
+                ..
+            } => {
+                let reflected = match self.cubic_control {
+                    Some(control) => reflect(control, self.current),
+                    None => self.current,
+                };
+                let values = match self.same(control1, reflected) {
+                    true => self.write(
+                        'S',
+                        &[
+                            Arg::X(control2.0),
+                            Arg::Y(control2.1),
+                            Arg::X(to.0),
+                            Arg::Y(to.1),
+                        ],
+                    ),
+                    false => self.write(
+                        'C',
+                        &[
+                            Arg::X(control1.0),
+                            Arg::Y(control1.1),
+                            Arg::X(control2.0),
+                            Arg::Y(control2.1),
+                            Arg::X(to.0),
+                            Arg::Y(to.1),
+                        ],
+                    ),
+                };
+
+                let [.., x2, y2, x, y] = values[..] else {
+                    unreachable!("a cubic Bézier has at least four arguments");
+                };
+                self.current = (x, y);
+                self.cubic_control = Some((x2, y2));
+            }
+            Segment::QuadraticTo { control, to, .. } => {
+                let reflected = match self.quadratic_control {
+                    Some(control) => reflect(control, self.current),
+                    None => self.current,
+                };
+                let control = match self.same(control, reflected) {
+                    true => {
+                        let values = self.write('T', &[Arg::X(to.0), Arg::Y(to.1)]);
+                        self.current = (values[0], values[1]);
+                        reflected
+                    }
+                    false => {
+                        let values = self.write(
+                            'Q',
+                            &[
+                                Arg::X(control.0),
+                                Arg::Y(control.1),
+                                Arg::X(to.0),
+                                Arg::Y(to.1),
+                            ],
+                        );
+                        self.current = (values[2], values[3]);
+                        (values[0], values[1])
+                    }
+                };
+                self.quadratic_control = Some(control);
+            }
+            Segment::ArcTo {
+                rx,
+                ry,
+                angle,
+                large_arc,
+                sweep,
+                to,
+                ..
+            } => {
+                let values = self.write(
+                    'A',
+                    &[
+                        Arg::Number(rx),
+                        Arg::Number(ry),
+                        Arg::Number(angle),
+                        Arg::Flag(large_arc),
+                        Arg::Flag(sweep),
+                        Arg::X(to.0),
+                        Arg::Y(to.1),
+                    ],
+                );
+                self.current = (values[5], values[6]);
+            }
+            Segment::ClosePath { .. } => {
+                self.output.push('z');
+                self.command = Some('z');
+                self.cubic_control = None;
+                self.quadratic_control = None;
+                self.current = self.start;
+            }
+        }
+    }
+}
+
+fn reflect(control: (f64, f64), center: (f64, f64)) -> (f64, f64) {
+    (2.0 * center.0 - control.0, 2.0 * center.1 - control.1)
+}
+
+/// Returns the number at the end of `output`, if it ends with one.
+fn last_number(output: &str) -> Option<&str> {
+    let mut start = output
+        .trim_end_matches(|c: char| c.is_ascii_digit() || c == '.')
+        .len();
+    if output[..start].ends_with('-') {
+        start -= 1;
+    }
+    Some(&output[start..]).filter(|number| !number.is_empty())
+}
+
+/// Returns whether `next` has to be separated from the `previous` number.
+fn needs_separator(previous: &str, next: &str) -> bool {
+    match next.as_bytes()[0] {
+        b'-' => false,
+        // a second decimal point starts a new number
+        b'.' => !previous.contains('.'),
+        _ => true,
+    }
+}
+
+impl Path {
+    /// Writes the path data in as few characters as possible, with numbers
+    /// rounded to `precision` decimal places, or as they are for `None`.
+    ///
+    /// Each segment is written absolute or relative, whichever is shorter,
+    /// with the `H`, `V`, `S` and `T` shorthands where they apply, and without
+    /// repeated command letters and separators that are not needed.
+    pub fn encode(&self, precision: Option<usize>) -> String {
+        let mut encoder = Encoder {
+            output: String::new(),
+            precision,
+            command: None,
+            current: (0.0, 0.0),
+            start: (0.0, 0.0),
+            cubic_control: None,
+            quadratic_control: None,
+        };
+
+        let segments = self.segments();
+        for (i, segment) in segments.iter().enumerate() {
+            // a line back to the start of the subpath is drawn by closing it
+            if let Segment::LineTo { to, .. } = segment
+                && let Some(Segment::ClosePath { .. }) = segments.get(i + 1)
+                && encoder.same(encoder.start, *to)
+            {
+                continue;
+            }
+
+            encoder.segment(segment);
+        }
+
+        encoder.output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(data: &str, precision: Option<usize>) -> String {
+        data.parse::<Path>().unwrap().encode(precision)
+    }
+
+    #[test]
+    fn encode_should_write_the_shortest_commands() {
+        assert_eq!(
+            encode(
+                "M 30,1 L 70,1 L 99,30 L 99,70 L 70,99 L 30,99 L 1,70 L 1,30 Z",
+                None
+            ),
+            "M30 1h40l29 29v40L70 99H30L1 70V30z"
+        );
+        assert_eq!(encode("M10 10 L20 20 L30 30", None), "M10 10l10 10 10 10");
+        assert_eq!(encode("M0 0 L0.5 0.5 L-1 -1.5", None), "M0 0l.5.5-1.5-2");
+        assert_eq!(encode("M0 0 L1.5 0 L1.5 -2 L2 -2", None), "M0 0h1.5v-2H2");
+        assert_eq!(encode("M0 0 L1.5 -2 L2 -1.5", None), "M0 0l1.5-2 .5.5");
+        assert_eq!(
+            encode("M10 10 L20 20 30 10 10 10 Z", None),
+            "M10 10l10 10 10-10z"
+        );
+        assert_eq!(encode("", None), "");
+    }
+
+    #[test]
+    fn encode_should_use_smooth_curves() {
+        assert_eq!(
+            encode("M0 0 C0 10 10 10 10 0 C10 -10 20 -10 20 0", None),
+            "M0 0c0 10 10 10 10 0s10-10 10 0"
+        );
+        assert_eq!(
+            encode("M0 0 Q5 5 10 0 Q15 -5 20 0 Q0 0 30 0", None),
+            "M0 0q5 5 10 0t10 0Q0 0 30 0"
+        );
+        assert_eq!(encode("M0 0 C0 0 5 5 10 0", None), "M0 0s5 5 10 0");
+    }
+
+    #[test]
+    fn encode_should_pack_arc_flags() {
+        assert_eq!(
+            encode("M0 0 A10 10 0 0 1 20 0 A10 10 0 1 0 40 0", None),
+            "M0 0a10 10 0 0120 0 10 10 0 1020 0"
+        );
+    }
+
+    #[test]
+    fn encode_should_round_without_drifting() {
+        assert_eq!(
+            encode("M0.123 0.456 L1.3333 0.456 L2.6666 0.456 L4 0.456", Some(1)),
+            "M.1.5h1.2 1.4H4"
+        );
+        assert_eq!(encode("M0 0 L10.004 -0.004", Some(2)), "M0 0h10");
+
+        let path: Path = "M0 0 C0 33.3333 66.6666 33.3333 66.6666 0 a50 50 0 0 0 -66.6666 0"
+            .parse()
+            .unwrap();
+        let encoded: Path = path.encode(Some(3)).parse().unwrap();
+        let (expected, actual) = (path.segments(), encoded.segments());
+        for (expected, actual) in expected.iter().zip(&actual) {
+            let (expected, actual) = (expected.to(), actual.to());
+            assert!((expected.0 - actual.0).abs() < 1e-3 && (expected.1 - actual.1).abs() < 1e-3);
+        }
+    }
+}
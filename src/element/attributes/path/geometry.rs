@@ -0,0 +1,896 @@
+//! Resolving path data to absolute coordinates, and converting between
+//! equivalent forms of the same path.
+
+use std::f64::consts::{FRAC_PI_2, TAU};
+
+use crate::svg::types::Number;
+
+use super::{
+    CubicBezierCurve, CubicBezierCurvePoint, EllipticalArcCurve, EllipticalArcPoint, LineTo,
+    MoveTo, Path, PathType, Point, QuadraticBezierCurve, QuadraticBezierCurvePoint,
+    SmoothCubicBezierCurvePoint,
+};
+
+/// A segment of a path in absolute coordinates, with the `H`, `V`, `S` and
+/// `T` shorthands expanded.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Segment {
+    /// Starts a new subpath at `to`.
+    MoveTo {
+        to: (f64, f64),
+    },
+    LineTo {
+        from: (f64, f64),
+        to: (f64, f64),
+    },
+    CubicTo {
+        from: (f64, f64),
+        control1: (f64, f64),
+        control2: (f64, f64),
+        to: (f64, f64),
+    },
+    QuadraticTo {
+        from: (f64, f64),
+        control: (f64, f64),
+        to: (f64, f64),
+    },
+    ArcTo {
+        from: (f64, f64),
+        rx: f64,
+        ry: f64,
+        /// Rotation of the ellipse in degrees.
+        angle: f64,
+        large_arc: bool,
+        sweep: bool,
+        to: (f64, f64),
+    },
+    /// Closes the subpath with a line from `from` back to its start, `to`.
+    ClosePath {
+        from: (f64, f64),
+        to: (f64, f64),
+    },
+}
+
+impl Segment {
+    /// Returns the point where the segment starts; for a moveto, the point it
+    /// moves to.
+    pub fn from(&self) -> (f64, f64) {
+        match *self {
+            Segment::MoveTo { to } => to,
+            Segment::LineTo { from, .. }
+            | Segment::CubicTo { from, .. }
+            | Segment::QuadraticTo { from, .. }
+            | Segment::ArcTo { from, .. }
+            | Segment::ClosePath { from, .. } => from,
+        }
+    }
+
+    /// Returns the point where the segment ends.
+    pub fn to(&self) -> (f64, f64) {
+        match *self {
+            Segment::MoveTo { to }
+            | Segment::LineTo { to, .. }
+            | Segment::CubicTo { to, .. }
+            | Segment::QuadraticTo { to, .. }
+            | Segment::ArcTo { to, .. }
+            | Segment::ClosePath { to, .. } => to,
+        }
+    }
+
+    /// Converts the segment to cubic Béziers, each given as its two control
+    /// points and its end point.
+    ///
+    /// A moveto has no curves, and neither has an arc whose end points are
+    /// the same, since such an arc is not drawn.
+    pub fn to_cubics(&self) -> Vec<[(f64, f64); 3]> {
+        match *self {
+            Segment::MoveTo { .. } => vec![],
+            Segment::LineTo { from, to } | Segment::ClosePath { from, to } => {
+                vec![line_to_cubic(from, to)]
+            }
+            Segment::CubicTo {
+                control1,
+                control2,
+                to,
+                ..
+            } => vec![[control1, control2, to]],
+            Segment::QuadraticTo { from, control, to } => vec![[
+                lerp(from, control, 2.0 / 3.0),
+                lerp(to, control, 2.0 / 3.0),
+                to,
+            ]],
+            Segment::ArcTo {
+                from,
+                rx,
+                ry,
+                angle,
+                large_arc,
+                sweep,
+                to,
+            } => arc_to_cubics(from, rx, ry, angle, large_arc, sweep, to),
+        }
+    }
+}
+
+fn lerp(from: (f64, f64), to: (f64, f64), t: f64) -> (f64, f64) {
+    (from.0 + (to.0 - from.0) * t, from.1 + (to.1 - from.1) * t)
+}
+
+/// Returns the point `control` mirrored about `center`.
+fn reflect(control: (f64, f64), center: (f64, f64)) -> (f64, f64) {
+    (2.0 * center.0 - control.0, 2.0 * center.1 - control.1)
+}
+
+fn line_to_cubic(from: (f64, f64), to: (f64, f64)) -> [(f64, f64); 3] {
+    [lerp(from, to, 1.0 / 3.0), lerp(from, to, 2.0 / 3.0), to]
+}
+
+/// An elliptical arc in center parameterization.
+#[derive(Clone, Copy, Debug)]
+pub(super) struct EllipseArc {
+    pub(super) center: (f64, f64),
+    pub(super) rx: f64,
+    pub(super) ry: f64,
+    /// Sine and cosine of the rotation of the ellipse.
+    pub(super) sin: f64,
+    pub(super) cos: f64,
+    /// Angle of the start point on the unrotated ellipse, in radians.
+    pub(super) start_angle: f64,
+    /// Angle from the start point to the end point, negative when the arc
+    /// runs against the positive angle direction.
+    pub(super) sweep_angle: f64,
+}
+
+impl EllipseArc {
+    /// Converts an arc from endpoint to center parameterization, as the SVG
+    /// specification describes in appendix B.2.4.
+    ///
+    /// Returns `None` when the arc is not drawn, or is drawn as a line.
+    pub(super) fn new(
+        from: (f64, f64),
+        rx: f64,
+        ry: f64,
+        angle: f64,
+        large_arc: bool,
+        sweep: bool,
+        to: (f64, f64),
+    ) -> Option<Self> {
+        let (mut rx, mut ry) = (rx.abs(), ry.abs());
+        if from == to || rx == 0.0 || ry == 0.0 {
+            return None;
+        }
+
+        let (sin, cos) = angle.to_radians().sin_cos();
+        let (dx, dy) = ((from.0 - to.0) / 2.0, (from.1 - to.1) / 2.0);
+        let (x1, y1) = (cos * dx + sin * dy, -sin * dx + cos * dy);
+
+        // radii too small to reach `to` are scaled up until they just do
+        let lambda = (x1 * x1) / (rx * rx) + (y1 * y1) / (ry * ry);
+        if lambda > 1.0 {
+            rx *= lambda.sqrt();
+            ry *= lambda.sqrt();
+        }
+
+        let numerator = rx * rx * ry * ry - rx * rx * y1 * y1 - ry * ry * x1 * x1;
+        let denominator = rx * rx * y1 * y1 + ry * ry * x1 * x1;
+        let mut coefficient = (numerator / denominator).max(0.0).sqrt();
+        if large_arc == sweep {
+            coefficient = -coefficient;
+        }
+        let (center_x1, center_y1) = (coefficient * rx * y1 / ry, -coefficient * ry * x1 / rx);
+        let center = (
+            cos * center_x1 - sin * center_y1 + (from.0 + to.0) / 2.0,
+            sin * center_x1 + cos * center_y1 + (from.1 + to.1) / 2.0,
+        );
+
+        let angle_between =
+            |u: (f64, f64), v: (f64, f64)| (u.0 * v.1 - u.1 * v.0).atan2(u.0 * v.0 + u.1 * v.1);
+        let start = ((x1 - center_x1) / rx, (y1 - center_y1) / ry);
+        let end = ((-x1 - center_x1) / rx, (-y1 - center_y1) / ry);
+        let mut sweep_angle = angle_between(start, end);
+        if !sweep && sweep_angle > 0.0 {
+            sweep_angle -= TAU;
+        } else if sweep && sweep_angle < 0.0 {
+            sweep_angle += TAU;
+        }
+
+        Some(Self {
+            center,
+            rx,
+            ry,
+            sin,
+            cos,
+            start_angle: angle_between((1.0, 0.0), start),
+            sweep_angle,
+        })
+    }
+
+    /// Maps the point `(x, y)` of the unit circle onto the ellipse.
+    pub(super) fn map(&self, (x, y): (f64, f64)) -> (f64, f64) {
+        let (x, y) = (x * self.rx, y * self.ry);
+        (
+            self.cos * x - self.sin * y + self.center.0,
+            self.sin * x + self.cos * y + self.center.1,
+        )
+    }
+
+    /// Returns the point of the ellipse at angle `theta`.
+    pub(super) fn point(&self, theta: f64) -> (f64, f64) {
+        let (sin, cos) = theta.sin_cos();
+        self.map((cos, sin))
+    }
+
+    /// Returns the derivative of [`EllipseArc::point`] at angle `theta`.
+    pub(super) fn derivative(&self, theta: f64) -> (f64, f64) {
+        let (sin, cos) = theta.sin_cos();
+        let (x, y) = (-sin * self.rx, cos * self.ry);
+        (self.cos * x - self.sin * y, self.sin * x + self.cos * y)
+    }
+}
+
+/// Converts an elliptical arc to at most four cubic Béziers, one per quarter
+/// of the ellipse or less.
+fn arc_to_cubics(
+    from: (f64, f64),
+    rx: f64,
+    ry: f64,
+    angle: f64,
+    large_arc: bool,
+    sweep: bool,
+    to: (f64, f64),
+) -> Vec<[(f64, f64); 3]> {
+    let Some(arc) = EllipseArc::new(from, rx, ry, angle, large_arc, sweep, to) else {
+        return match from == to {
+            true => vec![],
+            false => vec![line_to_cubic(from, to)],
+        };
+    };
+
+    let count = (arc.sweep_angle.abs() / FRAC_PI_2 - 1e-9).ceil().max(1.0) as usize;
+    let step = arc.sweep_angle / count as f64;
+    let k = 4.0 / 3.0 * (step / 4.0).tan();
+
+    let mut cubics = Vec::with_capacity(count);
+    for i in 0..count {
+        let (sin1, cos1) = (arc.start_angle + step * i as f64).sin_cos();
+        let (sin2, cos2) = (arc.start_angle + step * (i + 1) as f64).sin_cos();
+        cubics.push([
+            arc.map((cos1 - k * sin1, sin1 + k * cos1)),
+            arc.map((cos2 + k * sin2, sin2 - k * cos2)),
+            arc.map((cos2, sin2)),
+        ]);
+    }
+    if let Some(last) = cubics.last_mut() {
+        last[2] = to;
+    }
+
+    cubics
+}
+
+/// The current point and the start of the current subpath while walking a
+/// path.
+#[derive(Clone, Copy, Debug, Default)]
+struct Cursor {
+    current: (f64, f64),
+    start: (f64, f64),
+}
+
+impl Cursor {
+    /// Returns the absolute point of the coordinates `x` and `y`.
+    fn resolve(&self, relative: bool, x: Number, y: Number) -> (f64, f64) {
+        match relative {
+            true => (self.current.0 + x.0, self.current.1 + y.0),
+            false => (x.0, y.0),
+        }
+    }
+
+    /// Returns the coordinates of the absolute `point` in an absolute or a
+    /// relative command.
+    fn write(&self, absolute: bool, point: (f64, f64)) -> (Number, Number) {
+        match absolute {
+            true => (Number(point.0), Number(point.1)),
+            false => (
+                Number(point.0 - self.current.0),
+                Number(point.1 - self.current.1),
+            ),
+        }
+    }
+
+    fn convert(&self, relative: bool, absolute: bool, x: Number, y: Number) -> (Number, Number) {
+        self.write(absolute, self.resolve(relative, x, y))
+    }
+}
+
+impl PathType {
+    /// Returns the command in absolute or relative coordinates, starting at
+    /// `cursor`, and moves `cursor` to its end.
+    fn convert(&self, absolute: bool, cursor: &mut Cursor) -> PathType {
+        match self {
+            PathType::MoveTo(MoveTo::Absolute((x, y)) | MoveTo::Relative((x, y))) => {
+                let relative = matches!(self, PathType::MoveTo(MoveTo::Relative(_)));
+                let point = cursor.resolve(relative, *x, *y);
+                let coordinates = cursor.write(absolute, point);
+                cursor.current = point;
+                cursor.start = point;
+
+                PathType::MoveTo(match absolute {
+                    true => MoveTo::Absolute(coordinates),
+                    false => MoveTo::Relative(coordinates),
+                })
+            }
+            PathType::LineTo(line_to) => {
+                let mut numbers = vec![];
+                let line_to = match line_to {
+                    LineTo::XYAbsolute(coordinates) | LineTo::XYRelative(coordinates) => {
+                        let relative = matches!(line_to, LineTo::XYRelative(_));
+                        for pair in coordinates.chunks_exact(2) {
+                            let point = cursor.resolve(relative, pair[0], pair[1]);
+                            let (x, y) = cursor.write(absolute, point);
+                            numbers.extend([x, y]);
+                            cursor.current = point;
+                        }
+
+                        match absolute {
+                            true => LineTo::XYAbsolute(numbers),
+                            false => LineTo::XYRelative(numbers),
+                        }
+                    }
+                    LineTo::HorizontalAbsolute(coordinates)
+                    | LineTo::HorizontalRelative(coordinates) => {
+                        let relative = matches!(line_to, LineTo::HorizontalRelative(_));
+                        for &x in coordinates {
+                            let point = cursor.resolve(relative, x, Number(0.0));
+                            let point = (point.0, cursor.current.1);
+                            numbers.push(cursor.write(absolute, point).0);
+                            cursor.current = point;
+                        }
+
+                        match absolute {
+                            true => LineTo::HorizontalAbsolute(numbers),
+                            false => LineTo::HorizontalRelative(numbers),
+                        }
+                    }
+                    LineTo::VerticalAbsolute(coordinates)
+                    | LineTo::VerticalRelative(coordinates) => {
+                        let relative = matches!(line_to, LineTo::VerticalRelative(_));
+                        for &y in coordinates {
+                            let point = cursor.resolve(relative, Number(0.0), y);
+                            let point = (cursor.current.0, point.1);
+                            numbers.push(cursor.write(absolute, point).1);
+                            cursor.current = point;
+                        }
+
+                        match absolute {
+                            true => LineTo::VerticalAbsolute(numbers),
+                            false => LineTo::VerticalRelative(numbers),
+                        }
+                    }
+                };
+
+                PathType::LineTo(line_to)
+            }
+            PathType::CubicBezierCurve(
+                curve @ (CubicBezierCurve::Absolute(points) | CubicBezierCurve::Relative(points)),
+            ) => {
+                let relative = matches!(curve, CubicBezierCurve::Relative(_));
+                let points = points
+                    .iter()
+                    .map(|point| {
+                        let (x1, y1) = cursor.convert(relative, absolute, point.x1, point.y1);
+                        let (x2, y2) = cursor.convert(relative, absolute, point.x2, point.y2);
+                        let (x, y) = cursor.convert(relative, absolute, point.x, point.y);
+                        cursor.current = cursor.resolve(relative, point.x, point.y);
+                        CubicBezierCurvePoint {
+                            x1,
+                            y1,
+                            x2,
+                            y2,
+                            x,
+                            y,
+                        }
+                    })
+                    .collect();
+
+                PathType::CubicBezierCurve(match absolute {
+                    true => CubicBezierCurve::Absolute(points),
+                    false => CubicBezierCurve::Relative(points),
+                })
+            }
+            PathType::CubicBezierCurve(
+                curve @ (CubicBezierCurve::SmoothAbsolute(points)
+                | CubicBezierCurve::SmoothRelative(points)),
+            ) => {
+                let relative = matches!(curve, CubicBezierCurve::SmoothRelative(_));
+                let points = points
+                    .iter()
+                    .map(|point| {
+                        let (x2, y2) = cursor.convert(relative, absolute, point.x2, point.y2);
+                        let (x, y) = cursor.convert(relative, absolute, point.x, point.y);
+                        cursor.current = cursor.resolve(relative, point.x, point.y);
+                        SmoothCubicBezierCurvePoint { x2, y2, x, y }
+                    })
+                    .collect();
+
+                PathType::CubicBezierCurve(match absolute {
+                    true => CubicBezierCurve::SmoothAbsolute(points),
+                    false => CubicBezierCurve::SmoothRelative(points),
+                })
+            }
+            PathType::QuadraticBezierCurve(
+                curve @ (QuadraticBezierCurve::Absolute(points)
+                | QuadraticBezierCurve::Relative(points)),
+            ) => {
+                let relative = matches!(curve, QuadraticBezierCurve::Relative(_));
+                let points = points
+                    .iter()
+                    .map(|point| {
+                        let (x1, y1) = cursor.convert(relative, absolute, point.x1, point.y1);
+                        let (x, y) = cursor.convert(relative, absolute, point.x, point.y);
+                        cursor.current = cursor.resolve(relative, point.x, point.y);
+                        QuadraticBezierCurvePoint { x1, y1, x, y }
+                    })
+                    .collect();
+
+                PathType::QuadraticBezierCurve(match absolute {
+                    true => QuadraticBezierCurve::Absolute(points),
+                    false => QuadraticBezierCurve::Relative(points),
+                })
+            }
+            PathType::QuadraticBezierCurve(
+                curve @ (QuadraticBezierCurve::SmoothAbsolute(points)
+                | QuadraticBezierCurve::SmoothRelative(points)),
+            ) => {
+                let relative = matches!(curve, QuadraticBezierCurve::SmoothRelative(_));
+                let points = points
+                    .iter()
+                    .map(|point| {
+                        let (x, y) = cursor.convert(relative, absolute, point.x, point.y);
+                        cursor.current = cursor.resolve(relative, point.x, point.y);
+                        Point { x, y }
+                    })
+                    .collect();
+
+                PathType::QuadraticBezierCurve(match absolute {
+                    true => QuadraticBezierCurve::SmoothAbsolute(points),
+                    false => QuadraticBezierCurve::SmoothRelative(points),
+                })
+            }
+            PathType::EllipticalArcCurve(
+                curve @ (EllipticalArcCurve::Absolute(points)
+                | EllipticalArcCurve::Relative(points)),
+            ) => {
+                let relative = matches!(curve, EllipticalArcCurve::Relative(_));
+                let points = points
+                    .iter()
+                    .map(|point| {
+                        let (x, y) = cursor.convert(relative, absolute, point.x, point.y);
+                        cursor.current = cursor.resolve(relative, point.x, point.y);
+                        EllipticalArcPoint { x, y, ..*point }
+                    })
+                    .collect();
+
+                PathType::EllipticalArcCurve(match absolute {
+                    true => EllipticalArcCurve::Absolute(points),
+                    false => EllipticalArcCurve::Relative(points),
+                })
+            }
+            PathType::ClosePath => {
+                cursor.current = cursor.start;
+                PathType::ClosePath
+            }
+        }
+    }
+}
+
+impl Path {
+    /// Returns the path with every command in absolute coordinates.
+    pub fn to_absolute(&self) -> Path {
+        let mut cursor = Cursor::default();
+        Path(
+            self.0
+                .iter()
+                .map(|path_type| path_type.convert(true, &mut cursor))
+                .collect(),
+        )
+    }
+
+    /// Returns the path with every command in coordinates relative to the
+    /// current point.
+    pub fn to_relative(&self) -> Path {
+        let mut cursor = Cursor::default();
+        Path(
+            self.0
+                .iter()
+                .map(|path_type| path_type.convert(false, &mut cursor))
+                .collect(),
+        )
+    }
+
+    /// Returns the segments of the path in absolute coordinates.
+    pub fn segments(&self) -> Vec<Segment> {
+        fn current(segments: &[Segment]) -> (f64, f64) {
+            segments.last().map_or((0.0, 0.0), Segment::to)
+        }
+
+        let mut segments = vec![];
+        let mut start = (0.0, 0.0);
+
+        for path_type in &self.to_absolute().0 {
+            match path_type {
+                PathType::MoveTo(MoveTo::Absolute((x, y))) => {
+                    start = (x.0, y.0);
+                    segments.push(Segment::MoveTo { to: start });
+                }
+                PathType::LineTo(LineTo::XYAbsolute(numbers)) => {
+                    for pair in numbers.chunks_exact(2) {
+                        segments.push(Segment::LineTo {
+                            from: current(&segments),
+                            to: (pair[0].0, pair[1].0),
+                        });
+                    }
+                }
+                PathType::LineTo(LineTo::HorizontalAbsolute(numbers)) => {
+                    for x in numbers {
+                        let from = current(&segments);
+                        segments.push(Segment::LineTo {
+                            from,
+                            to: (x.0, from.1),
+                        });
+                    }
+                }
+                PathType::LineTo(LineTo::VerticalAbsolute(numbers)) => {
+                    for y in numbers {
+                        let from = current(&segments);
+                        segments.push(Segment::LineTo {
+                            from,
+                            to: (from.0, y.0),
+                        });
+                    }
+                }
+                PathType::CubicBezierCurve(CubicBezierCurve::Absolute(points)) => {
+                    for point in points {
+                        segments.push(Segment::CubicTo {
+                            from: current(&segments),
+                            control1: (point.x1.0, point.y1.0),
+                            control2: (point.x2.0, point.y2.0),
+                            to: (point.x.0, point.y.0),
+                        });
+                    }
+                }
+                PathType::CubicBezierCurve(CubicBezierCurve::SmoothAbsolute(points)) => {
+                    for point in points {
+                        let from = current(&segments);
+                        // the first control point reflects the second one of a preceding cubic
+                        let control1 = match segments.last() {
+                            Some(Segment::CubicTo { control2, .. }) => reflect(*control2, from),
+                            _ => from,
+                        };
+                        segments.push(Segment::CubicTo {
+                            from,
+                            control1,
+                            control2: (point.x2.0, point.y2.0),
+                            to: (point.x.0, point.y.0),
+                        });
+                    }
+                }
+                PathType::QuadraticBezierCurve(QuadraticBezierCurve::Absolute(points)) => {
+                    for point in points {
+                        segments.push(Segment::QuadraticTo {
+                            from: current(&segments),
+                            control: (point.x1.0, point.y1.0),
+                            to: (point.x.0, point.y.0),
+                        });
+                    }
+                }
+                PathType::QuadraticBezierCurve(QuadraticBezierCurve::SmoothAbsolute(points)) => {
+                    for point in points {
+                        let from = current(&segments);
+                        let control = match segments.last() {
+                            Some(Segment::QuadraticTo { control, .. }) => reflect(*control, from),
+                            _ => from,
+                        };
+                        segments.push(Segment::QuadraticTo {
+                            from,
+                            control,
+                            to: (point.x.0, point.y.0),
+                        });
+                    }
+                }
+                PathType::EllipticalArcCurve(EllipticalArcCurve::Absolute(points)) => {
+                    for point in points {
+                        segments.push(Segment::ArcTo {
+                            from: current(&segments),
+                            rx: point.rx.0,
+                            ry: point.ry.0,
+                            angle: point.angle.0,
+                            large_arc: point.large_arc_flag,
+                            sweep: point.sweep_flag,
+                            to: (point.x.0, point.y.0),
+                        });
+                    }
+                }
+                PathType::ClosePath => segments.push(Segment::ClosePath {
+                    from: current(&segments),
+                    to: start,
+                }),
+                _ => unreachable!("to_absolute only returns absolute commands"),
+            }
+        }
+
+        segments
+    }
+
+    /// Builds a path of absolute `M`, `L`, `C`, `Q`, `A` and `Z` commands,
+    /// with consecutive segments of the same kind in one command.
+    pub fn from_segments(segments: &[Segment]) -> Path {
+        let mut path_types: Vec<PathType> = vec![];
+
+        for segment in segments {
+            let number = |(x, y): (f64, f64)| (Number(x), Number(y));
+            match (*segment, path_types.last_mut()) {
+                (Segment::MoveTo { to }, _) => {
+                    path_types.push(PathType::MoveTo(MoveTo::Absolute(number(to))))
+                }
+                (
+                    Segment::LineTo { to, .. },
+                    Some(PathType::LineTo(LineTo::XYAbsolute(numbers))),
+                ) => numbers.extend([Number(to.0), Number(to.1)]),
+                (Segment::LineTo { to, .. }, _) => {
+                    path_types.push(PathType::LineTo(LineTo::XYAbsolute(vec![
+                        Number(to.0),
+                        Number(to.1),
+                    ])))
+                }
+                (
+                    Segment::CubicTo {
+                        control1,
+                        control2,
+                        to,
+                        ..
+                    },
+                    last,
+                ) => {
+                    let point = CubicBezierCurvePoint {
+                        x1: Number(control1.0),
+                        y1: Number(control1.1),
+                        x2: Number(control2.0),
+                        y2: Number(control2.1),
+                        x: Number(to.0),
+                        y: Number(to.1),
+                    };
+                    match last {
+                        Some(PathType::CubicBezierCurve(CubicBezierCurve::Absolute(points))) => {
+                            points.push(point)
+                        }
+                        _ => path_types.push(PathType::CubicBezierCurve(
+                            CubicBezierCurve::Absolute(vec![point]),
+                        )),
+                    }
+                }
+                (Segment::QuadraticTo { control, to, .. }, last) => {
+                    let point = QuadraticBezierCurvePoint {
+                        x1: Number(control.0),
+                        y1: Number(control.1),
+                        x: Number(to.0),
+                        y: Number(to.1),
+                    };
+                    match last {
+                        Some(PathType::QuadraticBezierCurve(QuadraticBezierCurve::Absolute(
+                            points,
+                        ))) => points.push(point),
+                        _ => path_types.push(PathType::QuadraticBezierCurve(
+                            QuadraticBezierCurve::Absolute(vec![point]),
+                        )),
+                    }
+                }
+                (
+                    Segment::ArcTo {
+                        rx,
+                        ry,
+                        angle,
+                        large_arc,
+                        sweep,
+                        to,
+                        ..
+                    },
+                    last,
+                ) => {
+                    let point = EllipticalArcPoint {
+                        rx: Number(rx),
+                        ry: Number(ry),
+                        angle: Number(angle),
+                        large_arc_flag: large_arc,
+                        sweep_flag: sweep,
+                        x: Number(to.0),
+                        y: Number(to.1),
+                    };
+                    match last {
+                        Some(PathType::EllipticalArcCurve(EllipticalArcCurve::Absolute(
+                            points,
+                        ))) => points.push(point),
+                        _ => path_types.push(PathType::EllipticalArcCurve(
+                            EllipticalArcCurve::Absolute(vec![point]),
+                        )),
+                    }
+                }
+                (Segment::ClosePath { .. }, _) => path_types.push(PathType::ClosePath),
+            }
+        }
+
+        Path(path_types)
+    }
+
+    /// Returns the path in absolute coordinates, with the `H`, `V`, `S` and
+    /// `T` shorthands replaced by `L`, `C` and `Q` commands.
+    pub fn expand_shorthands(&self) -> Path {
+        Path::from_segments(&self.segments())
+    }
+
+    /// Returns the path as absolute `M`, `C` and `Z` commands only.
+    ///
+    /// Lines, quadratic Béziers and arcs become cubic Béziers, and arcs
+    /// whose end points are the same are dropped, since they are not drawn.
+    pub fn normalize(&self) -> Path {
+        let mut normalized = vec![];
+        for segment in self.segments() {
+            if let Segment::MoveTo { .. } | Segment::ClosePath { .. } = segment {
+                normalized.push(segment);
+                continue;
+            }
+
+            let mut from = segment.from();
+            for [control1, control2, to] in segment.to_cubics() {
+                normalized.push(Segment::CubicTo {
+                    from,
+                    control1,
+                    control2,
+                    to,
+                });
+                from = to;
+            }
+        }
+
+        Path::from_segments(&normalized)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path(data: &str) -> Path {
+        data.parse().unwrap()
+    }
+
+    fn assert_near(actual: (f64, f64), expected: (f64, f64)) {
+        assert!(
+            (actual.0 - expected.0).abs() < 1e-9 && (actual.1 - expected.1).abs() < 1e-9,
+            "{actual:?} != {expected:?}"
+        );
+    }
+
+    #[test]
+    fn to_absolute_and_to_relative_should_resolve_the_current_point() {
+        let relative = path(
+            "m10 10 20 0 h5 v5 c1 1 2 2 3 3 s1 1 2 2 q1 1 2 2 t1 1 a1 1 0 0 1 2 0 z m1 1 l1 1",
+        );
+        let absolute = path(
+            "M10 10 30 10 H35 V15 C36 16 37 17 38 18 S39 19 40 20 Q41 21 42 22 T43 23 A1 1 0 0 1 45 23 Z M11 11 L12 12",
+        );
+
+        assert_eq!(relative.to_absolute(), absolute);
+        assert_eq!(absolute.to_relative(), relative);
+        assert_eq!(absolute.to_absolute(), absolute);
+    }
+
+    #[test]
+    fn segments_should_expand_shorthands() {
+        assert_eq!(
+            path("M0 0 C0 10 10 10 10 0 S20 -10 20 0 T30 0 h5 v5 Z").segments(),
+            vec![
+                Segment::MoveTo { to: (0.0, 0.0) },
+                Segment::CubicTo {
+                    from: (0.0, 0.0),
+                    control1: (0.0, 10.0),
+                    control2: (10.0, 10.0),
+                    to: (10.0, 0.0),
+                },
+                Segment::CubicTo {
+                    from: (10.0, 0.0),
+                    control1: (10.0, -10.0),
+                    control2: (20.0, -10.0),
+                    to: (20.0, 0.0),
+                },
+                // a `T` after a cubic uses the current point as its control point
+                Segment::QuadraticTo {
+                    from: (20.0, 0.0),
+                    control: (20.0, 0.0),
+                    to: (30.0, 0.0),
+                },
+                Segment::LineTo {
+                    from: (30.0, 0.0),
+                    to: (35.0, 0.0),
+                },
+                Segment::LineTo {
+                    from: (35.0, 0.0),
+                    to: (35.0, 5.0),
+                },
+                Segment::ClosePath {
+                    from: (35.0, 5.0),
+                    to: (0.0, 0.0),
+                },
+            ]
+        );
+
+        assert_eq!(
+            path("M0 0 Q5 5 10 0 T20 0 T30 0").expand_shorthands(),
+            path("M0 0 Q5 5 10 0 15 -5 20 0 25 5 30 0")
+        );
+    }
+
+    #[test]
+    fn arcs_should_become_cubics() {
+        let k = 4.0 / 3.0 * (std::f64::consts::PI / 8.0).tan();
+
+        let quarter = arc_to_cubics((1.0, 0.0), 1.0, 1.0, 0.0, false, true, (0.0, 1.0));
+        assert_eq!(quarter.len(), 1);
+        assert_near(quarter[0][0], (1.0, k));
+        assert_near(quarter[0][1], (k, 1.0));
+        assert_eq!(quarter[0][2], (0.0, 1.0));
+
+        // radii too small to reach the end point are scaled up to a half ellipse
+        let half = arc_to_cubics((0.0, 0.0), 1.0, 1.0, 0.0, false, false, (10.0, 0.0));
+        assert_eq!(half.len(), 2);
+        assert_near(half[0][2], (5.0, 5.0));
+
+        let large = arc_to_cubics((1.0, 0.0), 1.0, 1.0, 0.0, true, true, (0.0, -1.0));
+        assert_eq!(large.len(), 3);
+        assert_near(large[0][2], (0.0, 1.0));
+
+        assert!(arc_to_cubics((1.0, 1.0), 5.0, 5.0, 0.0, false, false, (1.0, 1.0)).is_empty());
+        assert_eq!(
+            arc_to_cubics((0.0, 0.0), 0.0, 5.0, 0.0, false, false, (3.0, 0.0)),
+            vec![[(1.0, 0.0), (2.0, 0.0), (3.0, 0.0)]]
+        );
+    }
+
+    #[test]
+    fn normalize_should_only_use_moveto_cubics_and_closepath() {
+        let normalized = path("m0 0 h3 q3 3 0 6 a3 3 0 0 1 -3 -3 a1 1 0 0 0 0 0 z").normalize();
+
+        let [
+            PathType::MoveTo(MoveTo::Absolute(start)),
+            PathType::CubicBezierCurve(CubicBezierCurve::Absolute(points)),
+            PathType::ClosePath,
+        ] = &normalized.0[..]
+        else {
+            panic!("expected M, C and Z, got {normalized:?}");
+        };
+
+        assert_eq!(*start, (Number(0.0), Number(0.0)));
+        // the arc that ends where it starts is dropped
+        assert_eq!(points.len(), 3);
+        assert_eq!(
+            points[..2],
+            [
+                CubicBezierCurvePoint {
+                    x1: Number(1.0),
+                    y1: Number(0.0),
+                    x2: Number(2.0),
+                    y2: Number(0.0),
+                    x: Number(3.0),
+                    y: Number(0.0),
+                },
+                CubicBezierCurvePoint {
+                    x1: Number(5.0),
+                    y1: Number(2.0),
+                    x2: Number(5.0),
+                    y2: Number(4.0),
+                    x: Number(3.0),
+                    y: Number(6.0),
+                },
+            ]
+        );
+        assert_eq!((points[2].x, points[2].y), (Number(0.0), Number(3.0)));
+    }
+}
@@ -0,0 +1,438 @@
+//! Measuring paths: bounding boxes, lengths, and points at a distance along a
+//! path.
+
+use std::f64::consts::PI;
+
+use super::{Path, Segment, geometry::EllipseArc};
+
+/// An axis-aligned rectangle around the geometry of a path.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BoundingBox {
+    pub min: (f64, f64),
+    pub max: (f64, f64),
+}
+
+impl BoundingBox {
+    fn around(point: (f64, f64)) -> Self {
+        Self {
+            min: point,
+            max: point,
+        }
+    }
+
+    fn include(&mut self, (x, y): (f64, f64)) {
+        self.min = (self.min.0.min(x), self.min.1.min(y));
+        self.max = (self.max.0.max(x), self.max.1.max(y));
+    }
+
+    pub fn width(&self) -> f64 {
+        self.max.0 - self.min.0
+    }
+
+    pub fn height(&self) -> f64 {
+        self.max.1 - self.min.1
+    }
+}
+
+/// A point at a distance along a path, and the direction of the path there.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PathPoint {
+    pub point: (f64, f64),
+    /// Angle of the tangent in degrees, from the positive x axis towards the
+    /// positive y axis.
+    pub angle: f64,
+}
+
+/// The shape of a drawn segment, parameterized over `0.0..=1.0`.
+enum Curve {
+    Line([(f64, f64); 2]),
+    Cubic([(f64, f64); 4]),
+    Arc(EllipseArc),
+}
+
+impl Curve {
+    /// Returns the shape of `segment`, or `None` if the segment draws nothing.
+    fn new(segment: &Segment) -> Option<Curve> {
+        match *segment {
+            Segment::MoveTo { .. } => None,
+            Segment::LineTo { from, to } | Segment::ClosePath { from, to } => {
+                Some(Curve::Line([from, to]))
+            }
+            Segment::CubicTo {
+                from,
+                control1,
+                control2,
+                to,
+            } => Some(Curve::Cubic([from, control1, control2, to])),
+            Segment::QuadraticTo { .. } => {
+                let [[control1, control2, to]] = segment.to_cubics()[..] else {
+                    unreachable!("a quadratic Bézier is one cubic Bézier");
+                };
+                Some(Curve::Cubic([segment.from(), control1, control2, to]))
+            }
+            Segment::ArcTo {
+                from,
+                rx,
+                ry,
+                angle,
+                large_arc,
+                sweep,
+                to,
+            } => match EllipseArc::new(from, rx, ry, angle, large_arc, sweep, to) {
+                Some(arc) => Some(Curve::Arc(arc)),
+                None if from != to => Some(Curve::Line([from, to])),
+                None => None,
+            },
+        }
+    }
+
+    fn point(&self, t: f64) -> (f64, f64) {
+        match self {
+            Curve::Line([from, to]) => (from.0 + (to.0 - from.0) * t, from.1 + (to.1 - from.1) * t),
+            Curve::Cubic(points) => {
+                let s = 1.0 - t;
+                let weights = [s * s * s, 3.0 * s * s * t, 3.0 * s * t * t, t * t * t];
+                weights
+                    .iter()
+                    .zip(points)
+                    .fold((0.0, 0.0), |(x, y), (weight, point)| {
+                        (x + weight * point.0, y + weight * point.1)
+                    })
+            }
+            Curve::Arc(arc) => arc.point(arc.start_angle + arc.sweep_angle * t),
+        }
+    }
+
+    fn derivative(&self, t: f64) -> (f64, f64) {
+        match self {
+            Curve::Line([from, to]) => (to.0 - from.0, to.1 - from.1),
+            Curve::Cubic([p0, p1, p2, p3]) => {
+                let s = 1.0 - t;
+                let weights = [3.0 * s * s, 6.0 * s * t, 3.0 * t * t];
+                let deltas = [
+                    (p1.0 - p0.0, p1.1 - p0.1),
+                    (p2.0 - p1.0, p2.1 - p1.1),
+                    (p3.0 - p2.0, p3.1 - p2.1),
+                ];
+                weights
+                    .iter()
+                    .zip(deltas)
+                    .fold((0.0, 0.0), |(x, y), (weight, delta)| {
+                        (x + weight * delta.0, y + weight * delta.1)
+                    })
+            }
+            Curve::Arc(arc) => {
+                let (x, y) = arc.derivative(arc.start_angle + arc.sweep_angle * t);
+                (x * arc.sweep_angle, y * arc.sweep_angle)
+            }
+        }
+    }
+
+    /// Returns the direction of the curve at `t` in degrees.
+    fn angle(&self, t: f64) -> f64 {
+        let (mut dx, mut dy) = self.derivative(t);
+
+        // at a cusp or a control point on an end point, look a little further
+        if dx.hypot(dy) < 1e-12 {
+            let (before, after) = ((t - 1e-6).max(0.0), (t + 1e-6).min(1.0));
+            let (from, to) = (self.point(before), self.point(after));
+            (dx, dy) = (to.0 - from.0, to.1 - from.1);
+        }
+
+        dy.atan2(dx).to_degrees()
+    }
+
+    fn bounding_box(&self) -> BoundingBox {
+        let mut bounding_box = BoundingBox::around(self.point(0.0));
+        bounding_box.include(self.point(1.0));
+
+        match self {
+            Curve::Line(_) => {}
+            Curve::Cubic([p0, p1, p2, p3]) => {
+                // the extrema are where the derivative of either coordinate is zero
+                let axes = [(p0.0, p1.0, p2.0, p3.0), (p0.1, p1.1, p2.1, p3.1)];
+                for (v0, v1, v2, v3) in axes {
+                    let a = -v0 + 3.0 * v1 - 3.0 * v2 + v3;
+                    let b = 2.0 * (v0 - 2.0 * v1 + v2);
+                    let c = v1 - v0;
+                    for t in quadratic_roots(a, b, c) {
+                        if t > 0.0 && t < 1.0 {
+                            bounding_box.include(self.point(t));
+                        }
+                    }
+                }
+            }
+            Curve::Arc(arc) => {
+                let x_extremum = (-arc.sin * arc.ry).atan2(arc.cos * arc.rx);
+                let y_extremum = (arc.cos * arc.ry).atan2(arc.sin * arc.rx);
+                for theta in [x_extremum, x_extremum + PI, y_extremum, y_extremum + PI] {
+                    let offset = match arc.sweep_angle > 0.0 {
+                        true => (theta - arc.start_angle).rem_euclid(2.0 * PI),
+                        false => (arc.start_angle - theta).rem_euclid(2.0 * PI),
+                    };
+                    if offset < arc.sweep_angle.abs() {
+                        bounding_box.include(arc.point(theta));
+                    }
+                }
+            }
+        }
+
+        bounding_box
+    }
+
+    /// Returns the length of the curve from its start to `t`.
+    fn length_to(&self, t: f64) -> f64 {
+        match self {
+            Curve::Line([from, to]) => (to.0 - from.0).hypot(to.1 - from.1) * t,
+            _ => integrate(
+                &|t| {
+                    let (dx, dy) = self.derivative(t);
+                    dx.hypot(dy)
+                },
+                0.0,
+                t,
+            ),
+        }
+    }
+
+    /// Returns the `t` at which the curve of the given total `length` has
+    /// covered `distance`.
+    fn t_at_length(&self, distance: f64, length: f64) -> f64 {
+        if let Curve::Line(_) = self {
+            return distance / length;
+        }
+
+        // Newton's method, falling back to bisection when it overshoots
+        let (mut low, mut high) = (0.0, 1.0);
+        let mut t = distance / length;
+        for _ in 0..64 {
+            let error = self.length_to(t) - distance;
+            if error.abs() < 1e-9 * length.max(1.0) {
+                break;
+            }
+            match error > 0.0 {
+                true => high = t,
+                false => low = t,
+            }
+
+            let (dx, dy) = self.derivative(t);
+            let next = t - error / dx.hypot(dy);
+            t = match next > low && next < high {
+                true => next,
+                false => (low + high) / 2.0,
+            };
+        }
+
+        t
+    }
+}
+
+/// Returns the real roots of `a * t² + b * t + c`.
+fn quadratic_roots(a: f64, b: f64, c: f64) -> Vec<f64> {
+    if a.abs() < 1e-12 {
+        return match b.abs() < 1e-12 {
+            true => vec![],
+            false => vec![-c / b],
+        };
+    }
+
+    let discriminant = b * b - 4.0 * a * c;
+    if discriminant < 0.0 {
+        return vec![];
+    }
+    let root = discriminant.sqrt();
+    vec![(-b + root) / (2.0 * a), (-b - root) / (2.0 * a)]
+}
+
+/// Integrates `f` over `from..to` with adaptive Gauss-Legendre quadrature.
+fn integrate(f: &impl Fn(f64) -> f64, from: f64, to: f64) -> f64 {
+    const NODES: [(f64, f64); 5] = [
+        (0.0, 0.568_888_888_888_888_9),
+        (-0.538_469_310_105_683_1, 0.478_628_670_499_366_5),
+        (0.538_469_310_105_683_1, 0.478_628_670_499_366_5),
+        (-0.906_179_845_938_664, 0.236_926_885_056_189_1),
+        (0.906_179_845_938_664, 0.236_926_885_056_189_1),
+    ];
+
+    let gauss = |from: f64, to: f64| {
+        let (half, middle) = ((to - from) / 2.0, (to + from) / 2.0);
+        NODES
+            .iter()
+            .map(|(node, weight)| weight * f(middle + half * node))
+            .sum::<f64>()
+            * half
+    };
+
+    let mut total = 0.0;
+    let mut pending = vec![(from, to, gauss(from, to), 0)];
+    while let Some((from, to, whole, depth)) = pending.pop() {
+        let middle = (from + to) / 2.0;
+        let (left, right) = (gauss(from, middle), gauss(middle, to));
+        if depth >= 16 || (left + right - whole).abs() <= 1e-10 * (left + right).abs().max(1.0) {
+            total += left + right;
+        } else {
+            pending.push((from, middle, left, depth + 1));
+            pending.push((middle, to, right, depth + 1));
+        }
+    }
+
+    total
+}
+
+impl Segment {
+    /// Returns the tight bounding box of the segment, or `None` for a moveto
+    /// and an arc that is not drawn.
+    pub fn bounding_box(&self) -> Option<BoundingBox> {
+        Curve::new(self).map(|curve| curve.bounding_box())
+    }
+
+    pub fn length(&self) -> f64 {
+        Curve::new(self).map_or(0.0, |curve| curve.length_to(1.0))
+    }
+}
+
+impl Path {
+    /// Returns the tight bounding box of the segments the path draws, or
+    /// `None` if it draws none.
+    pub fn bounding_box(&self) -> Option<BoundingBox> {
+        self.segments()
+            .iter()
+            .filter_map(Segment::bounding_box)
+            .reduce(|mut union, bounding_box| {
+                union.include(bounding_box.min);
+                union.include(bounding_box.max);
+                union
+            })
+    }
+
+    /// Returns the length of each segment of [`Path::segments`].
+    pub fn segment_lengths(&self) -> Vec<f64> {
+        self.segments().iter().map(Segment::length).collect()
+    }
+
+    pub fn length(&self) -> f64 {
+        self.segment_lengths().iter().sum()
+    }
+
+    /// Returns the point at `distance` along the path, and the direction of
+    /// the path there.
+    ///
+    /// `distance` is clamped to the length of the path, as in
+    /// `getPointAtLength()`. Returns `None` for a path without segments.
+    pub fn point_at_length(&self, distance: f64) -> Option<PathPoint> {
+        let segments = self.segments();
+        let mut remaining = distance.max(0.0);
+        let mut last = None;
+
+        for segment in &segments {
+            let Some(curve) = Curve::new(segment) else {
+                continue;
+            };
+
+            let length = curve.length_to(1.0);
+            if remaining <= length && length > 0.0 {
+                let t = curve.t_at_length(remaining, length);
+                return Some(PathPoint {
+                    point: curve.point(t),
+                    angle: curve.angle(t),
+                });
+            }
+            remaining -= length;
+            last = Some(curve);
+        }
+
+        match last {
+            Some(curve) => Some(PathPoint {
+                point: curve.point(1.0),
+                angle: curve.angle(1.0),
+            }),
+            // a path of moveto commands only stays at its first point
+            None => segments.first().map(|segment| PathPoint {
+                point: segment.to(),
+                angle: 0.0,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path(data: &str) -> Path {
+        data.parse().unwrap()
+    }
+
+    fn assert_near(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-6, "{actual} != {expected}");
+    }
+
+    #[test]
+    fn bounding_box_should_include_curve_extrema() {
+        assert_eq!(path("M0 0").bounding_box(), None);
+        assert_eq!(
+            path("M10 10 h20 v-5").bounding_box(),
+            Some(BoundingBox {
+                min: (10.0, 5.0),
+                max: (30.0, 10.0),
+            })
+        );
+
+        let cubic = path("M0 0 C0 40 40 40 40 0").bounding_box().unwrap();
+        assert_eq!(cubic.min, (0.0, 0.0));
+        assert_near(cubic.max.1, 30.0);
+
+        let quadratic = path("M0 0 Q10 -20 20 0").bounding_box().unwrap();
+        assert_near(quadratic.min.1, -10.0);
+
+        // the top half of a circle of radius 10 around (10, 0)
+        let arc = path("M0 0 A10 10 0 0 1 20 0").bounding_box().unwrap();
+        assert_near(arc.min.1, -10.0);
+        assert_near(arc.max.1, 0.0);
+        assert_near(arc.width(), 20.0);
+
+        // half of an ellipse turned upright
+        let rotated = path("M0 0 A20 10 90 0 1 0 40").bounding_box().unwrap();
+        assert_near(rotated.width(), 10.0);
+        assert_near(rotated.height(), 40.0);
+    }
+
+    #[test]
+    fn length_should_measure_every_segment() {
+        let square = path("M0 0 h10 v10 h-10 z");
+        assert_eq!(square.segment_lengths(), vec![0.0, 10.0, 10.0, 10.0, 10.0]);
+        assert_eq!(square.length(), 40.0);
+
+        assert_near(path("M0 0 A5 5 0 0 1 10 0").length(), 5.0 * PI);
+        assert_near(path("M0 0 C1 1 2 2 3 3").length(), 18f64.sqrt());
+        assert_near(path("M0 0 a1 1 0 0 0 0 0").length(), 0.0);
+    }
+
+    #[test]
+    fn point_at_length_should_follow_the_path() {
+        let line = path("M0 0 h10 v10");
+        assert_eq!(
+            line.point_at_length(15.0),
+            Some(PathPoint {
+                point: (10.0, 5.0),
+                angle: 90.0,
+            })
+        );
+        assert_eq!(line.point_at_length(-1.0).unwrap().point, (0.0, 0.0));
+        assert_eq!(line.point_at_length(100.0).unwrap().point, (10.0, 10.0));
+        assert_eq!(path("M3 4").point_at_length(1.0).unwrap().point, (3.0, 4.0));
+        assert_eq!(path("").point_at_length(1.0), None);
+
+        // a quarter of the way around a half circle
+        let arc = path("M0 0 A5 5 0 0 1 10 0");
+        let quarter = arc.point_at_length(5.0 * PI / 2.0).unwrap();
+        assert_near(quarter.point.0, 5.0);
+        assert_near(quarter.point.1, -5.0);
+        assert_near(quarter.angle, 0.0);
+
+        let curve = path("M0 0 C0 10 10 10 10 0");
+        let middle = curve.point_at_length(curve.length() / 2.0).unwrap();
+        assert_near(middle.point.0, 5.0);
+        assert_near(middle.point.1, 7.5);
+    }
+}
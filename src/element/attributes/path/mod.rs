@@ -0,0 +1,856 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::svg::types::{Number, basic::number_len};
+
+mod encode;
+mod geometry;
+mod measure;
+
+pub use geometry::Segment;
+pub use measure::{BoundingBox, PathPoint};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum MoveTo {
+    Absolute((Number, Number)),
+    Relative((Number, Number)),
+}
+
+impl fmt::Display for MoveTo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoveTo::Absolute((x, y)) => write!(f, "M {},{}", x, y),
+            MoveTo::Relative((dx, dy)) => write!(f, "m {},{}", dx, dy),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum LineTo {
+    XYAbsolute(Vec<Number>),
+    XYRelative(Vec<Number>),
+    HorizontalAbsolute(Vec<Number>),
+    HorizontalRelative(Vec<Number>),
+    VerticalAbsolute(Vec<Number>),
+    VerticalRelative(Vec<Number>),
+}
+
+impl fmt::Display for LineTo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LineTo::XYAbsolute(v) => write!(
+                f,
+                "L {}",
+                v.iter()
+                    .map(|x| x.to_string())
+                    .collect::<Vec<String>>()
+                    .join(",")
+            ),
+            LineTo::XYRelative(v) => write!(
+                f,
+                "l {}",
+                v.iter()
+                    .map(|x| x.to_string())
+                    .collect::<Vec<String>>()
+                    .join(",")
+            ),
+            LineTo::HorizontalAbsolute(v) => write!(
+                f,
+                "H {}",
+                v.iter()
+                    .map(|x| x.to_string())
+                    .collect::<Vec<String>>()
+                    .join(",")
+            ),
+            LineTo::HorizontalRelative(v) => write!(
+                f,
+                "h {}",
+                v.iter()
+                    .map(|x| x.to_string())
+                    .collect::<Vec<String>>()
+                    .join(",")
+            ),
+            LineTo::VerticalAbsolute(v) => write!(
+                f,
+                "V {}",
+                v.iter()
+                    .map(|x| x.to_string())
+                    .collect::<Vec<String>>()
+                    .join(",")
+            ),
+            LineTo::VerticalRelative(v) => write!(
+                f,
+                "v {}",
+                v.iter()
+                    .map(|x| x.to_string())
+                    .collect::<Vec<String>>()
+                    .join(",")
+            ),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CubicBezierCurvePoint {
+    pub x1: Number,
+    pub y1: Number,
+    pub x2: Number,
+    pub y2: Number,
+    pub x: Number,
+    pub y: Number,
+}
+
+impl fmt::Display for CubicBezierCurvePoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {} {} {}",
+            self.x1, self.y1, self.x2, self.y2, self.x, self.y,
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SmoothCubicBezierCurvePoint {
+    pub x2: Number,
+    pub y2: Number,
+    pub x: Number,
+    pub y: Number,
+}
+
+impl fmt::Display for SmoothCubicBezierCurvePoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {} {}", self.x2, self.y2, self.x, self.y)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum CubicBezierCurve {
+    Absolute(Vec<CubicBezierCurvePoint>),
+    Relative(Vec<CubicBezierCurvePoint>),
+    SmoothAbsolute(Vec<SmoothCubicBezierCurvePoint>),
+    SmoothRelative(Vec<SmoothCubicBezierCurvePoint>),
+}
+
+impl fmt::Display for CubicBezierCurve {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CubicBezierCurve::Absolute(cubic_bezier_curve_points) => write!(
+                f,
+                "C {}",
+                cubic_bezier_curve_points
+                    .iter()
+                    .map(|cubic_bezier_curve_point| cubic_bezier_curve_point.to_string())
+                    .collect::<Vec<String>>()
+                    .join(" ")
+            ),
+            CubicBezierCurve::Relative(cubic_bezier_curve_points) => write!(
+                f,
+                "c {}",
+                cubic_bezier_curve_points
+                    .iter()
+                    .map(|cubic_bezier_curve_point| cubic_bezier_curve_point.to_string())
+                    .collect::<Vec<String>>()
+                    .join(" ")
+            ),
+            CubicBezierCurve::SmoothAbsolute(smooth_cubic_bezier_curve_points) => {
+                write!(
+                    f,
+                    "S {}",
+                    smooth_cubic_bezier_curve_points
+                        .iter()
+                        .map(|smooth_cubic_bezier_curve_point| {
+                            smooth_cubic_bezier_curve_point.to_string()
+                        })
+                        .collect::<Vec<String>>()
+                        .join(" ")
+                )
+            }
+            CubicBezierCurve::SmoothRelative(smooth_cubic_bezier_curve_points) => {
+                write!(
+                    f,
+                    "s {}",
+                    smooth_cubic_bezier_curve_points
+                        .iter()
+                        .map(|smooth_cubic_bezier_curve_point| {
+                            smooth_cubic_bezier_curve_point.to_string()
+                        })
+                        .collect::<Vec<String>>()
+                        .join(" ")
+                )
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct QuadraticBezierCurvePoint {
+    pub x1: Number,
+    pub y1: Number,
+    pub x: Number,
+    pub y: Number,
+}
+
+impl fmt::Display for QuadraticBezierCurvePoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {} {}", self.x1, self.y1, self.x, self.y)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Point {
+    pub x: Number,
+    pub y: Number,
+}
+
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.x, self.y)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum QuadraticBezierCurve {
+    Absolute(Vec<QuadraticBezierCurvePoint>),
+    Relative(Vec<QuadraticBezierCurvePoint>),
+    SmoothAbsolute(Vec<Point>),
+    SmoothRelative(Vec<Point>),
+}
+
+impl fmt::Display for QuadraticBezierCurve {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QuadraticBezierCurve::Absolute(quadratic_bezier_curve_points) => {
+                write!(
+                    f,
+                    "Q {}",
+                    quadratic_bezier_curve_points
+                        .iter()
+                        .map(|point| point.to_string())
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                )
+            }
+            QuadraticBezierCurve::Relative(quadratic_bezier_curve_points) => {
+                write!(
+                    f,
+                    "q {}",
+                    quadratic_bezier_curve_points
+                        .iter()
+                        .map(|point| point.to_string())
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                )
+            }
+            QuadraticBezierCurve::SmoothAbsolute(points) => write!(
+                f,
+                "T {}",
+                points
+                    .iter()
+                    .map(|point| point.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            ),
+            QuadraticBezierCurve::SmoothRelative(points) => write!(
+                f,
+                "t {}",
+                points
+                    .iter()
+                    .map(|point| point.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            ),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct EllipticalArcPoint {
+    pub rx: Number,
+    pub ry: Number,
+    pub angle: Number,
+    pub large_arc_flag: bool,
+    pub sweep_flag: bool,
+    pub x: Number,
+    pub y: Number,
+}
+
+impl fmt::Display for EllipticalArcPoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {} {} {} {}",
+            self.rx,
+            self.ry,
+            self.angle,
+            self.large_arc_flag as u8,
+            self.sweep_flag as u8,
+            self.x,
+            self.y,
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum EllipticalArcCurve {
+    Absolute(Vec<EllipticalArcPoint>),
+    Relative(Vec<EllipticalArcPoint>),
+}
+
+impl fmt::Display for EllipticalArcCurve {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EllipticalArcCurve::Absolute(elliptical_arc_points) => write!(
+                f,
+                "A {}",
+                elliptical_arc_points
+                    .iter()
+                    .map(|point| point.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            ),
+            EllipticalArcCurve::Relative(elliptical_arc_points) => write!(
+                f,
+                "a {}",
+                elliptical_arc_points
+                    .iter()
+                    .map(|point| point.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            ),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PathType {
+    MoveTo(MoveTo),
+    LineTo(LineTo),
+    CubicBezierCurve(CubicBezierCurve),
+    QuadraticBezierCurve(QuadraticBezierCurve),
+    EllipticalArcCurve(EllipticalArcCurve),
+    ClosePath,
+}
+
+impl fmt::Display for PathType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathType::MoveTo(move_to) => write!(f, "{}", move_to),
+            PathType::LineTo(line_to) => write!(f, "{}", line_to),
+            PathType::CubicBezierCurve(cubic_bezier_curve) => write!(f, "{}", cubic_bezier_curve),
+            PathType::QuadraticBezierCurve(quadratic_bezier_curve) => {
+                write!(f, "{}", quadratic_bezier_curve)
+            }
+            PathType::EllipticalArcCurve(elliptical_arc_curve) => {
+                write!(f, "{}", elliptical_arc_curve)
+            }
+            PathType::ClosePath => write!(f, "Z"),
+        }
+    }
+}
+
+/// The kind of a [`PathError`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum PathErrorKind {
+    /// Path data that does not start with `M` or `m`.
+    ExpectedMoveTo,
+    /// A character where a command letter should be.
+    ExpectedCommand,
+    ExpectedNumber,
+    /// A character other than `0` or `1` where an arc flag should be.
+    ExpectedFlag,
+}
+
+/// An error in path data.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PathError {
+    pub kind: PathErrorKind,
+    /// Byte offset of the error in the path data.
+    pub position: usize,
+}
+
+impl fmt::Display for PathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let expected = match self.kind {
+            PathErrorKind::ExpectedMoveTo => "a moveto command",
+            PathErrorKind::ExpectedCommand => "a path command",
+            PathErrorKind::ExpectedNumber => "a number",
+            PathErrorKind::ExpectedFlag => "an arc flag",
+        };
+        write!(f, "expected {} at byte {}", expected, self.position)
+    }
+}
+
+impl std::error::Error for PathError {}
+
+/// Reads path data following the SVG 2 path grammar.
+struct PathParser<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> PathParser<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.input.as_bytes().get(self.position).copied()
+    }
+
+    fn error(&self, kind: PathErrorKind) -> PathError {
+        PathError {
+            kind,
+            position: self.position,
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r' | b'\x0C')) {
+            self.position += 1;
+        }
+    }
+
+    /// Skips whitespace with at most one comma, and returns whether there was
+    /// a comma.
+    fn skip_separator(&mut self) -> bool {
+        self.skip_whitespace();
+        let comma = self.peek() == Some(b',');
+        if comma {
+            self.position += 1;
+            self.skip_whitespace();
+        }
+        comma
+    }
+
+    fn at_number(&self) -> bool {
+        matches!(self.peek(), Some(b'0'..=b'9' | b'+' | b'-' | b'.'))
+    }
+
+    fn number(&mut self) -> Result<Number, PathError> {
+        let bytes = &self.input.as_bytes()[self.position..];
+        let mut len = number_len(bytes).ok_or_else(|| self.error(PathErrorKind::ExpectedNumber))?;
+
+        // unlike CSS, path data allows a trailing `.`, as in `1.`
+        let number = &bytes[..len];
+        if bytes.get(len) == Some(&b'.')
+            && !number.contains(&b'.')
+            && !number.contains(&b'e')
+            && !number.contains(&b'E')
+        {
+            len += 1;
+        }
+
+        // `1e999` overflows to infinity, which no path can use
+        let value = self.input[self.position..self.position + len]
+            .parse::<f64>()
+            .ok()
+            .filter(|value| value.is_finite())
+            .ok_or_else(|| self.error(PathErrorKind::ExpectedNumber))?;
+        self.position += len;
+        Ok(Number(value))
+    }
+
+    /// Parses a number after an optional separator.
+    fn next_number(&mut self) -> Result<Number, PathError> {
+        self.skip_separator();
+        self.number()
+    }
+
+    /// Parses an arc flag after an optional separator.
+    fn next_flag(&mut self) -> Result<bool, PathError> {
+        self.skip_separator();
+        let flag = match self.peek() {
+            Some(b'0') => false,
+            Some(b'1') => true,
+            _ => return Err(self.error(PathErrorKind::ExpectedFlag)),
+        };
+        self.position += 1;
+        Ok(flag)
+    }
+
+    fn pair(&mut self) -> Result<(Number, Number), PathError> {
+        Ok((self.number()?, self.next_number()?))
+    }
+
+    /// Parses one or more argument groups of a command.
+    ///
+    /// Returns the groups parsed before an error along with the error.
+    fn groups<T>(
+        &mut self,
+        group: impl Fn(&mut Self) -> Result<T, PathError>,
+    ) -> (Vec<T>, Option<PathError>) {
+        let mut groups = vec![];
+        loop {
+            match group(self) {
+                Ok(value) => groups.push(value),
+                Err(error) => return (groups, Some(error)),
+            }
+
+            let before = self.position;
+            if !self.skip_separator() && !self.at_number() {
+                self.position = before;
+                return (groups, None);
+            }
+        }
+    }
+
+    /// Parses the arguments of the command `letter`, and pushes its segments
+    /// to `segments`.
+    fn command(&mut self, letter: u8, segments: &mut Vec<PathType>) -> Result<(), PathError> {
+        let absolute = letter.is_ascii_uppercase();
+        let (error, segment) = match letter.to_ascii_uppercase() {
+            b'Z' => {
+                segments.push(PathType::ClosePath);
+                return Ok(());
+            }
+            b'M' => {
+                let (mut pairs, error) = self.groups(Self::pair);
+                if pairs.is_empty() {
+                    return Err(error.unwrap_or_else(|| self.error(PathErrorKind::ExpectedNumber)));
+                }
+
+                let first = pairs.remove(0);
+                segments.push(PathType::MoveTo(match absolute {
+                    true => MoveTo::Absolute(first),
+                    false => MoveTo::Relative(first),
+                }));
+
+                // pairs after the first one are implicit lineto commands
+                let numbers = pairs.into_iter().flat_map(|(x, y)| [x, y]).collect();
+                (
+                    error,
+                    PathType::LineTo(match absolute {
+                        true => LineTo::XYAbsolute(numbers),
+                        false => LineTo::XYRelative(numbers),
+                    }),
+                )
+            }
+            b'L' => {
+                let (pairs, error) = self.groups(Self::pair);
+                let numbers = pairs.into_iter().flat_map(|(x, y)| [x, y]).collect();
+                (
+                    error,
+                    PathType::LineTo(match absolute {
+                        true => LineTo::XYAbsolute(numbers),
+                        false => LineTo::XYRelative(numbers),
+                    }),
+                )
+            }
+            b'H' => {
+                let (numbers, error) = self.groups(Self::number);
+                (
+                    error,
+                    PathType::LineTo(match absolute {
+                        true => LineTo::HorizontalAbsolute(numbers),
+                        false => LineTo::HorizontalRelative(numbers),
+                    }),
+                )
+            }
+            b'V' => {
+                let (numbers, error) = self.groups(Self::number);
+                (
+                    error,
+                    PathType::LineTo(match absolute {
+                        true => LineTo::VerticalAbsolute(numbers),
+                        false => LineTo::VerticalRelative(numbers),
+                    }),
+                )
+            }
+            b'C' => {
+                let (points, error) = self.groups(|parser| {
+                    Ok(CubicBezierCurvePoint {
+                        x1: parser.number()?,
+                        y1: parser.next_number()?,
+                        x2: parser.next_number()?,
+                        y2: parser.next_number()?,
+                        x: parser.next_number()?,
+                        y: parser.next_number()?,
+                    })
+                });
+                (
+                    error,
+                    PathType::CubicBezierCurve(match absolute {
+                        true => CubicBezierCurve::Absolute(points),
+                        false => CubicBezierCurve::Relative(points),
+                    }),
+                )
+            }
+            b'S' => {
+                let (points, error) = self.groups(|parser| {
+                    Ok(SmoothCubicBezierCurvePoint {
+                        x2: parser.number()?,
+                        y2: parser.next_number()?,
+                        x: parser.next_number()?,
+                        y: parser.next_number()?,
+                    })
+                });
+                (
+                    error,
+                    PathType::CubicBezierCurve(match absolute {
+                        true => CubicBezierCurve::SmoothAbsolute(points),
+                        false => CubicBezierCurve::SmoothRelative(points),
+                    }),
+                )
+            }
+            b'Q' => {
+                let (points, error) = self.groups(|parser| {
+                    Ok(QuadraticBezierCurvePoint {
+                        x1: parser.number()?,
+                        y1: parser.next_number()?,
+                        x: parser.next_number()?,
+                        y: parser.next_number()?,
+                    })
+                });
+                (
+                    error,
+                    PathType::QuadraticBezierCurve(match absolute {
+                        true => QuadraticBezierCurve::Absolute(points),
+                        false => QuadraticBezierCurve::Relative(points),
+                    }),
+                )
+            }
+            b'T' => {
+                let (points, error) = self.groups(|parser| {
+                    let (x, y) = parser.pair()?;
+                    Ok(Point { x, y })
+                });
+                (
+                    error,
+                    PathType::QuadraticBezierCurve(match absolute {
+                        true => QuadraticBezierCurve::SmoothAbsolute(points),
+                        false => QuadraticBezierCurve::SmoothRelative(points),
+                    }),
+                )
+            }
+            b'A' => {
+                let (points, error) = self.groups(|parser| {
+                    Ok(EllipticalArcPoint {
+                        rx: parser.number()?,
+                        ry: parser.next_number()?,
+                        angle: parser.next_number()?,
+                        large_arc_flag: parser.next_flag()?,
+                        sweep_flag: parser.next_flag()?,
+                        x: parser.next_number()?,
+                        y: parser.next_number()?,
+                    })
+                });
+                (
+                    error,
+                    PathType::EllipticalArcCurve(match absolute {
+                        true => EllipticalArcCurve::Absolute(points),
+                        false => EllipticalArcCurve::Relative(points),
+                    }),
+                )
+            }
+            _ => return Err(self.error(PathErrorKind::ExpectedCommand)),
+        };
+
+        if !segment.is_empty() {
+            segments.push(segment);
+        }
+        match error {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+}
+
+impl PathType {
+    /// Returns whether the segment has no arguments, e.g. an `L` command
+    /// whose first argument failed to parse.
+    fn is_empty(&self) -> bool {
+        match self {
+            PathType::MoveTo(_) | PathType::ClosePath => false,
+            PathType::LineTo(
+                LineTo::XYAbsolute(numbers)
+                | LineTo::XYRelative(numbers)
+                | LineTo::HorizontalAbsolute(numbers)
+                | LineTo::HorizontalRelative(numbers)
+                | LineTo::VerticalAbsolute(numbers)
+                | LineTo::VerticalRelative(numbers),
+            ) => numbers.is_empty(),
+            PathType::CubicBezierCurve(
+                CubicBezierCurve::Absolute(points) | CubicBezierCurve::Relative(points),
+            ) => points.is_empty(),
+            PathType::CubicBezierCurve(
+                CubicBezierCurve::SmoothAbsolute(points) | CubicBezierCurve::SmoothRelative(points),
+            ) => points.is_empty(),
+            PathType::QuadraticBezierCurve(
+                QuadraticBezierCurve::Absolute(points) | QuadraticBezierCurve::Relative(points),
+            ) => points.is_empty(),
+            PathType::QuadraticBezierCurve(
+                QuadraticBezierCurve::SmoothAbsolute(points)
+                | QuadraticBezierCurve::SmoothRelative(points),
+            ) => points.is_empty(),
+            PathType::EllipticalArcCurve(
+                EllipticalArcCurve::Absolute(points) | EllipticalArcCurve::Relative(points),
+            ) => points.is_empty(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Path(pub Vec<PathType>);
+
+impl Path {
+    /// Parses path data, recovering from errors as the SVG specification
+    /// requires: the path keeps the segments before the first error, and the
+    /// error is returned along with it.
+    pub fn parse(input: &str) -> (Self, Option<PathError>) {
+        let mut parser = PathParser { input, position: 0 };
+        let mut segments = vec![];
+
+        parser.skip_whitespace();
+        while let Some(letter) = parser.peek() {
+            if segments.is_empty() && !matches!(letter, b'M' | b'm') {
+                return (
+                    Self(segments),
+                    Some(parser.error(PathErrorKind::ExpectedMoveTo)),
+                );
+            }
+            if !b"MmZzLlHhVvCcSsQqTtAa".contains(&letter) {
+                return (
+                    Self(segments),
+                    Some(parser.error(PathErrorKind::ExpectedCommand)),
+                );
+            }
+
+            parser.position += 1;
+            parser.skip_whitespace();
+
+            if let Err(error) = parser.command(letter, &mut segments) {
+                return (Self(segments), Some(error));
+            }
+            parser.skip_whitespace();
+        }
+
+        (Self(segments), None)
+    }
+}
+
+impl FromStr for Path {
+    type Err = ();
+
+    /// Parses path data, failing on any error. Use [`Path::parse`] to keep
+    /// the segments before an error.
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match Path::parse(input) {
+            (path, None) => Ok(path),
+            (_, Some(_)) => Err(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbers(values: &[f64]) -> Vec<Number> {
+        values.iter().copied().map(Number).collect()
+    }
+
+    #[test]
+    fn parse_should_follow_the_grammar() {
+        let pair = |x, y| (Number(x), Number(y));
+
+        assert_eq!(
+            "M10 20L30 40".parse(),
+            Ok(Path(vec![
+                PathType::MoveTo(MoveTo::Absolute(pair(10.0, 20.0))),
+                PathType::LineTo(LineTo::XYAbsolute(numbers(&[30.0, 40.0]))),
+            ]))
+        );
+        assert_eq!(
+            "m0 0 10 10,20-20".parse(),
+            Ok(Path(vec![
+                PathType::MoveTo(MoveTo::Relative(pair(0.0, 0.0))),
+                PathType::LineTo(LineTo::XYRelative(numbers(&[10.0, 10.0, 20.0, -20.0]))),
+            ]))
+        );
+        assert_eq!(
+            "M.5.5h1e1 2.5E-1z".parse(),
+            Ok(Path(vec![
+                PathType::MoveTo(MoveTo::Absolute(pair(0.5, 0.5))),
+                PathType::LineTo(LineTo::HorizontalRelative(numbers(&[10.0, 0.25]))),
+                PathType::ClosePath,
+            ]))
+        );
+        assert_eq!(
+            "M0 0a1 1 0 011 1".parse(),
+            Ok(Path(vec![
+                PathType::MoveTo(MoveTo::Absolute(pair(0.0, 0.0))),
+                PathType::EllipticalArcCurve(EllipticalArcCurve::Relative(vec![
+                    EllipticalArcPoint {
+                        rx: Number(1.0),
+                        ry: Number(1.0),
+                        angle: Number(0.0),
+                        large_arc_flag: false,
+                        sweep_flag: true,
+                        x: Number(1.0),
+                        y: Number(1.0),
+                    }
+                ])),
+            ]))
+        );
+        assert_eq!(
+            " M 1. 2 Z m 3 , 4 \n".parse(),
+            Ok(Path(vec![
+                PathType::MoveTo(MoveTo::Absolute(pair(1.0, 2.0))),
+                PathType::ClosePath,
+                PathType::MoveTo(MoveTo::Relative(pair(3.0, 4.0))),
+            ]))
+        );
+        assert_eq!("".parse(), Ok(Path(vec![])));
+    }
+
+    #[test]
+    fn parse_should_report_error_positions() {
+        for (input, kind, position) in [
+            ("L1 2", PathErrorKind::ExpectedMoveTo, 0),
+            ("M1 2 X3 4", PathErrorKind::ExpectedCommand, 5),
+            ("M1 2 L3,", PathErrorKind::ExpectedNumber, 8),
+            ("M1 2 L3,,4", PathErrorKind::ExpectedNumber, 8),
+            ("M1 2 L3 4,L", PathErrorKind::ExpectedNumber, 10),
+            ("M1 2 L3 1e999", PathErrorKind::ExpectedNumber, 8),
+            ("M1 2 Z 3", PathErrorKind::ExpectedCommand, 7),
+            ("M0 0 A1 1 0 2 0 1 1", PathErrorKind::ExpectedFlag, 12),
+        ] {
+            let (_, error) = Path::parse(input);
+            assert_eq!(error, Some(PathError { kind, position }), "{input}");
+            assert_eq!(input.parse::<Path>(), Err(()), "{input}");
+        }
+    }
+
+    #[test]
+    fn parse_should_keep_segments_before_the_first_error() {
+        let (path, error) = Path::parse("M10,20 L30 40 50 60 70 C1 2 3");
+
+        assert_eq!(
+            path,
+            Path(vec![
+                PathType::MoveTo(MoveTo::Absolute((Number(10.0), Number(20.0)))),
+                PathType::LineTo(LineTo::XYAbsolute(numbers(&[30.0, 40.0, 50.0, 60.0]))),
+            ])
+        );
+        assert_eq!(
+            error,
+            Some(PathError {
+                kind: PathErrorKind::ExpectedNumber,
+                position: 23,
+            })
+        );
+
+        let (path, error) = Path::parse("M10,20 L30 40 1e999 60");
+        assert_eq!(
+            path,
+            Path(vec![
+                PathType::MoveTo(MoveTo::Absolute((Number(10.0), Number(20.0)))),
+                PathType::LineTo(LineTo::XYAbsolute(numbers(&[30.0, 40.0]))),
+            ])
+        );
+        assert_eq!(
+            error,
+            Some(PathError {
+                kind: PathErrorKind::ExpectedNumber,
+                position: 14,
+            })
+        );
+    }
+}
@@ -2,11 +2,9 @@ use std::{convert::Infallible, fmt, str::FromStr};
 
 use crate::element::{
     lang::LanguageTag,
-    types::{
-        AbsoluteLength, AbsoluteSize, Color, FontWeight, Length, LengthOrPercentage, Paint,
-        Percentage, RelativeSize,
-    },
+    types::{AbsoluteSize, Color, FontWeight, Paint, RelativeSize},
 };
+use crate::svg::types::{Length, LengthOrPercentage, Percentage};
 
 use crate::element::types::ColorLiteral;
 
@@ -1558,40 +1556,3 @@ impl FromStr for Rotate {
         }
     }
 }
-
-#[derive(Clone, Debug, PartialEq)]
-pub enum LengthOrPercentageOrNumber {
-    Length(Length),
-    Percentage(Percentage),
-    Number(f64),
-}
-
-impl fmt::Display for LengthOrPercentageOrNumber {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            LengthOrPercentageOrNumber::Length(length) => write!(f, "{}", length),
-            LengthOrPercentageOrNumber::Percentage(percentage) => write!(f, "{}", percentage),
-            LengthOrPercentageOrNumber::Number(number) => write!(f, "{}", number),
-        }
-    }
-}
-
-impl FromStr for LengthOrPercentageOrNumber {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if let Ok(percentage) = Percentage::from_str(s) {
-            return Ok(LengthOrPercentageOrNumber::Percentage(percentage));
-        }
-
-        if let Ok(length) = Length::from_str(s) {
-            return Ok(LengthOrPercentageOrNumber::Length(length));
-        }
-
-        if let Ok(number) = f64::from_str(s) {
-            return Ok(LengthOrPercentageOrNumber::Number(number));
-        }
-
-        Err(())
-    }
-}
@@ -6,14 +6,13 @@ where
     I: Iterator,
     I::Item: fmt::Display,
 {
-    write!(f, "=\"")?;
     for (i, item) in iter.enumerate() {
         if i > 0 {
             write!(f, ";")?;
         }
         write!(f, "{}", item)?;
     }
-    write!(f, "\"")
+    Ok(())
 }
 
 pub fn write_space_separated<W, I>(f: &mut W, iter: I) -> fmt::Result
@@ -22,14 +21,13 @@ where
     I: Iterator,
     I::Item: fmt::Display,
 {
-    write!(f, "=\"")?;
     for (i, item) in iter.enumerate() {
         if i > 0 {
             write!(f, " ")?;
         }
         write!(f, "{}", item)?;
     }
-    write!(f, "\"")
+    Ok(())
 }
 
 pub fn write_comma_separated<W, I>(f: &mut W, iter: I) -> fmt::Result
@@ -38,12 +36,11 @@ where
     I: Iterator,
     I::Item: fmt::Display,
 {
-    write!(f, "=\"")?;
     for (i, item) in iter.enumerate() {
         if i > 0 {
             write!(f, ",")?;
         }
         write!(f, "{}", item)?;
     }
-    write!(f, "\"")
+    Ok(())
 }
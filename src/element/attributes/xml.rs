@@ -0,0 +1,36 @@
+use std::{fmt, str::FromStr};
+
+/// The value of `xml:space`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum XmlSpace {
+    #[default]
+    Default,
+    Preserve,
+}
+
+impl XmlSpace {
+    pub fn as_str(&self) -> &str {
+        match self {
+            XmlSpace::Default => "default",
+            XmlSpace::Preserve => "preserve",
+        }
+    }
+}
+
+impl FromStr for XmlSpace {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "default" => Ok(XmlSpace::Default),
+            "preserve" => Ok(XmlSpace::Preserve),
+            _ => Err(()),
+        }
+    }
+}
+
+impl fmt::Display for XmlSpace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
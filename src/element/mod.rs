@@ -1,10 +1,12 @@
 use crate::parser::ast::{AST, NodeId};
+use crate::serialize::{SerializeOptions, Serializer};
 use std::{
     fmt::{self, Display},
     str::FromStr,
 };
 
-use crate::element::attributes::Attribute;
+use crate::element::attributes::{Attribute, WhiteSpace, XmlSpace};
+use crate::namespace::QualifiedName;
 
 pub mod attributes;
 pub mod lang;
@@ -13,6 +15,10 @@ pub mod types;
 #[derive(Clone, Debug, PartialEq)]
 pub struct Element {
     pub element_type: ElementType,
+    /// The namespace prefix of an SVG element written as e.g. `<svg:rect>`.
+    ///
+    /// Always `None` for [`ElementType::Foreign`], whose name already includes it.
+    pub prefix: Option<String>,
     pub attributes: Vec<Attribute>,
     pub children: Vec<NodeId>,
     pub parent: Option<NodeId>,
@@ -20,7 +26,177 @@ pub struct Element {
 
 impl Element {
     pub fn is_allowed_as_child(&self, element: &ElementType) -> bool {
-        match self.element_type {
+        self.element_type.allows_child(element)
+    }
+
+    /// Writes the element name, including its prefix.
+    pub(crate) fn write_name(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        if let Some(prefix) = &self.prefix {
+            write!(f, "{}:", prefix)?;
+        }
+        write!(f, "{}", self.element_type)
+    }
+
+    /// Serializes this element and its children.
+    pub fn write_svg(
+        &self,
+        ast: &AST,
+        f: &mut impl fmt::Write,
+        options: &SerializeOptions,
+    ) -> fmt::Result {
+        Serializer::new(ast, options, f).write_element(None, self, 0)?;
+        match options.minify {
+            true => Ok(()),
+            false => writeln!(f),
+        }
+    }
+
+    /// Returns whether the `white-space` property or `xml:space` of this element
+    /// keeps whitespace as written, or `None` if it sets neither.
+    ///
+    /// `white-space` in `style` takes precedence over the attribute, and both
+    /// over `xml:space`.
+    pub fn preserves_whitespace(&self) -> Option<bool> {
+        let declared = self
+            .attributes
+            .iter()
+            .find_map(|attribute| match attribute {
+                Attribute::Style(style) => style
+                    .split(';')
+                    .filter_map(|declaration| declaration.split_once(':'))
+                    .find(|(name, _)| name.trim() == "white-space")
+                    .and_then(|(_, value)| value.trim().parse::<WhiteSpace>().ok()),
+                _ => None,
+            });
+        let white_space = declared.or_else(|| {
+            self.attributes
+                .iter()
+                .find_map(|attribute| match attribute {
+                    Attribute::WhiteSpace(white_space) => Some(white_space.clone()),
+                    _ => None,
+                })
+        });
+
+        if let Some(white_space) = white_space {
+            return Some(!matches!(
+                white_space,
+                WhiteSpace::Normal | WhiteSpace::Nowrap
+            ));
+        }
+
+        self.attributes
+            .iter()
+            .find_map(|attribute| match attribute {
+                Attribute::XmlSpace(space) => Some(*space == XmlSpace::Preserve),
+                _ => None,
+            })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ElementType {
+    // Animation Elements
+    Animate,
+    AnimateMotion,
+    AnimateTransform,
+    MPath,
+    Set,
+
+    // Basic Shapes
+    Circle,
+    Ellipse,
+    Line,
+    Polygon,
+    PolyLine,
+    Rect,
+
+    // Container Elements
+    A,
+    Defs,
+    G,
+    Marker,
+    Mask,
+    Pattern,
+    Svg,
+    Switch,
+    Symbol,
+
+    // Descriptive Elements
+    Desc,
+    Metadata,
+    Title,
+
+    // Filter primitive Elements
+    FeBlend,
+    FeColorMatrix,
+    FeComponentTransfer,
+    FeComposite,
+    FeConvolveMatrix,
+    FeDiffuseLightning,
+    FeDisplacementMap,
+    FeDropShadow,
+    FeFlood,
+    FeFuncA,
+    FeFuncB,
+    FeFuncG,
+    FeFuncR,
+    FeGaussianBlur,
+    FeImage,
+    FeMerge,
+    FeMergeNode,
+    FeMorphology,
+    FeOffset,
+    FeSpecularLighting,
+    FeTile,
+    FeTurbulence,
+
+    // Gradient Elements
+    LinearGradient,
+    RadialGradient,
+    Stop,
+
+    // Graphics Elements
+    Image,
+    Path,
+    Text,
+    Use,
+
+    // Light Source Elements
+    FeDistantLight,
+    FePointLight,
+    FeSpotLight,
+
+    // Never rendered elements
+    ClipPath,
+    Script,
+    Style,
+
+    // Text Content Elements
+    TextPath,
+    TSpan,
+
+    // Uncategorized Elements
+    Filter,
+    ForeignObject,
+    View,
+
+    /// An element from a namespace other than SVG, e.g. `sodipodi:namedview`.
+    Foreign(Box<QualifiedName>),
+
+    /// An SVG element this crate does not know, such as one from a newer spec.
+    Unknown(String),
+}
+
+impl ElementType {
+    /// Returns `true` if an element of this type may contain `element`.
+    pub fn allows_child(&self, element: &ElementType) -> bool {
+        // elements from other namespaces and unknown elements are ignored by
+        // renderers, so they may appear anywhere
+        if matches!(element, ElementType::Foreign(_) | ElementType::Unknown(_)) {
+            return true;
+        }
+
+        match self {
             ElementType::Animate => element.is_descriptive(),
             ElementType::AnimateMotion => {
                 element.is_descriptive() || matches!(element, ElementType::MPath)
@@ -414,146 +590,10 @@ impl Element {
             }
             ElementType::ForeignObject => true,
             ElementType::View => element.is_descriptive(),
+            ElementType::Foreign(_) | ElementType::Unknown(_) => true,
         }
     }
 
-    pub fn write_svg(&self, ast: &AST, f: &mut impl fmt::Write, indent: usize) -> fmt::Result {
-        for _ in 0..indent {
-            write!(f, "  ")?;
-        }
-
-        write!(f, "<{}", self.element_type)?;
-
-        let mut attrs = self.attributes.iter().peekable();
-        if attrs.peek().is_some() {
-            write!(f, " ")?;
-        }
-
-        while let Some(attr) = attrs.next() {
-            attr.write_svg(f)?;
-            if attrs.peek().is_some() {
-                write!(f, " ")?;
-            }
-        }
-
-        if self.children.is_empty() {
-            return write!(f, "/>");
-        }
-
-        write!(f, ">\n")?;
-
-        // NOTE(@bleksak): If this crashes some day, we need to rewrite it without recursion
-        let mut children = self.children.iter().peekable();
-
-        while let Some(child_id) = children.next() {
-            let Some(child) = ast.nodes.get(*child_id) else {
-                continue;
-            };
-
-            child.write_svg(ast, f, indent + 1)?;
-
-            if children.peek().is_some() {
-                write!(f, "\n")?;
-            }
-        }
-
-        for _ in 0..indent {
-            write!(f, "  ")?;
-        }
-
-        write!(f, "</{}>\n", self.element_type)
-    }
-}
-
-#[derive(Clone, Copy, Debug, PartialEq)]
-pub enum ElementType {
-    // Animation Elements
-    Animate,
-    AnimateMotion,
-    AnimateTransform,
-    MPath,
-    Set,
-
-    // Basic Shapes
-    Circle,
-    Ellipse,
-    Line,
-    Polygon,
-    PolyLine,
-    Rect,
-
-    // Container Elements
-    A,
-    Defs,
-    G,
-    Marker,
-    Mask,
-    Pattern,
-    Svg,
-    Switch,
-    Symbol,
-
-    // Descriptive Elements
-    Desc,
-    Metadata,
-    Title,
-
-    // Filter primitive Elements
-    FeBlend,
-    FeColorMatrix,
-    FeComponentTransfer,
-    FeComposite,
-    FeConvolveMatrix,
-    FeDiffuseLightning,
-    FeDisplacementMap,
-    FeDropShadow,
-    FeFlood,
-    FeFuncA,
-    FeFuncB,
-    FeFuncG,
-    FeFuncR,
-    FeGaussianBlur,
-    FeImage,
-    FeMerge,
-    FeMergeNode,
-    FeMorphology,
-    FeOffset,
-    FeSpecularLighting,
-    FeTile,
-    FeTurbulence,
-
-    // Gradient Elements
-    LinearGradient,
-    RadialGradient,
-    Stop,
-
-    // Graphics Elements
-    Image,
-    Path,
-    Text,
-    Use,
-
-    // Light Source Elements
-    FeDistantLight,
-    FePointLight,
-    FeSpotLight,
-
-    // Never rendered elements
-    ClipPath,
-    Script,
-    Style,
-
-    // Text Content Elements
-    TextPath,
-    TSpan,
-
-    // Uncategorized Elements
-    Filter,
-    ForeignObject,
-    View,
-}
-
-impl ElementType {
     pub fn is_animation(&self) -> bool {
         matches!(
             self,
@@ -565,6 +605,15 @@ impl ElementType {
         )
     }
 
+    /// Returns `true` for elements whose text is rendered: `<text>`, `<tspan>`
+    /// and `<textPath>`.
+    pub fn is_text_content(&self) -> bool {
+        matches!(
+            self,
+            ElementType::Text | ElementType::TSpan | ElementType::TextPath
+        )
+    }
+
     pub fn is_descriptive(&self) -> bool {
         matches!(
             self,
@@ -707,6 +756,8 @@ impl ElementType {
             Self::Filter => "filter",
             Self::ForeignObject => "foreignObject",
             Self::View => "view",
+            Self::Foreign(name) => &name.name,
+            Self::Unknown(name) => name,
         }
     }
 }
@@ -1,431 +1,6 @@
 use std::fmt;
 use std::str::FromStr;
 
-#[derive(Clone, Debug, PartialEq)]
-pub enum RelativeLength {
-    Cap(f64),
-    Em(f64),
-    Ex(f64),
-    Ic(f64),
-    Lh(f64),
-}
-
-impl fmt::Display for RelativeLength {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            RelativeLength::Cap(c) => write!(f, "{}cap", c),
-            RelativeLength::Em(e) => write!(f, "{}em", e),
-            RelativeLength::Ex(e) => write!(f, "{}ex", e),
-            RelativeLength::Ic(i) => write!(f, "{}ic", i),
-            RelativeLength::Lh(l) => write!(f, "{}lh", l),
-        }
-    }
-}
-
-impl FromStr for RelativeLength {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            _ if s.eq_ignore_ascii_case("cap") => Ok(Self::Cap(1.0)),
-            _ if s.eq_ignore_ascii_case("em") => Ok(Self::Em(1.0)),
-            _ if s.eq_ignore_ascii_case("ex") => Ok(Self::Ex(1.0)),
-            _ if s.eq_ignore_ascii_case("ic") => Ok(Self::Ic(1.0)),
-            _ if s.eq_ignore_ascii_case("lh") => Ok(Self::Lh(1.0)),
-            _ => Err(()),
-        }
-    }
-}
-
-#[derive(Clone, Debug, PartialEq)]
-pub enum RelativeLengthBasedOnRoot {
-    Rcap(f64),
-    Rch(f64),
-    Rem(f64),
-    Rex(f64),
-    Ric(f64),
-    Rlh(f64),
-}
-
-impl fmt::Display for RelativeLengthBasedOnRoot {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            RelativeLengthBasedOnRoot::Rcap(c) => write!(f, "{}rcap", c),
-            RelativeLengthBasedOnRoot::Rch(c) => write!(f, "{}rch", c),
-            RelativeLengthBasedOnRoot::Rem(r) => write!(f, "{}rem", r),
-            RelativeLengthBasedOnRoot::Rex(r) => write!(f, "{}rex", r),
-            RelativeLengthBasedOnRoot::Ric(r) => write!(f, "{}ric", r),
-            RelativeLengthBasedOnRoot::Rlh(r) => write!(f, "{}rlh", r),
-        }
-    }
-}
-
-impl FromStr for RelativeLengthBasedOnRoot {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            _ if s.eq_ignore_ascii_case("rcap") => Ok(Self::Rcap(1.0)),
-            _ if s.eq_ignore_ascii_case("rch") => Ok(Self::Rch(1.0)),
-            _ if s.eq_ignore_ascii_case("rem") => Ok(Self::Rem(1.0)),
-            _ if s.eq_ignore_ascii_case("rex") => Ok(Self::Rex(1.0)),
-            _ if s.eq_ignore_ascii_case("ric") => Ok(Self::Ric(1.0)),
-            _ if s.eq_ignore_ascii_case("rlh") => Ok(Self::Rlh(1.0)),
-            _ => Err(()),
-        }
-    }
-}
-
-#[derive(Clone, Debug, PartialEq)]
-pub enum AbsoluteLength {
-    Px(f64), // for print 1px = 1/96th of an inch
-    Cm(f64), // 1cm = 96px / 2.54
-    Q(f64),  // 1q = 1cm / 40
-    In(f64), // 1in = 96px
-    Mm(f64), // 1mm = 1cm / 10
-    Pt(f64), // 1pt = 1/72th of an inch
-    Pc(f64), // 1pc = 1/6th of an inch
-}
-
-impl fmt::Display for AbsoluteLength {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            AbsoluteLength::Px(p) => write!(f, "{}px", p),
-            AbsoluteLength::Cm(c) => write!(f, "{}cm", c),
-            AbsoluteLength::Q(q) => write!(f, "{}q", q),
-            AbsoluteLength::In(i) => write!(f, "{}in", i),
-            AbsoluteLength::Mm(m) => write!(f, "{}mm", m),
-            AbsoluteLength::Pt(p) => write!(f, "{}pt", p),
-            AbsoluteLength::Pc(p) => write!(f, "{}pc", p),
-        }
-    }
-}
-
-impl FromStr for AbsoluteLength {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            _ if s.eq_ignore_ascii_case("px") => Ok(Self::Px(1.0)),
-            _ if s.eq_ignore_ascii_case("cm") => Ok(Self::Cm(1.0)),
-            _ if s.eq_ignore_ascii_case("q") => Ok(Self::Q(1.0)),
-            _ if s.eq_ignore_ascii_case("in") => Ok(Self::In(1.0)),
-            _ if s.eq_ignore_ascii_case("mm") => Ok(Self::Mm(1.0)),
-            _ if s.eq_ignore_ascii_case("pt") => Ok(Self::Pt(1.0)),
-            _ if s.eq_ignore_ascii_case("pc") => Ok(Self::Pc(1.0)),
-            _ => Err(()),
-        }
-    }
-}
-
-#[derive(Clone, Debug, PartialEq)]
-pub enum RelativeLengths {
-    RelativeLength(RelativeLength),
-    RelativeLengthBasedOnRoot(RelativeLengthBasedOnRoot),
-}
-
-impl fmt::Display for RelativeLengths {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            RelativeLengths::RelativeLength(r) => write!(f, "{}", r),
-            RelativeLengths::RelativeLengthBasedOnRoot(r) => write!(f, "{}", r),
-        }
-    }
-}
-
-impl FromStr for RelativeLengths {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if let Ok(relative) = RelativeLength::from_str(s) {
-            return Ok(RelativeLengths::RelativeLength(relative));
-        }
-
-        if let Ok(relative) = RelativeLengthBasedOnRoot::from_str(s) {
-            return Ok(RelativeLengths::RelativeLengthBasedOnRoot(relative));
-        }
-
-        return Err(());
-    }
-}
-
-#[derive(Clone, Debug, PartialEq)]
-pub enum Length {
-    Absolute(AbsoluteLength),
-    Relative(RelativeLengths),
-}
-
-impl fmt::Display for Length {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Length::Absolute(a) => write!(f, "{}", a),
-            Length::Relative(r) => write!(f, "{}", r),
-        }
-    }
-}
-
-impl FromStr for Length {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if let Ok(absolute) = AbsoluteLength::from_str(s) {
-            return Ok(Length::Absolute(absolute));
-        }
-
-        if let Ok(relative) = RelativeLengths::from_str(s) {
-            return Ok(Length::Relative(relative));
-        }
-
-        if s.chars().all(|c| c.is_ascii_digit() || c == '-') {
-            return Ok(Length::Absolute(AbsoluteLength::Px(
-                s.parse::<f64>().map_err(|_| ())?,
-            )));
-        }
-
-        return Err(());
-    }
-}
-
-#[derive(Clone, Debug, PartialEq)]
-pub struct Percentage(pub f64);
-
-impl fmt::Display for Percentage {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}%", self.0)
-    }
-}
-
-impl FromStr for Percentage {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.ends_with('%') {
-            let (value, rhs) = s.split_once('%').ok_or(())?;
-
-            if rhs.is_empty() {
-                return Ok(Percentage(value.parse::<f64>().map_err(|_| ())?));
-            }
-        }
-
-        Err(())
-    }
-}
-
-#[derive(Clone, Debug, PartialEq)]
-pub enum LengthOrPercentage {
-    Length(Length),
-    Percentage(Percentage),
-}
-
-impl fmt::Display for LengthOrPercentage {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            LengthOrPercentage::Length(l) => write!(f, "{}", l),
-            LengthOrPercentage::Percentage(p) => write!(f, "{}", p),
-        }
-    }
-}
-
-impl Default for LengthOrPercentage {
-    fn default() -> Self {
-        LengthOrPercentage::Length(Length::Absolute(AbsoluteLength::Px(0.0)))
-    }
-}
-
-impl FromStr for LengthOrPercentage {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Self::try_from(s)
-    }
-}
-
-impl TryFrom<&str> for RelativeLength {
-    type Error = ();
-
-    fn try_from(input: &str) -> Result<Self, Self::Error> {
-        if let Some(num_str) = input.strip_suffix("cap") {
-            num_str
-                .trim()
-                .parse::<f64>()
-                .map_err(|_| ())
-                .map(RelativeLength::Cap)
-        } else if let Some(num_str) = input.strip_suffix("em") {
-            num_str
-                .trim()
-                .parse::<f64>()
-                .map_err(|_| ())
-                .map(RelativeLength::Em)
-        } else if let Some(num_str) = input.strip_suffix("ex") {
-            num_str
-                .trim()
-                .parse::<f64>()
-                .map_err(|_| ())
-                .map(RelativeLength::Ex)
-        } else if let Some(num_str) = input.strip_suffix("ic") {
-            num_str
-                .trim()
-                .parse::<f64>()
-                .map_err(|_| ())
-                .map(RelativeLength::Ic)
-        } else if let Some(num_str) = input.strip_suffix("lh") {
-            num_str
-                .trim()
-                .parse::<f64>()
-                .map_err(|_| ())
-                .map(RelativeLength::Lh)
-        } else {
-            Err(())
-        }
-    }
-}
-
-impl TryFrom<&str> for RelativeLengthBasedOnRoot {
-    type Error = ();
-
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
-        if let Some(num_str) = value.strip_suffix("rcap") {
-            num_str
-                .trim()
-                .parse::<f64>()
-                .map_err(|_| ())
-                .map(RelativeLengthBasedOnRoot::Rcap)
-        } else if let Some(num_str) = value.strip_suffix("rch") {
-            num_str
-                .trim()
-                .parse::<f64>()
-                .map_err(|_| ())
-                .map(RelativeLengthBasedOnRoot::Rch)
-        } else if let Some(num_str) = value.strip_suffix("rem") {
-            num_str
-                .trim()
-                .parse::<f64>()
-                .map_err(|_| ())
-                .map(RelativeLengthBasedOnRoot::Rem)
-        } else if let Some(num_str) = value.strip_suffix("rex") {
-            num_str
-                .trim()
-                .parse::<f64>()
-                .map_err(|_| ())
-                .map(RelativeLengthBasedOnRoot::Rex)
-        } else if let Some(num_str) = value.strip_suffix("ric") {
-            num_str
-                .trim()
-                .parse::<f64>()
-                .map_err(|_| ())
-                .map(RelativeLengthBasedOnRoot::Ric)
-        } else if let Some(num_str) = value.strip_suffix("rlh") {
-            num_str
-                .trim()
-                .parse::<f64>()
-                .map_err(|_| ())
-                .map(RelativeLengthBasedOnRoot::Rlh)
-        } else {
-            Err(())
-        }
-    }
-}
-
-impl TryFrom<&str> for AbsoluteLength {
-    type Error = ();
-
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
-        if let Some(num_str) = value.strip_suffix("px") {
-            num_str
-                .trim()
-                .parse::<f64>()
-                .map_err(|_| ())
-                .map(AbsoluteLength::Px)
-        } else if let Some(num_str) = value.strip_suffix("cm") {
-            num_str
-                .trim()
-                .parse::<f64>()
-                .map_err(|_| ())
-                .map(AbsoluteLength::Cm)
-        } else if let Some(num_str) = value.strip_suffix("q") {
-            num_str
-                .trim()
-                .parse::<f64>()
-                .map_err(|_| ())
-                .map(AbsoluteLength::Q)
-        } else if let Some(num_str) = value.strip_suffix("in") {
-            num_str
-                .trim()
-                .parse::<f64>()
-                .map_err(|_| ())
-                .map(AbsoluteLength::In)
-        } else if let Some(num_str) = value.strip_suffix("mm") {
-            num_str
-                .trim()
-                .parse::<f64>()
-                .map_err(|_| ())
-                .map(AbsoluteLength::Mm)
-        } else if let Some(num_str) = value.strip_suffix("pt") {
-            num_str
-                .trim()
-                .parse::<f64>()
-                .map_err(|_| ())
-                .map(AbsoluteLength::Pt)
-        } else if let Some(num_str) = value.strip_suffix("pc") {
-            num_str
-                .trim()
-                .parse::<f64>()
-                .map_err(|_| ())
-                .map(AbsoluteLength::Pc)
-        } else {
-            Err(())
-        }
-    }
-}
-
-impl TryFrom<&str> for RelativeLengths {
-    type Error = ();
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
-        if let Ok(relative_length) = RelativeLength::try_from(value) {
-            Ok(RelativeLengths::RelativeLength(relative_length))
-        } else if let Ok(relative_length) = RelativeLengthBasedOnRoot::try_from(value) {
-            Ok(RelativeLengths::RelativeLengthBasedOnRoot(relative_length))
-        } else {
-            Err(())
-        }
-    }
-}
-
-impl TryFrom<&str> for Length {
-    type Error = ();
-
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
-        if let Ok(absolute_length) = AbsoluteLength::try_from(value) {
-            Ok(Length::Absolute(absolute_length))
-        } else if let Ok(relative_length) = RelativeLengths::try_from(value) {
-            Ok(Length::Relative(relative_length))
-        } else {
-            if value.chars().all(|c| c.is_ascii_digit() || c == '-') {
-                return Ok(Length::Absolute(AbsoluteLength::Px(
-                    value.parse::<f64>().map_err(|_| ())?,
-                )));
-            }
-            Err(())
-        }
-    }
-}
-
-impl TryFrom<&str> for LengthOrPercentage {
-    type Error = ();
-
-    fn try_from(s: &str) -> Result<Self, Self::Error> {
-        if let Ok(length) = Length::try_from(s) {
-            return Ok(LengthOrPercentage::Length(length));
-        }
-
-        if let Ok(percentage) = s.parse() {
-            return Ok(LengthOrPercentage::Percentage(percentage));
-        }
-
-        return Err(());
-    }
-}
-
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum ColorLiteral {
     Maroon,
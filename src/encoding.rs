@@ -0,0 +1,213 @@
+//! Detection of the source encoding and conversion to UTF-8.
+//!
+//! The lexer works on UTF-8 text. [`decode`] looks at the byte order mark and
+//! the `encoding` of the XML declaration, validates UTF-8 sources and transcodes
+//! UTF-16 and ISO-8859-1 ones.
+
+use std::{borrow::Cow, fmt, ops::Range};
+
+use crate::{
+    error::{ParseError, ParseErrorKind},
+    parser::prolog,
+};
+
+/// A character encoding supported by [`decode`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Encoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    /// ISO-8859-1, also known as Latin-1.
+    Latin1,
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Encoding::Utf8 => write!(f, "UTF-8"),
+            Encoding::Utf16Le => write!(f, "UTF-16LE"),
+            Encoding::Utf16Be => write!(f, "UTF-16BE"),
+            Encoding::Latin1 => write!(f, "ISO-8859-1"),
+        }
+    }
+}
+
+/// Converts raw source bytes to UTF-8 text, without the byte order mark.
+///
+/// UTF-8 sources are borrowed. Spans of a transcoded source refer to the
+/// returned text rather than to `bytes`.
+pub fn decode(bytes: &[u8]) -> Result<Cow<'_, str>, ParseError> {
+    let (encoding, bom) = detect(bytes)?;
+    let bytes = &bytes[bom..];
+
+    match encoding {
+        Encoding::Utf8 => std::str::from_utf8(bytes)
+            .map(Cow::Borrowed)
+            .map_err(|error| {
+                let start = bom + error.valid_up_to();
+                let end = start
+                    + error
+                        .error_len()
+                        .unwrap_or(bytes.len() - error.valid_up_to());
+                invalid(encoding, start..end)
+            }),
+        Encoding::Utf16Le | Encoding::Utf16Be => {
+            if !bytes.len().is_multiple_of(2) {
+                return Err(invalid(encoding, bom + bytes.len() - 1..bom + bytes.len()));
+            }
+
+            let units = bytes.chunks_exact(2).map(|pair| match encoding {
+                Encoding::Utf16Le => u16::from_le_bytes([pair[0], pair[1]]),
+                _ => u16::from_be_bytes([pair[0], pair[1]]),
+            });
+
+            let mut output = String::with_capacity(bytes.len() / 2);
+            let mut offset = bom;
+            for c in char::decode_utf16(units) {
+                let c = c.map_err(|_| invalid(encoding, offset..offset + 2))?;
+                output.push(c);
+                offset += c.len_utf16() * 2;
+            }
+
+            Ok(Cow::Owned(output))
+        }
+        Encoding::Latin1 => match std::str::from_utf8(bytes) {
+            Ok(text) if text.is_ascii() => Ok(Cow::Borrowed(text)),
+            _ => Ok(Cow::Owned(bytes.iter().map(|&b| char::from(b)).collect())),
+        },
+    }
+}
+
+/// Returns `true` if `name` is a label of UTF-8, the encoding of serialized output.
+pub(crate) fn is_utf8_label(name: &str) -> bool {
+    name.eq_ignore_ascii_case("utf-8") || name.eq_ignore_ascii_case("utf8")
+}
+
+/// Returns the encoding of `bytes` and the length of its byte order mark.
+pub(crate) fn detect(bytes: &[u8]) -> Result<(Encoding, usize), ParseError> {
+    match bytes {
+        [0xEF, 0xBB, 0xBF, ..] => return Ok((Encoding::Utf8, 3)),
+        [0xFF, 0xFE, ..] => return Ok((Encoding::Utf16Le, 2)),
+        [0xFE, 0xFF, ..] => return Ok((Encoding::Utf16Be, 2)),
+        // `<` without a byte order mark
+        [b'<', 0, ..] => return Ok((Encoding::Utf16Le, 0)),
+        [0, b'<', ..] => return Ok((Encoding::Utf16Be, 0)),
+        _ => {}
+    }
+
+    let Some((name, span)) = declared_encoding(bytes) else {
+        return Ok((Encoding::Utf8, 0));
+    };
+
+    match name.to_ascii_lowercase().as_str() {
+        // a UTF-16 file would not have a readable declaration, so it was
+        // converted without updating it
+        "utf-8" | "utf8" | "us-ascii" | "ascii" | "utf-16" | "utf16" => Ok((Encoding::Utf8, 0)),
+        "iso-8859-1" | "iso8859-1" | "latin1" | "latin-1" | "l1" => Ok((Encoding::Latin1, 0)),
+        _ => Err(ParseError::new(
+            ParseErrorKind::UnsupportedEncoding(name),
+            span,
+        )),
+    }
+}
+
+/// Returns the `encoding` of an ASCII XML declaration and the span of the declaration.
+fn declared_encoding(bytes: &[u8]) -> Option<(String, Range<usize>)> {
+    if !bytes.starts_with(b"<?xml") {
+        return None;
+    }
+
+    let end = memchr::memmem::find(bytes, b"?>")? + 2;
+    let declaration = std::str::from_utf8(&bytes[..end]).ok()?;
+
+    Some((prolog::xml_declaration(declaration)?.encoding?, 0..end))
+}
+
+fn invalid(encoding: Encoding, span: Range<usize>) -> ParseError {
+    ParseError::new(ParseErrorKind::InvalidEncoding(encoding), span)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf16(text: &str, big_endian: bool) -> Vec<u8> {
+        text.encode_utf16()
+            .flat_map(|unit| match big_endian {
+                true => unit.to_be_bytes(),
+                false => unit.to_le_bytes(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn decode_should_borrow_utf8_and_strip_bom() {
+        let source = "\u{FEFF}<svg>žluť</svg>";
+
+        let decoded = decode(source.as_bytes()).unwrap();
+        assert!(matches!(decoded, Cow::Borrowed(_)));
+        assert_eq!(decoded, "<svg>žluť</svg>");
+    }
+
+    #[test]
+    fn decode_should_reject_invalid_utf8() {
+        let error = decode(b"<svg>\xE9</svg>").unwrap_err();
+
+        assert_eq!(error.kind, ParseErrorKind::InvalidEncoding(Encoding::Utf8));
+        assert_eq!(error.span, 5..6);
+    }
+
+    #[test]
+    fn decode_should_transcode_utf16() {
+        let source = r#"<?xml version="1.0" encoding="UTF-16"?><svg>žluť</svg>"#;
+
+        let mut little_endian = vec![0xFF, 0xFE];
+        little_endian.extend(utf16(source, false));
+        assert_eq!(decode(&little_endian).unwrap(), source);
+
+        let mut big_endian = vec![0xFE, 0xFF];
+        big_endian.extend(utf16(source, true));
+        assert_eq!(decode(&big_endian).unwrap(), source);
+
+        // without a byte order mark, or a declaration
+        assert_eq!(decode(&utf16(source, false)).unwrap(), source);
+        assert_eq!(decode(&utf16("<svg/>", false)).unwrap(), "<svg/>");
+        assert_eq!(decode(&utf16("<svg/>", true)).unwrap(), "<svg/>");
+    }
+
+    #[test]
+    fn decode_should_reject_unpaired_surrogates() {
+        let mut bytes = vec![0xFF, 0xFE];
+        bytes.extend(utf16("<svg>", false));
+        bytes.extend(0xD800u16.to_le_bytes());
+
+        let error = decode(&bytes).unwrap_err();
+        assert_eq!(
+            error.kind,
+            ParseErrorKind::InvalidEncoding(Encoding::Utf16Le)
+        );
+        assert_eq!(error.span, 12..14);
+    }
+
+    #[test]
+    fn decode_should_transcode_latin1() {
+        let decoded =
+            decode(b"<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?><text>caf\xE9</text>").unwrap();
+
+        assert_eq!(
+            decoded,
+            r#"<?xml version="1.0" encoding="ISO-8859-1"?><text>café</text>"#
+        );
+    }
+
+    #[test]
+    fn decode_should_reject_unsupported_encodings() {
+        let error = decode(br#"<?xml version="1.0" encoding="Shift_JIS"?><svg/>"#).unwrap_err();
+
+        assert_eq!(
+            error.kind,
+            ParseErrorKind::UnsupportedEncoding("Shift_JIS".to_string())
+        );
+        assert_eq!(error.span, 0..42);
+    }
+}
@@ -0,0 +1,341 @@
+//! XML character and entity references.
+//!
+//! Text and attribute values are decoded when parsed (`&amp;` becomes `&`,
+//! `&#x41;` becomes `A`) and escaped again when serialized. Besides the five
+//! predefined entities, general entities declared in a DOCTYPE internal subset
+//! (`<!ENTITY ns_svg "http://www.w3.org/2000/svg">`) are supported.
+
+use std::{borrow::Cow, collections::HashMap};
+
+/// Maximum number of bytes that declared entities may expand to in one document.
+///
+/// Guards against amplification, both from nested declarations ("billion
+/// laughs") and from one large entity referenced many times.
+pub const MAX_EXPANSION_BYTES: usize = 8 * 1024 * 1024;
+
+/// Maximum nesting of entities referencing other entities, which also stops recursion.
+const MAX_DEPTH: usize = 16;
+
+/// The entities that can be referenced from a document.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Entities {
+    /// Entities declared in the DOCTYPE internal subset, with their replacement text.
+    declared: HashMap<String, String>,
+}
+
+impl Entities {
+    /// Collects the general entities declared in a DOCTYPE internal subset.
+    ///
+    /// Parameter entities (`<!ENTITY % name ...>`) and external entities
+    /// (`SYSTEM` / `PUBLIC`) are ignored.
+    pub fn from_internal_subset(subset: &str) -> Self {
+        let mut entities = Self::default();
+        let mut rest = subset;
+
+        while let Some(start) = rest.find('<') {
+            rest = &rest[start..];
+
+            if let Some(comment) = rest.strip_prefix("<!--") {
+                rest = comment.split_once("-->").map_or("", |(_, after)| after);
+                continue;
+            }
+
+            let Some(declaration) = rest.strip_prefix("<!ENTITY") else {
+                rest = &rest[1..];
+                continue;
+            };
+
+            rest = match parse_entity_declaration(declaration) {
+                Some((name, value, after)) => {
+                    entities.declare(name, value);
+                    after
+                }
+                None => declaration,
+            };
+        }
+
+        entities
+    }
+
+    /// Declares an entity. As in XML, the first declaration of a name wins.
+    pub fn declare(&mut self, name: &str, value: &str) {
+        self.declared
+            .entry(name.to_string())
+            .or_insert_with(|| value.to_string());
+    }
+
+    /// Returns the replacement text of a declared or predefined entity.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        predefined(name).or_else(|| self.declared.get(name).map(String::as_str))
+    }
+
+    /// Replaces all character and entity references in `input`.
+    ///
+    /// Returns the offending reference (e.g. `&foo;`) if it is unknown or
+    /// malformed, or if declared entities expand to more than
+    /// [`MAX_EXPANSION_BYTES`].
+    pub fn decode<'a>(&self, input: &'a str) -> Result<Cow<'a, str>, String> {
+        let mut budget = MAX_EXPANSION_BYTES;
+
+        self.decode_within(input, &mut budget)
+            .map_err(|error| match error {
+                DecodeError::InvalidReference(reference) | DecodeError::TooLarge(reference) => {
+                    reference
+                }
+            })
+    }
+
+    /// Replaces all character and entity references in `input`, taking the
+    /// bytes declared entities expand to from `budget`.
+    ///
+    /// The budget is shared by all values of a document, so that expansions
+    /// add up across them.
+    pub(crate) fn decode_within<'a>(
+        &self,
+        input: &'a str,
+        budget: &mut usize,
+    ) -> Result<Cow<'a, str>, DecodeError> {
+        if !input.contains('&') {
+            return Ok(Cow::Borrowed(input));
+        }
+
+        let mut output = String::with_capacity(input.len());
+        self.decode_into(input, &mut output, budget, 0)?;
+
+        Ok(Cow::Owned(output))
+    }
+
+    fn decode_into(
+        &self,
+        input: &str,
+        output: &mut String,
+        budget: &mut usize,
+        depth: usize,
+    ) -> Result<(), DecodeError> {
+        let mut rest = input;
+
+        while let Some(start) = rest.find('&') {
+            output.push_str(&rest[..start]);
+            rest = &rest[start..];
+
+            let Some(end) = rest.find(';') else {
+                return Err(DecodeError::InvalidReference(rest.to_string()));
+            };
+            let reference = &rest[..=end];
+            let name = &reference[1..end];
+
+            if let Some(number) = name.strip_prefix('#') {
+                let code = match number.strip_prefix(['x', 'X']) {
+                    Some(hex) => u32::from_str_radix(hex, 16),
+                    None => number.parse(),
+                };
+
+                match code.ok().and_then(char::from_u32) {
+                    Some(c) if c != '\0' => output.push(c),
+                    _ => return Err(DecodeError::InvalidReference(reference.to_string())),
+                }
+            } else if let Some(c) = predefined(name) {
+                output.push_str(c);
+            } else if let Some(value) = self.declared.get(name) {
+                if depth == MAX_DEPTH {
+                    return Err(DecodeError::InvalidReference(reference.to_string()));
+                }
+                // the replacement text is charged before it is expanded, so
+                // nested references cannot grow the output past the budget
+                *budget = budget
+                    .checked_sub(value.len())
+                    .ok_or_else(|| DecodeError::TooLarge(reference.to_string()))?;
+
+                self.decode_into(value, output, budget, depth + 1)?;
+            } else {
+                return Err(DecodeError::InvalidReference(reference.to_string()));
+            }
+
+            rest = &rest[end + 1..];
+        }
+
+        output.push_str(rest);
+
+        Ok(())
+    }
+}
+
+/// Why a value could not be decoded, with the offending reference.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum DecodeError {
+    /// An unknown or malformed reference, such as `&foo;`.
+    InvalidReference(String),
+    /// A reference that expands past the remaining budget of bytes.
+    TooLarge(String),
+}
+
+/// Replaces character references and predefined entities in `input`.
+pub fn decode(input: &str) -> Result<Cow<'_, str>, String> {
+    Entities::default().decode(input)
+}
+
+/// Escapes `&`, `<` and `>` for use as text content.
+pub fn escape_text(input: &str) -> Cow<'_, str> {
+    escape(input, |c| match c {
+        '&' => Some("&amp;"),
+        '<' => Some("&lt;"),
+        '>' => Some("&gt;"),
+        _ => None,
+    })
+}
+
+/// Escapes `&`, `<` and the `quote` character for use inside an attribute value.
+pub fn escape_attribute(input: &str, quote: char) -> Cow<'_, str> {
+    escape(input, |c| match c {
+        '&' => Some("&amp;"),
+        '<' => Some("&lt;"),
+        '"' if quote == '"' => Some("&quot;"),
+        '\'' if quote == '\'' => Some("&apos;"),
+        _ => None,
+    })
+}
+
+fn escape(input: &str, replacement: impl Fn(char) -> Option<&'static str>) -> Cow<'_, str> {
+    if !input.chars().any(|c| replacement(c).is_some()) {
+        return Cow::Borrowed(input);
+    }
+
+    let mut output = String::with_capacity(input.len() + 8);
+    for c in input.chars() {
+        match replacement(c) {
+            Some(escaped) => output.push_str(escaped),
+            None => output.push(c),
+        }
+    }
+
+    Cow::Owned(output)
+}
+
+fn predefined(name: &str) -> Option<&'static str> {
+    match name {
+        "amp" => Some("&"),
+        "lt" => Some("<"),
+        "gt" => Some(">"),
+        "quot" => Some("\""),
+        "apos" => Some("'"),
+        _ => None,
+    }
+}
+
+/// Parses the rest of `<!ENTITY name "value">`.
+///
+/// Returns the name, the value and the input after the declaration, or `None`
+/// for parameter, external and malformed declarations.
+fn parse_entity_declaration(declaration: &str) -> Option<(&str, &str, &str)> {
+    let rest = declaration.strip_prefix(|c: char| c.is_ascii_whitespace())?;
+    let rest = rest.trim_start();
+    if rest.starts_with('%') {
+        return None;
+    }
+
+    let name_end = rest.find(|c: char| c.is_ascii_whitespace())?;
+    let (name, rest) = rest.split_at(name_end);
+    let rest = rest.trim_start();
+
+    let quote = rest.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let rest = &rest[1..];
+    let value_end = rest.find(quote)?;
+    let (value, rest) = rest.split_at(value_end);
+    let after = rest[1..].trim_start().strip_prefix('>')?;
+
+    Some((name, value, after))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_should_replace_predefined_and_character_references() {
+        assert_eq!(
+            decode("a &amp; b &lt;c&gt; &quot;&apos; &#65;&#x42;&#X43;").unwrap(),
+            "a & b <c> \"' ABC"
+        );
+    }
+
+    #[test]
+    fn decode_should_borrow_when_there_is_nothing_to_decode() {
+        assert!(matches!(decode("plain text").unwrap(), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn decode_should_reject_unknown_and_malformed_references() {
+        assert_eq!(decode("a &foo; b"), Err("&foo;".to_string()));
+        assert_eq!(decode("&#xZZ;"), Err("&#xZZ;".to_string()));
+        assert_eq!(decode("&#0;"), Err("&#0;".to_string()));
+        assert_eq!(decode("fish & chips"), Err("& chips".to_string()));
+    }
+
+    #[test]
+    fn from_internal_subset_should_collect_general_entities() {
+        let entities = Entities::from_internal_subset(
+            r#"
+            <!ENTITY ns_svg "http://www.w3.org/2000/svg">
+            <!-- <!ENTITY commented "no"> -->
+            <!ENTITY % parameter "ignored">
+            <!ENTITY external SYSTEM "file.xml">
+            <!ENTITY ns_svg "second declaration is ignored">
+            <!ENTITY nested '&ns_svg;#&#x41;'>
+            "#,
+        );
+
+        assert_eq!(entities.get("ns_svg"), Some("http://www.w3.org/2000/svg"));
+        assert_eq!(entities.get("commented"), None);
+        assert_eq!(entities.get("parameter"), None);
+        assert_eq!(entities.get("external"), None);
+        assert_eq!(
+            entities.decode("&nested;").unwrap(),
+            "http://www.w3.org/2000/svg#A"
+        );
+    }
+
+    #[test]
+    fn decode_should_stop_recursive_entities() {
+        let entities =
+            Entities::from_internal_subset(r#"<!ENTITY a "&b;&b;"> <!ENTITY b "&a;&a;">"#);
+
+        assert!(entities.decode("&a;").is_err());
+    }
+
+    #[test]
+    fn decode_within_should_share_the_budget_across_values() {
+        let entities = Entities::from_internal_subset(r#"<!ENTITY big "0123456789">"#);
+        let mut budget = 25;
+
+        assert_eq!(
+            entities.decode_within("&big;&big;", &mut budget).unwrap(),
+            "01234567890123456789"
+        );
+        assert_eq!(budget, 5);
+        assert_eq!(
+            entities.decode_within("&amp;&#65;", &mut budget).unwrap(),
+            "&A"
+        );
+        assert_eq!(
+            entities.decode_within("&big;", &mut budget),
+            Err(DecodeError::TooLarge("&big;".to_string()))
+        );
+    }
+
+    #[test]
+    fn escape_should_replace_special_characters() {
+        assert_eq!(
+            escape_text("a < b && c > d"),
+            "a &lt; b &amp;&amp; c &gt; d"
+        );
+        assert_eq!(
+            escape_attribute(r#"say "it's" <here>"#, '"'),
+            r#"say &quot;it's&quot; &lt;here>"#
+        );
+        assert_eq!(
+            escape_attribute(r#"say "it's""#, '\''),
+            r#"say "it&apos;s""#
+        );
+        assert!(matches!(escape_text("plain"), Cow::Borrowed(_)));
+    }
+}
@@ -0,0 +1,223 @@
+//! Error types reported by the lexer and parser.
+
+use std::{fmt, ops::Range};
+
+use crate::{
+    element::{ElementType, attributes::PathError},
+    encoding::Encoding,
+    token::TokenKind,
+};
+
+/// A syntactic construct that is unterminated or malformed.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Construct {
+    /// A comment (`<!-- ...` without `-->`).
+    Comment,
+    /// A CDATA section (`<![CDATA[ ...` without `]]>`).
+    CData,
+    /// An XML declaration (`<?xml ...?>`).
+    XmlDeclaration,
+    /// A document type declaration (`<!DOCTYPE ...>`).
+    Doctype,
+    /// A processing instruction (`<?target ...?>`).
+    ProcessingInstruction,
+    /// A quoted attribute value without its closing quote.
+    AttributeValue,
+    /// A start or end tag without its closing `>`.
+    Tag,
+    /// An element without a matching closing tag.
+    Element(ElementType),
+}
+
+impl fmt::Display for Construct {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Construct::Comment => write!(f, "comment"),
+            Construct::CData => write!(f, "CDATA section"),
+            Construct::XmlDeclaration => write!(f, "XML declaration"),
+            Construct::Doctype => write!(f, "DOCTYPE"),
+            Construct::ProcessingInstruction => write!(f, "processing instruction"),
+            Construct::AttributeValue => write!(f, "attribute value"),
+            Construct::Tag => write!(f, "tag"),
+            Construct::Element(element_type) => write!(f, "<{}> element", element_type),
+        }
+    }
+}
+
+/// The kind of a [`ParseError`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParseErrorKind {
+    /// A token that is not valid in the current parser state.
+    UnexpectedToken,
+    /// A namespace prefix without a matching `xmlns:prefix` declaration in scope.
+    UnboundPrefix(String),
+    /// A closing tag that does not match the innermost open element.
+    ///
+    /// `expected` is `None` when there is no open element at all.
+    MismatchedCloseTag {
+        expected: Option<String>,
+        found: String,
+    },
+    /// An element that is not allowed as a child of its parent.
+    DisallowedChild {
+        parent: ElementType,
+        child: ElementType,
+    },
+    /// An unknown or malformed entity or character reference, such as `&foo;`.
+    InvalidReference(String),
+    /// A known attribute whose value failed to parse.
+    InvalidAttribute { name: String, value: String },
+    /// Path data with an error, at the offset of the error in the value.
+    ///
+    /// In lenient mode the path keeps the segments before the error.
+    InvalidPathData(PathError),
+    /// A construct that is still open when the input ends.
+    UnterminatedConstruct(Construct),
+    /// A construct whose content could not be parsed, e.g. an XML declaration without `version`.
+    MalformedConstruct(Construct),
+    /// Bytes that are not valid in the detected encoding.
+    InvalidEncoding(Encoding),
+    /// An `encoding` in the XML declaration that cannot be decoded.
+    UnsupportedEncoding(String),
+    /// Reading the input failed, with the error message of the reader.
+    Io(String),
+    /// An element nested deeper than the maximum depth of the parser.
+    NestingTooDeep(usize),
+    /// Entity references that expand to more than this many bytes in total,
+    /// such as one large entity referenced many times.
+    ExpansionTooLarge(usize),
+}
+
+/// A structured error produced while lexing or parsing an SVG document.
+///
+/// Carries the [`ParseErrorKind`], the byte span in the source where the
+/// problem was detected, and for token mismatches the expected and found
+/// [`TokenKind`]s.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseError {
+    /// What went wrong.
+    pub kind: ParseErrorKind,
+    /// Byte range in the original source (inclusive start, exclusive end).
+    pub span: Range<usize>,
+    /// Token kinds that would have been accepted at this position.
+    pub expected: Vec<TokenKind>,
+    /// The token kind that was actually found, or `None` at end of input.
+    pub found: Option<TokenKind>,
+}
+
+impl ParseError {
+    /// Creates an error of the given kind without token expectations.
+    pub fn new(kind: ParseErrorKind, span: Range<usize>) -> Self {
+        Self {
+            kind,
+            span,
+            expected: vec![],
+            found: None,
+        }
+    }
+
+    /// Creates an [`ParseErrorKind::UnexpectedToken`] error.
+    pub fn unexpected(found: TokenKind, span: Range<usize>, expected: &[TokenKind]) -> Self {
+        Self {
+            kind: ParseErrorKind::UnexpectedToken,
+            span,
+            expected: expected.to_vec(),
+            found: Some(found),
+        }
+    }
+
+    /// Creates an [`ParseErrorKind::UnterminatedConstruct`] error.
+    pub fn unterminated(construct: Construct, span: Range<usize>) -> Self {
+        Self::new(ParseErrorKind::UnterminatedConstruct(construct), span)
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            ParseErrorKind::UnexpectedToken => {
+                match &self.found {
+                    Some(found) => write!(f, "unexpected {}", found)?,
+                    None => write!(f, "unexpected end of input")?,
+                }
+
+                for (i, expected) in self.expected.iter().enumerate() {
+                    if i == 0 {
+                        write!(f, ", expected {}", expected)?;
+                    } else {
+                        write!(f, " or {}", expected)?;
+                    }
+                }
+            }
+            ParseErrorKind::UnboundPrefix(prefix) => {
+                write!(f, "undeclared namespace prefix `{}`", prefix)?
+            }
+            ParseErrorKind::MismatchedCloseTag { expected, found } => match expected {
+                Some(expected) => {
+                    write!(f, "closing tag </{}> does not match <{}>", found, expected)?
+                }
+                None => write!(f, "closing tag </{}> has no matching opening tag", found)?,
+            },
+            ParseErrorKind::DisallowedChild { parent, child } => {
+                write!(f, "<{}> is not allowed as a child of <{}>", child, parent)?
+            }
+            ParseErrorKind::InvalidReference(reference) => {
+                write!(f, "invalid reference {}", reference)?
+            }
+            ParseErrorKind::InvalidAttribute { name, value } => {
+                write!(f, "invalid attribute {}=\"{}\"", name, value)?
+            }
+            ParseErrorKind::InvalidPathData(error) => write!(f, "invalid path data: {}", error)?,
+            ParseErrorKind::UnterminatedConstruct(construct) => {
+                write!(f, "unterminated {}", construct)?
+            }
+            ParseErrorKind::MalformedConstruct(construct) => write!(f, "malformed {}", construct)?,
+            ParseErrorKind::InvalidEncoding(encoding) => write!(f, "invalid {}", encoding)?,
+            ParseErrorKind::UnsupportedEncoding(name) => {
+                write!(f, "unsupported encoding \"{}\"", name)?
+            }
+            ParseErrorKind::Io(message) => write!(f, "I/O error: {}", message)?,
+            ParseErrorKind::NestingTooDeep(max_depth) => {
+                write!(f, "elements nested deeper than {} levels", max_depth)?
+            }
+            ParseErrorKind::ExpansionTooLarge(limit) => {
+                write!(f, "entity references expand to more than {} bytes", limit)?
+            }
+        }
+
+        write!(f, " at {}..{}", self.span.start, self.span.end)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// How serious a [`Diagnostic`] is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Severity {
+    /// The document is questionable but was parsed as written.
+    Warning,
+    /// The document is malformed and the parser had to recover.
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// A problem recorded while parsing in lenient mode.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub error: ParseError,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.severity, self.error)
+    }
+}
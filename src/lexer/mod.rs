@@ -5,38 +5,61 @@
 //!
 //! - **Text**: Scanning for `<` delimiters that mark transitions into markup.
 //! - **Markup**: Parsing element names, attributes, and closing delimiters.
-//! - **Quote**: Parsing quoted attribute values with backslash escaping.
+//! - **Quote**: Parsing single- or double-quoted attribute values with backslash escaping.
+//!
+//! [`Lexer`] works on a string in memory; [`StreamLexer`] reads the document
+//! from an [`io::Read`](std::io::Read) and produces the same tokens.
 //!
 //! # Example
 //!
 //! ```
 //! use esvega::lexer::{Input, Lexer};
 //!
-//! let input = b"<svg width=\"100\"></svg>";
+//! let input = "<svg width=\"100\"></svg>";
 //! let mut lexer = Lexer::new(Input::new(input));
 //!
 //! // Consume tokens until EOF
-//! // while let Some(token) = lexer.advance() { ... }
+//! // while let Some(token) = lexer.advance()? { ... }
 //! ```
 
 mod mode;
+mod stream;
 
-use std::ops::Range;
+use std::{borrow::Cow, ops::Range};
 
 use memchr::memchr;
 use memchr::memmem::find;
 
+pub use stream::StreamLexer;
+
 use crate::{
+    error::{Construct, ParseError},
     lexer::mode::LexerMode,
     token::{Token, TokenKind},
 };
 
-/// An immutable reference to SVG source bytes.
+/// Something that produces [`Token`]s, such as a [`Lexer`] over a string or a
+/// [`StreamLexer`] over a reader.
+pub trait TokenSource<'input> {
+    /// Returns the next token, or `Ok(None)` at the end of input.
+    fn advance(&mut self) -> Result<Option<Token<'input>>, ParseError>;
+
+    /// Returns the offset of the end of the input read so far, which is the end
+    /// of the document once [`advance`](TokenSource::advance) returned `Ok(None)`.
+    fn end(&self) -> usize;
+}
+
+/// An immutable reference to SVG source text.
+///
+/// Tracks the current position (`offset`) within the source bytes and provides
+/// methods for advancing through the input. Use [`encoding::decode`] to get the
+/// text of a source that is not known to be UTF-8.
 ///
-/// Tracks the current position (`offset`) within the byte slice and provides
-/// methods for advancing through the input.
+/// [`encoding::decode`]: crate::encoding::decode
 #[derive(Debug)]
 pub struct Input<'a> {
+    /// The source text that tokens are sliced from.
+    source: &'a str,
     /// The raw SVG source bytes.
     pub bytes: &'a [u8],
     /// Total length of the source in bytes.
@@ -46,15 +69,29 @@ pub struct Input<'a> {
 }
 
 impl<'a> Input<'a> {
-    /// Creates a new `Input` from a byte slice.
-    pub fn new(bytes: &'a [u8]) -> Self {
+    /// Creates a new `Input` from source text.
+    ///
+    /// Reading starts after a leading byte order mark, as it does for a
+    /// [`StreamLexer`]; spans still refer to `source` as given.
+    pub fn new(source: &'a str) -> Self {
+        let bom = match source.starts_with('\u{FEFF}') {
+            true => '\u{FEFF}'.len_utf8(),
+            false => 0,
+        };
+
         Self {
-            bytes,
-            length: bytes.len(),
-            offset: 0,
+            source,
+            bytes: source.as_bytes(),
+            length: source.len(),
+            offset: bom,
         }
     }
 
+    /// Returns the whole source text.
+    pub fn source(&self) -> &'a str {
+        self.source
+    }
+
     /// Returns `true` if the lexer has consumed all bytes.
     pub const fn has_reached_eof(&self) -> bool {
         self.offset >= self.length
@@ -103,6 +140,39 @@ impl<'a> Input<'a> {
         }
     }
 
+    /// Consumes a `<!DOCTYPE ...>` declaration up to its closing `>`.
+    ///
+    /// Skips over quoted strings, comments and the `[ ... ]` internal subset,
+    /// which may all contain `>` themselves.
+    pub fn consume_doctype(&mut self) {
+        let mut in_internal_subset = false;
+
+        while !self.has_reached_eof() {
+            match self.bytes[self.offset] {
+                quote @ (b'"' | b'\'') => {
+                    self.skip(1);
+                    self.consume_until(&[quote], false);
+                    self.skip(1);
+                }
+                b'<' if self.is_at(b"<!--", false) => {
+                    self.skip(4);
+                    self.consume_until(b"-->", false);
+                    self.skip(3);
+                }
+                b'[' => {
+                    in_internal_subset = true;
+                    self.skip(1);
+                }
+                b']' => {
+                    in_internal_subset = false;
+                    self.skip(1);
+                }
+                b'>' if !in_internal_subset => return,
+                _ => self.skip(1),
+            }
+        }
+    }
+
     /// Returns `true` if the bytes at the current position match `search`.
     ///
     /// If `ignore_ascii_case` is `true`, the comparison is case-insensitive
@@ -160,9 +230,10 @@ impl<'a> Input<'a> {
 ///
 /// - **Text**: Scans for `<` delimiters, emitting text content between tags.
 /// - **Markup**: Parses element names, attribute names, `=` signs, and `>` delimiters.
-/// - **Quote**: Handles quoted attribute values, including backslash-escaped quotes.
+/// - **Quote**: Handles single- or double-quoted attribute values, including backslash-escaped quotes.
 ///
-/// Comments (`<!-- ... -->`) are scanned inline regardless of mode.
+/// Comments (`<!-- ... -->`) are scanned inline regardless of mode. CDATA sections,
+/// the XML declaration, DOCTYPE and processing instructions only in text mode.
 ///
 /// [`advance`]: Lexer::advance
 #[derive(Debug)]
@@ -186,10 +257,16 @@ impl<'input> Lexer<'input> {
 
     /// Advances the lexer to the next token and returns it.
     ///
-    /// Returns `None` when the end of input is reached.
-    /// Skips whitespace automatically before each token.
-    pub fn advance(&mut self) -> Option<Token> {
-        self.input.consume_whitespace();
+    /// Returns `Ok(None)` when the end of input is reached, or an error if the
+    /// input ends inside a comment, a quoted value or a tag.
+    ///
+    /// Inside tags, whitespace before each token is skipped. Between tags it is
+    /// part of the [`TokenKind::Text`] token, so indentation is kept as written.
+    pub fn advance(&mut self) -> Result<Option<Token<'input>>, ParseError> {
+        // whitespace between tags is text, and kept as written
+        if !matches!(self.mode, LexerMode::Text) {
+            self.input.consume_whitespace();
+        }
 
         let token_start = self.input.offset;
 
@@ -197,31 +274,101 @@ impl<'input> Lexer<'input> {
             let byte = self.input.bytes[self.input.offset];
 
             if self.input.is_at(b"<!--", false) {
+                // the dashes of `<!--` do not also end the comment, as in `<!-->`
+                self.input.skip(4);
                 self.input.consume_until(b"-->", false);
 
                 if !self.input.is_at(b"-->", false) {
-                    // TODO: Error
-                    return None;
+                    return Err(ParseError::unterminated(
+                        Construct::Comment,
+                        token_start..self.input.offset,
+                    ));
                 }
                 self.input.consume(3);
 
-                return Some(self.token(TokenKind::Comment, token_start..self.input.offset));
+                return Ok(Some(
+                    self.token(TokenKind::Comment, token_start..self.input.offset),
+                ));
+            }
+
+            if matches!(self.mode, LexerMode::Text) && self.input.is_at(b"<?", false) {
+                self.input.consume_until(b"?>", false);
+
+                if !self.input.is_at(b"?>", false) {
+                    return Err(ParseError::unterminated(
+                        Construct::ProcessingInstruction,
+                        token_start..self.input.offset,
+                    ));
+                }
+                self.input.consume(2);
+
+                // `<?xml ` is the XML declaration, `<?xml-stylesheet ` is not
+                let target = &self.input.bytes[token_start + 2..];
+                let kind = if target.starts_with(b"xml")
+                    && target
+                        .get(3)
+                        .is_some_and(|b| b.is_ascii_whitespace() || *b == b'?')
+                {
+                    TokenKind::XmlDeclaration
+                } else {
+                    TokenKind::ProcessingInstruction
+                };
+
+                return Ok(Some(self.token(kind, token_start..self.input.offset)));
+            }
+
+            if matches!(self.mode, LexerMode::Text) && self.input.is_at(b"<!DOCTYPE", false) {
+                self.input.consume_doctype();
+
+                if !self.input.is_at(b">", false) {
+                    return Err(ParseError::unterminated(
+                        Construct::Doctype,
+                        token_start..self.input.offset,
+                    ));
+                }
+                self.input.consume(1);
+
+                return Ok(Some(
+                    self.token(TokenKind::Doctype, token_start..self.input.offset),
+                ));
+            }
+
+            if matches!(self.mode, LexerMode::Text) && self.input.is_at(b"<![CDATA[", false) {
+                self.input.consume_until(b"]]>", false);
+
+                if !self.input.is_at(b"]]>", false) {
+                    return Err(ParseError::unterminated(
+                        Construct::CData,
+                        token_start..self.input.offset,
+                    ));
+                }
+                self.input.consume(3);
+
+                return Ok(Some(
+                    self.token(TokenKind::CData, token_start..self.input.offset),
+                ));
             }
 
             match self.mode {
-                LexerMode::Quote => {
+                LexerMode::Quote(quote) => {
+                    let quote = [quote];
+
                     // this emits the ending quote and sets the mode back to markup
-                    if self.input.is_at(b"\"", false) {
+                    if self.input.is_at(&quote, false) {
                         self.input.consume(1);
                         self.mode = LexerMode::Markup;
 
-                        return Some(self.token(TokenKind::Quote, token_start..self.input.offset));
+                        return Ok(Some(
+                            self.token(TokenKind::Quote, token_start..self.input.offset),
+                        ));
                     }
                     loop {
-                        self.input.consume_until(b"\"", false);
-                        if !self.input.is_at(b"\"", false) {
-                            // TODO: Error
-                            return None;
+                        self.input.consume_until(&quote, false);
+                        if !self.input.is_at(&quote, false) {
+                            return Err(ParseError::unterminated(
+                                Construct::AttributeValue,
+                                token_start..self.input.offset,
+                            ));
                         }
 
                         if self.input.bytes[self.input.offset - 1] != b'\\' {
@@ -231,71 +378,82 @@ impl<'input> Lexer<'input> {
                         self.input.consume(1);
                     }
 
-                    return Some(self.token(TokenKind::Literal, token_start..self.input.offset));
+                    return Ok(Some(
+                        self.token(TokenKind::Literal, token_start..self.input.offset),
+                    ));
                 }
                 LexerMode::Markup => {
                     if self.input.is_at(b">", false) {
                         self.mode = LexerMode::Text;
                         self.input.consume(1);
 
-                        return Some(
+                        return Ok(Some(
                             self.token(TokenKind::GreaterThan, token_start..self.input.offset),
-                        );
+                        ));
                     }
 
                     if self.input.is_at(b"/", false) {
                         self.input.consume_until(b">", false);
 
                         if !self.input.is_at(b">", false) {
-                            // TODO: Error - we're at something like "<div /x
-                            return None;
+                            return Err(ParseError::unterminated(
+                                Construct::Tag,
+                                token_start..self.input.offset,
+                            ));
                         }
                         self.input.consume(1);
 
                         self.mode = LexerMode::Text;
 
-                        return Some(
-                            self.token(TokenKind::SlashGreaterThan, token_start..self.input.offset),
-                        );
+                        return Ok(Some(self.token(
+                            TokenKind::SlashGreaterThan,
+                            token_start..self.input.offset,
+                        )));
                     }
 
                     match byte {
                         // here we need to find attributes, equal signs, and values
                         b'=' => {
                             self.input.consume(1);
-                            return Some(
+                            return Ok(Some(
                                 self.token(TokenKind::Equals, token_start..self.input.offset),
-                            );
+                            ));
                         }
                         _ => {
                             // here we will find attribute values inside of quotes, we need to be able
                             // to handle escaped quotes as proper values
-                            if self.input.is_at(b"\"", false) {
-                                self.mode = LexerMode::Quote;
+                            if byte == b'"' || byte == b'\'' {
+                                self.mode = LexerMode::Quote(byte);
                                 self.input.consume(1);
-                                return Some(
+                                return Ok(Some(
                                     self.token(TokenKind::Quote, token_start..self.input.offset),
-                                );
+                                ));
                             }
 
                             // this is the last part -> we are looking for identifiers(attribute names)
                             // we know that we are at the start of an identifier, and we need to find
-                            // the end of the word - which is either a space, equal sign, or >
+                            // the end of the word - which is either whitespace, equal sign, or >
                             loop {
                                 if self.input.is_at(b"=", false)
-                                    || self.input.is_at(b" ", false)
+                                    || self
+                                        .input
+                                        .bytes
+                                        .get(self.input.offset)
+                                        .is_some_and(u8::is_ascii_whitespace)
                                     || self.input.is_at(b">", false)
                                     || self.input.is_at(b"/", false)
                                 {
-                                    return Some(self.token(
+                                    return Ok(Some(self.token(
                                         TokenKind::Identifier,
                                         token_start..self.input.offset,
-                                    ));
+                                    )));
                                 }
 
                                 if self.input.has_reached_eof() {
-                                    // TODO: Error, expected one of " ", ">", "=" or "/"
-                                    return None;
+                                    return Err(ParseError::unterminated(
+                                        Construct::Tag,
+                                        token_start..self.input.offset,
+                                    ));
                                 }
                                 self.input.consume(1);
                             }
@@ -307,41 +465,47 @@ impl<'input> Lexer<'input> {
                         self.input.consume(2);
                         self.mode = LexerMode::Markup;
 
-                        return Some(
+                        return Ok(Some(
                             self.token(TokenKind::LessThanSlash, token_start..self.input.offset),
-                        );
+                        ));
                     }
 
                     if self.input.is_at(b"<", false) {
                         self.mode = LexerMode::Markup;
                         self.input.consume(1);
 
-                        return Some(
+                        return Ok(Some(
                             self.token(TokenKind::LessThan, token_start..self.input.offset),
-                        );
+                        ));
                     }
 
                     // here we just consume until eof or LessThan sign
                     self.input.consume_until(b"<", false);
-                    return Some(self.token(TokenKind::Text, token_start..self.input.offset));
+                    return Ok(Some(
+                        self.token(TokenKind::Text, token_start..self.input.offset),
+                    ));
                 }
             }
         }
 
-        None
+        Ok(None)
     }
 
     #[inline]
-    fn token(&mut self, kind: TokenKind, span: Range<usize>) -> Token {
-        // SAFETY: The input bytes are guaranteed to be valid UTF-8 because:
-        // All byte slices here are subslices of the validated input
-        let string =
-            unsafe { std::str::from_utf8_unchecked(&self.input.bytes[span.clone()]) }.to_owned();
-
-        Token {
-            kind,
-            value: string,
-            span: span,
-        }
+    fn token(&self, kind: TokenKind, span: Range<usize>) -> Token<'input> {
+        // tokens start and end at ASCII delimiters, so they are on character boundaries
+        let value = Cow::Borrowed(&self.input.source[span.clone()]);
+
+        Token { kind, value, span }
+    }
+}
+
+impl<'input> TokenSource<'input> for Lexer<'input> {
+    fn advance(&mut self) -> Result<Option<Token<'input>>, ParseError> {
+        Lexer::advance(self)
+    }
+
+    fn end(&self) -> usize {
+        self.input.length
     }
 }
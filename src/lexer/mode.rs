@@ -14,6 +14,7 @@ pub enum LexerMode {
     Markup,
     /// Scanning a quoted attribute value.
     ///
-    /// Looks for the closing quote `"`, handling backslash escapes.
-    Quote,
+    /// Holds the quote character (`"` or `'`) that opened the value, and looks
+    /// for the same character to close it, handling backslash escapes.
+    Quote(u8),
 }
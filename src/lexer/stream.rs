@@ -0,0 +1,385 @@
+//! Lexing a document read from an [`io::Read`](std::io::Read) in chunks.
+
+use std::{borrow::Cow, io::Read, ops::Range};
+
+use crate::{
+    encoding::{self, Encoding},
+    error::{ParseError, ParseErrorKind},
+    lexer::{Input, Lexer, TokenSource, mode::LexerMode},
+    span::{Locate, Location},
+    token::Token,
+};
+
+/// Number of bytes read from the reader at once.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// How far the lexer looks past the end of a token, for `<![CDATA[` and `<!DOCTYPE`.
+const LOOKAHEAD: usize = 9;
+
+/// A lexer over the bytes of a reader, such as a file or a socket.
+///
+/// The input is read in chunks, and only the text since the last tag boundary
+/// is kept in memory. Tokens that straddle two chunks, like a long comment or
+/// a quoted value, are completed before they are returned, so they are the
+/// same as the tokens of a [`Lexer`] over the whole document; their values are
+/// owned. Wrap the reader in a [`BufReader`](std::io::BufReader) only if it is
+/// slow to read small amounts; reads are already 64 KiB or more.
+///
+/// The encoding is detected as by [`encoding::decode`], and UTF-16 and
+/// ISO-8859-1 input is transcoded chunk by chunk; spans refer to the UTF-8
+/// text, without the byte order mark.
+#[derive(Debug)]
+pub struct StreamLexer<R> {
+    reader: R,
+    /// The encoding of the reader, known once the first chunk is read.
+    encoding: Encoding,
+    /// The text read but not discarded yet.
+    buffer: String,
+    /// The bytes of a character split between two chunks.
+    pending: Vec<u8>,
+    /// Offset of the start of `buffer` in the document.
+    base: usize,
+    /// Position of the next token in `buffer`.
+    position: usize,
+    /// Position in `buffer` before which no event refers to the text anymore.
+    discardable: usize,
+    mode: LexerMode,
+    /// Whether the reader has no more bytes.
+    eof: bool,
+    /// Location of the start of `buffer`.
+    base_location: Location,
+    /// The last two locations looked up, where the next lookup continues from.
+    ///
+    /// Two, because the spans of an element are looked up before the spans of
+    /// its attributes.
+    cursors: [Location; 2],
+}
+
+impl<R: Read> StreamLexer<R> {
+    /// Creates a lexer that reads from `reader`.
+    pub fn new(reader: R) -> Self {
+        let start = Location {
+            offset: 0,
+            line: 1,
+            column: 1,
+        };
+
+        Self {
+            reader,
+            encoding: Encoding::Utf8,
+            buffer: String::new(),
+            pending: vec![],
+            base: 0,
+            position: 0,
+            discardable: 0,
+            mode: LexerMode::default(),
+            eof: false,
+            base_location: start,
+            cursors: [start; 2],
+        }
+    }
+
+    /// Advances the lexer to the next token and returns it.
+    ///
+    /// Reads from the reader until the token is complete. Returns `Ok(None)`
+    /// when the end of input is reached, or an error if the input ends inside a
+    /// comment, a quoted value or a tag, is not valid UTF-8, or cannot be read.
+    pub fn advance(&mut self) -> Result<Option<Token<'static>>, ParseError> {
+        // between tags, the events of everything before have been handled
+        if matches!(self.mode, LexerMode::Text) {
+            self.discardable = self.position;
+        }
+
+        loop {
+            let mut lexer = Lexer::new(Input::new(&self.buffer));
+            lexer.input.offset = self.position;
+            lexer.mode = self.mode.clone();
+            let result = lexer.advance();
+
+            // a token that ends close to the end of the buffer may continue in
+            // the next chunk, or be followed by a longer delimiter
+            let is_complete = self.eof
+                || matches!(&result, Ok(Some(token)) if token.span.end + LOOKAHEAD <= self.buffer.len());
+
+            if is_complete {
+                self.position = lexer.input.offset;
+                self.mode = lexer.mode;
+
+                let base = self.base;
+                return match result {
+                    Ok(token) => Ok(token.map(|token| Token {
+                        kind: token.kind,
+                        value: Cow::Owned(token.value.into_owned()),
+                        span: shift(token.span, base),
+                    })),
+                    Err(mut error) => {
+                        error.span = shift(error.span, base);
+                        Err(error)
+                    }
+                };
+            }
+
+            self.read_chunk()?;
+        }
+    }
+
+    /// Discards the text no event refers to, and appends the next chunk of the
+    /// reader to the buffer.
+    ///
+    /// Reads at least as much as is buffered, so a long token is lexed again
+    /// only a logarithmic number of times.
+    fn read_chunk(&mut self) -> Result<(), ParseError> {
+        if self.discardable > 0 {
+            self.base_location = self.location(self.base + self.discardable);
+            self.buffer.drain(..self.discardable);
+            self.base += self.discardable;
+            self.position -= self.discardable;
+            self.discardable = 0;
+        }
+
+        let is_first = self.base == 0 && self.buffer.is_empty() && self.pending.is_empty();
+        let mut bytes = std::mem::take(&mut self.pending);
+        let before = bytes.len();
+        let limit = CHUNK_SIZE.max(self.buffer.len());
+
+        let end = self.end();
+        (&mut self.reader)
+            .take(limit as u64)
+            .read_to_end(&mut bytes)
+            .map_err(|error| ParseError::new(ParseErrorKind::Io(error.to_string()), end..end))?;
+        self.eof = bytes.len() == before;
+
+        let mut text = &bytes[..];
+        if is_first {
+            let (encoding, bom) = encoding::detect(text)?;
+            self.encoding = encoding;
+            text = &text[bom..];
+        }
+
+        match self.encoding {
+            Encoding::Utf8 => self.push_utf8(text),
+            Encoding::Utf16Le | Encoding::Utf16Be => self.push_utf16(text),
+            Encoding::Latin1 => {
+                self.buffer.extend(text.iter().map(|&b| char::from(b)));
+                Ok(())
+            }
+        }
+    }
+
+    /// Appends UTF-8 bytes to the buffer, keeping a character that continues
+    /// in the next chunk for later.
+    fn push_utf8(&mut self, bytes: &[u8]) -> Result<(), ParseError> {
+        let error = match std::str::from_utf8(bytes) {
+            Ok(valid) => {
+                self.buffer.push_str(valid);
+                return Ok(());
+            }
+            Err(error) => error,
+        };
+
+        let (valid, rest) = bytes.split_at(error.valid_up_to());
+        self.buffer
+            .push_str(std::str::from_utf8(valid).unwrap_or_default());
+
+        match error.error_len() {
+            // the rest of the character is in the next chunk
+            None if !self.eof => {
+                self.pending = rest.to_vec();
+                Ok(())
+            }
+            length => {
+                let start = self.end();
+                Err(ParseError::new(
+                    ParseErrorKind::InvalidEncoding(Encoding::Utf8),
+                    start..start + length.unwrap_or(rest.len()),
+                ))
+            }
+        }
+    }
+
+    /// Appends UTF-16 bytes to the buffer as UTF-8, keeping a code unit or a
+    /// surrogate pair that continues in the next chunk for later.
+    fn push_utf16(&mut self, bytes: &[u8]) -> Result<(), ParseError> {
+        let encoding = self.encoding;
+        let mut units: Vec<u16> = bytes
+            .chunks_exact(2)
+            .map(|pair| match encoding {
+                Encoding::Utf16Be => u16::from_be_bytes([pair[0], pair[1]]),
+                _ => u16::from_le_bytes([pair[0], pair[1]]),
+            })
+            .collect();
+
+        let mut complete = units.len() * 2;
+        if !self.eof
+            && units
+                .last()
+                .is_some_and(|unit| (0xD800..0xDC00).contains(unit))
+        {
+            units.pop();
+            complete -= 2;
+        }
+
+        for c in char::decode_utf16(units) {
+            let start = self.end();
+            let c = c.map_err(|_| {
+                ParseError::new(ParseErrorKind::InvalidEncoding(encoding), start..start)
+            })?;
+            self.buffer.push(c);
+        }
+
+        let rest = &bytes[complete..];
+        if self.eof && !rest.is_empty() {
+            let start = self.end();
+            return Err(ParseError::new(
+                ParseErrorKind::InvalidEncoding(encoding),
+                start..start,
+            ));
+        }
+        self.pending = rest.to_vec();
+
+        Ok(())
+    }
+}
+
+impl<R: Read> TokenSource<'static> for StreamLexer<R> {
+    fn advance(&mut self) -> Result<Option<Token<'static>>, ParseError> {
+        StreamLexer::advance(self)
+    }
+
+    fn end(&self) -> usize {
+        self.base + self.buffer.len()
+    }
+}
+
+impl<R> Locate for StreamLexer<R> {
+    /// Returns the location of an offset that was not discarded yet.
+    fn location(&mut self, offset: usize) -> Location {
+        let offset = offset.clamp(self.base, self.base + self.buffer.len());
+        let mut location = self
+            .cursors
+            .into_iter()
+            .filter(|cursor| (self.base..=offset).contains(&cursor.offset))
+            .max()
+            .unwrap_or(self.base_location);
+
+        let bytes = &self.buffer.as_bytes()[location.offset - self.base..offset - self.base];
+        for &byte in bytes {
+            if byte == b'\n' {
+                location.line += 1;
+                location.column = 1;
+            } else if byte & 0xC0 != 0x80 {
+                location.column += 1;
+            }
+        }
+        location.offset = offset;
+
+        if location != self.cursors[0] {
+            self.cursors = [location, self.cursors[0]];
+        }
+
+        location
+    }
+}
+
+fn shift(span: Range<usize>, base: usize) -> Range<usize> {
+    span.start + base..span.end + base
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{span::LineIndex, token::TokenKind};
+
+    fn lex(source: &str) -> Vec<(TokenKind, String, Range<usize>)> {
+        let mut lexer = Lexer::new(Input::new(source));
+        std::iter::from_fn(|| lexer.advance().unwrap())
+            .map(|token| (token.kind, token.value.into_owned(), token.span))
+            .collect()
+    }
+
+    fn lex_stream(bytes: &[u8]) -> Vec<(TokenKind, String, Range<usize>)> {
+        let mut lexer = StreamLexer::new(bytes);
+        std::iter::from_fn(|| lexer.advance().unwrap())
+            .map(|token| (token.kind, token.value.into_owned(), token.span))
+            .collect()
+    }
+
+    #[test]
+    fn stream_lexer_should_complete_tokens_across_chunks() {
+        let comment = format!("<!--{}-->", "-".repeat(CHUNK_SIZE));
+        // two-byte characters, so one of them is split between chunks
+        let value = "é".repeat(CHUNK_SIZE);
+
+        for padding in ["", " "] {
+            let source = format!(
+                "<?xml version=\"1.0\"?>\n<svg>{padding}{comment}<text id=\"{value}\">ž</text></svg>\n"
+            );
+
+            assert_eq!(lex_stream(source.as_bytes()), lex(&source));
+        }
+    }
+
+    #[test]
+    fn stream_lexer_should_skip_the_byte_order_mark() {
+        let tokens = lex_stream("\u{FEFF}<svg/>".as_bytes());
+
+        assert_eq!(tokens[0], (TokenKind::LessThan, "<".to_string(), 0..1));
+    }
+
+    #[test]
+    fn stream_lexer_should_locate_offsets_like_line_index() {
+        let source = "<svg>\n  <text>žluť</text>\n</svg>";
+        let index = LineIndex::new(source.as_bytes());
+        let mut lexer = StreamLexer::new(source.as_bytes());
+        lexer.advance().unwrap();
+
+        for offset in [24, 0, 5, 6, 14, 22, source.len()] {
+            assert_eq!(lexer.location(offset), index.location(offset));
+        }
+    }
+
+    #[test]
+    fn stream_lexer_should_reject_invalid_utf8_and_unknown_encodings() {
+        let mut lexer = StreamLexer::new(&b"<svg>\xE9</svg>"[..]);
+        let error = lexer.advance().unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::InvalidEncoding(Encoding::Utf8));
+        assert_eq!(error.span, 5..6);
+
+        let mut lexer = StreamLexer::new(&br#"<?xml version="1.0" encoding="Shift_JIS"?>"#[..]);
+        let error = lexer.advance().unwrap_err();
+        assert_eq!(
+            error.kind,
+            ParseErrorKind::UnsupportedEncoding("Shift_JIS".to_string())
+        );
+    }
+
+    #[test]
+    fn stream_lexer_should_transcode_utf16_and_latin1() {
+        // after the byte order mark, the first chunk ends inside the surrogate pair
+        let text = "a".repeat(CHUNK_SIZE / 2 - 2 - "<svg><text>".len());
+        let source = format!("<svg><text>{text}🦀</text></svg>");
+        for big_endian in [false, true] {
+            let mut bytes = match big_endian {
+                true => vec![0xFE, 0xFF],
+                false => vec![0xFF, 0xFE],
+            };
+            bytes.extend(source.encode_utf16().flat_map(|unit| match big_endian {
+                true => unit.to_be_bytes(),
+                false => unit.to_le_bytes(),
+            }));
+
+            assert_eq!(lex_stream(&bytes), lex(&source));
+        }
+
+        let latin1 = b"<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?><text>caf\xE9</text>";
+        let source = r#"<?xml version="1.0" encoding="ISO-8859-1"?><text>café</text>"#;
+        assert_eq!(lex_stream(latin1), lex(source));
+
+        let mut lexer = StreamLexer::new(&b"\xFF\xFE<\0s\0v"[..]);
+        let error = lexer.advance().unwrap_err();
+        assert_eq!(
+            error.kind,
+            ParseErrorKind::InvalidEncoding(Encoding::Utf16Le)
+        );
+    }
+}
@@ -1,13 +1,27 @@
 pub mod element;
+pub mod encoding;
+pub mod entity;
+pub mod error;
 pub mod lexer;
+pub mod namespace;
 pub mod parser;
+pub mod serialize;
+pub mod span;
 pub mod svg;
 pub mod token;
 
 pub use element::Element;
 pub use element::ElementType;
-pub use lexer::{Input, Lexer};
-pub use parser::Parser;
-pub use parser::ast::{AST, CDataNode, CommentNode, Node, NodeId, TextNode};
+pub use error::{Diagnostic, ParseError, ParseErrorKind, Severity};
+pub use lexer::{Input, Lexer, StreamLexer, TokenSource};
+pub use parser::ast::{
+    AST, CDataNode, CommentNode, DoctypeNode, Node, NodeId, ProcessingInstructionNode, TextNode,
+    XmlDeclarationNode,
+};
+pub use parser::events::{Event, Events};
+pub use parser::incremental::TextEdit;
+pub use parser::{ParseMode, Parser};
+pub use serialize::{AttributeOrder, Indent, SelfClosing, SerializeOptions, XmlDeclarationMode};
+pub use span::{Location, Span};
 pub use token::Token;
 pub use token::TokenKind;
@@ -1,16 +1,12 @@
-use std::fs;
+use std::fs::File;
 
-use esvega::{Input, Lexer, Parser};
+use esvega::Parser;
 
 fn main() {
-    let content = fs::read_to_string("410_2.svg").unwrap();
+    let file = File::open("410_2.svg").unwrap();
 
-    let lexer = Lexer::new(Input::new(content.as_bytes()));
-    let mut parser = Parser::new(lexer);
-    let ast = parser.parse();
-
-    if let Some(ast) = ast {
-        print!("{}", ast.to_svg());
+    match Parser::parse_reader(file) {
+        Ok(ast) => print!("{}", ast.to_svg()),
+        Err(error) => eprintln!("error: {}", error),
     }
 }
-
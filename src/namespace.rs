@@ -0,0 +1,94 @@
+//! XML namespaces and qualified names.
+//!
+//! Elements and attributes from namespaces other than SVG (Inkscape, Sodipodi,
+//! Adobe, RDF, ...) are kept generically with their [`QualifiedName`], so they
+//! survive a round trip through the [`AST`](crate::AST).
+
+use std::fmt;
+
+/// The SVG namespace.
+pub const SVG_NAMESPACE: &str = "http://www.w3.org/2000/svg";
+/// The XLink namespace, used by `xlink:href`.
+pub const XLINK_NAMESPACE: &str = "http://www.w3.org/1999/xlink";
+/// The XML namespace, implicitly bound to the `xml` prefix.
+pub const XML_NAMESPACE: &str = "http://www.w3.org/XML/1998/namespace";
+/// The namespace of `xmlns` and `xmlns:*` declarations.
+pub const XMLNS_NAMESPACE: &str = "http://www.w3.org/2000/xmlns/";
+
+/// A name as written in the source, with the namespace its prefix resolved to.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct QualifiedName {
+    /// The name as written, e.g. `inkscape:label`.
+    pub name: String,
+    /// The namespace URI, or `None` if the prefix was not declared.
+    pub namespace: Option<String>,
+}
+
+impl QualifiedName {
+    pub fn new(name: impl Into<String>, namespace: Option<&str>) -> Self {
+        Self {
+            name: name.into(),
+            namespace: namespace.map(str::to_string),
+        }
+    }
+
+    /// Returns the part before the `:`, if any.
+    pub fn prefix(&self) -> Option<&str> {
+        split(&self.name).0
+    }
+
+    /// Returns the part after the `:`, or the whole name.
+    pub fn local_name(&self) -> &str {
+        split(&self.name).1
+    }
+}
+
+impl fmt::Display for QualifiedName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+/// Splits `prefix:local` into its prefix and local name.
+pub fn split(name: &str) -> (Option<&str>, &str) {
+    match name.split_once(':') {
+        Some((prefix, local_name)) => (Some(prefix), local_name),
+        None => (None, name),
+    }
+}
+
+/// Returns the prefix declared by a namespace declaration attribute.
+///
+/// `xmlns` declares the default namespace (`Some(None)`), `xmlns:p` declares
+/// `p` (`Some(Some("p"))`), anything else is not a declaration (`None`).
+pub fn declared_prefix(attribute_name: &str) -> Option<Option<&str>> {
+    match split(attribute_name) {
+        (None, "xmlns") => Some(None),
+        (Some("xmlns"), prefix) => Some(Some(prefix)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn qualified_name_should_split_prefix() {
+        let name = QualifiedName::new("inkscape:label", None);
+        assert_eq!(name.prefix(), Some("inkscape"));
+        assert_eq!(name.local_name(), "label");
+
+        let name = QualifiedName::new("rect", Some(SVG_NAMESPACE));
+        assert_eq!(name.prefix(), None);
+        assert_eq!(name.local_name(), "rect");
+    }
+
+    #[test]
+    fn declared_prefix_should_recognize_declarations() {
+        assert_eq!(declared_prefix("xmlns"), Some(None));
+        assert_eq!(declared_prefix("xmlns:xlink"), Some(Some("xlink")));
+        assert_eq!(declared_prefix("xlink:href"), None);
+        assert_eq!(declared_prefix("fill"), None);
+    }
+}
@@ -1,5 +1,12 @@
-use crate::{Element, element::ElementType, element::attributes::Attribute};
-use slotmap::{SlotMap, new_key_type};
+use crate::{
+    Element,
+    element::ElementType,
+    element::attributes::Attribute,
+    parser::{ParseMode, lossless::Source},
+    serialize::{SerializeOptions, Serializer},
+    span::{NodeSpan, Span},
+};
+use slotmap::{SecondaryMap, SlotMap, new_key_type};
 use std::fmt;
 
 /// Arena-backed AST for SVG documents.
@@ -12,6 +19,14 @@ pub struct AST {
     pub nodes: SlotMap<NodeId, Node>,
     /// Root-level children of the AST.
     pub children: Vec<NodeId>,
+    /// Source locations of parsed nodes. Nodes created programmatically have none.
+    pub spans: SecondaryMap<NodeId, NodeSpan>,
+    /// The text the tree was parsed from, for [`AST::to_svg_lossless`].
+    pub(crate) source: Option<Source>,
+    /// The mode the tree was parsed in, which [`AST::reparse`] keeps.
+    pub(crate) mode: ParseMode,
+    /// The nesting limit the tree was parsed with, or `None` for the default.
+    pub(crate) max_depth: Option<usize>,
 }
 
 new_key_type! {
@@ -46,10 +61,49 @@ pub struct CDataNode {
     pub parent: Option<NodeId>,
 }
 
+/// An XML declaration (`<?xml version="1.0" ...?>`) in the SVG tree.
+#[derive(Clone, PartialEq, Debug)]
+pub struct XmlDeclarationNode {
+    /// The `version` pseudo-attribute.
+    pub version: String,
+    /// The `encoding` pseudo-attribute, if present.
+    pub encoding: Option<String>,
+    /// The `standalone` pseudo-attribute, if present.
+    pub standalone: Option<String>,
+    /// Parent node ID, always `None` for a well-formed document.
+    pub parent: Option<NodeId>,
+}
+
+/// A document type declaration (`<!DOCTYPE svg ...>`) in the SVG tree.
+#[derive(Clone, PartialEq, Debug)]
+pub struct DoctypeNode {
+    /// The root element name, usually `svg`.
+    pub name: String,
+    /// The external identifier, e.g. `PUBLIC "-//W3C//DTD SVG 1.1//EN" "..."`.
+    pub external_id: Option<String>,
+    /// The internal subset (without the `[` / `]` delimiters).
+    pub internal_subset: Option<String>,
+    /// Parent node ID, always `None` for a well-formed document.
+    pub parent: Option<NodeId>,
+}
+
+/// A processing instruction (`<?target data?>`) in the SVG tree,
+/// such as `<?xml-stylesheet href="style.css"?>`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ProcessingInstructionNode {
+    /// The target name.
+    pub target: String,
+    /// Everything after the target, without leading whitespace.
+    pub data: String,
+    /// Parent node ID, or `None` for root-level processing instructions.
+    pub parent: Option<NodeId>,
+}
+
 /// A node in the SVG AST.
 ///
-/// Wraps one of four variants: an [`Element`], [`TextNode`], [`CommentNode`],
-/// or [`CDataNode`]. Each variant carries a parent pointer.
+/// Wraps an [`Element`], character data ([`TextNode`], [`CDataNode`]), a
+/// [`CommentNode`], or one of the XML prolog nodes ([`XmlDeclarationNode`],
+/// [`DoctypeNode`], [`ProcessingInstructionNode`]). Each variant carries a parent pointer.
 #[derive(Clone, PartialEq, Debug)]
 pub enum Node {
     /// A text node containing character data.
@@ -60,22 +114,23 @@ pub enum Node {
     Comment(CommentNode),
     /// A CDATA section node.
     CData(CDataNode),
+    /// An XML declaration.
+    XmlDeclaration(XmlDeclarationNode),
+    /// A document type declaration.
+    Doctype(DoctypeNode),
+    /// A processing instruction.
+    ProcessingInstruction(ProcessingInstructionNode),
 }
 
 impl Node {
     /// Serializes this node to SVG format using the given writer.
-    pub fn write_svg(&self, ast: &AST, f: &mut impl fmt::Write, indent: usize) -> fmt::Result {
-        match self {
-            Node::Text(text_node) => {
-                for _ in 0..indent {
-                    write!(f, "  ")?;
-                }
-                write!(f, "{}\n", text_node.content)
-            }
-            Node::Element(element) => element.write_svg(ast, f, indent),
-            Node::Comment(comment_node) => write!(f, "<!-- {} -->", comment_node.content),
-            Node::CData(cdata_node) => write!(f, "<![CDATA[{}]]>", cdata_node.content),
-        }
+    pub fn write_svg(
+        &self,
+        ast: &AST,
+        f: &mut impl fmt::Write,
+        options: &SerializeOptions,
+    ) -> fmt::Result {
+        Serializer::new(ast, options, f).write_node(None, self, 0)
     }
 
     /// Returns a reference to the inner [`Element`] if this is an `Element` node.
@@ -133,6 +188,9 @@ impl Node {
             Node::Element(e) => e.parent,
             Node::Comment(c) => c.parent,
             Node::CData(c) => c.parent,
+            Node::XmlDeclaration(d) => d.parent,
+            Node::Doctype(d) => d.parent,
+            Node::ProcessingInstruction(p) => p.parent,
         }
     }
 }
@@ -150,18 +208,6 @@ impl Node {
 /// let mut ast = AST::default();
 /// ```
 impl AST {
-    /// Serializes the entire AST to an SVG string.
-    pub fn to_svg(&self) -> String {
-        let mut s = String::new();
-        for node_id in &self.children {
-            let Some(node) = self.nodes.get(*node_id) else {
-                continue;
-            };
-            node.write_svg(self, &mut s, 0).unwrap();
-        }
-        s
-    }
-
     /// Inserts a node into the arena and returns its ID.
     ///
     /// The node is not attached to any parent — use [`append_child`], [`prepend_child`],
@@ -180,13 +226,8 @@ impl AST {
     /// Parent pointers and children lists are updated to reflect the removal.
     pub fn remove_node(&mut self, id: NodeId) -> Option<Node> {
         let node = self.nodes.remove(id)?;
-        let parent_id = match &node {
-            Node::Text(t) => t.parent,
-            Node::Element(e) => e.parent,
-            Node::Comment(c) => c.parent,
-            Node::CData(c) => c.parent,
-        };
-        match parent_id {
+        self.spans.remove(id);
+        match node.parent_id() {
             Some(pid) => {
                 if let Some(Node::Element(element)) = self.nodes.get_mut(pid) {
                     element.children.retain(|&cid| cid != id);
@@ -247,55 +288,49 @@ impl AST {
     /// [`append_child`]: AST::append_child
     /// [`prepend_child`]: AST::prepend_child
     pub fn clone_node(&mut self, id: NodeId) -> NodeId {
-        let node = self.nodes.get(id).expect("Node must exist").clone();
-        self.clone_subtree(node)
-    }
-
-    fn clone_subtree(&mut self, node: Node) -> NodeId {
-        match node {
-            Node::Text(text) => {
-                let id = self.nodes.insert(Node::Text(TextNode {
-                    content: text.content,
-                    parent: None,
-                }));
-                id
-            }
-            Node::Element(e) => {
-                let cloned = Element {
-                    element_type: e.element_type.clone(),
-                    attributes: e.attributes.clone(),
-                    children: vec![],
-                    parent: None,
-                };
-
-                let id = self.nodes.insert(Node::Element(cloned));
-
-                for child_id in &e.children {
-                    let child_node = self.nodes.get(*child_id).expect("Child must exist").clone();
-                    let new_child_id = self.clone_subtree(child_node);
-                    if let Some(Node::Element(element)) = self.nodes.get_mut(id) {
-                        element.children.push(new_child_id);
-                    }
-                    self.set_parent(new_child_id, Some(id));
-                }
-
-                id
-            }
-            Node::Comment(comment) => {
-                let id = self.nodes.insert(Node::Comment(CommentNode {
-                    content: comment.content,
-                    parent: None,
-                }));
-                id
-            }
-            Node::CData(cdata) => {
-                let id = self.nodes.insert(Node::CData(CDataNode {
-                    content: cdata.content,
-                    parent: None,
-                }));
-                id
-            }
-        }
+        let node = self.nodes.get(id).expect("Node must exist");
+        let clone = self.nodes.insert(shallow_clone(node));
+
+        // pairs of a node to clone and the clone of its parent
+        let mut pending: Vec<_> = self
+            .get_children(id)
+            .into_iter()
+            .flatten()
+            .rev()
+            .map(|&child| (child, clone))
+            .collect();
+        while let Some((id, parent)) = pending.pop() {
+            let node = self.nodes.get(id).expect("Child must exist");
+            let child = self.nodes.insert(shallow_clone(node));
+            if let Some(Node::Element(element)) = self.nodes.get_mut(parent) {
+                element.children.push(child);
+            }
+            self.set_parent(child, Some(parent));
+
+            pending.extend(
+                self.get_children(id)
+                    .into_iter()
+                    .flatten()
+                    .rev()
+                    .map(|&grandchild| (grandchild, child)),
+            );
+        }
+
+        clone
+    }
+
+    /// Returns the IDs of all nodes in document order, each node before its
+    /// children.
+    ///
+    /// Uses an explicit stack, so any nesting depth can be traversed.
+    pub fn depth_first(&self) -> impl Iterator<Item = NodeId> + '_ {
+        let mut pending: Vec<_> = self.children.iter().rev().copied().collect();
+
+        std::iter::from_fn(move || {
+            let id = pending.pop()?;
+            pending.extend(self.get_children(id).into_iter().flatten().rev());
+            Some(id)
+        })
     }
 
     /// Finds the first node with an `id` attribute matching `id_value`.
@@ -303,41 +338,12 @@ impl AST {
     /// Performs a depth-first traversal of the entire tree.
     /// Returns `None` if no matching node is found.
     pub fn find_by_id(&self, id_value: &str) -> Option<NodeId> {
-        let mut found = None;
-        self.find_by_id_recursive(id_value, &mut found);
-        found
-    }
-
-    fn find_by_id_recursive(&self, id_value: &str, found: &mut Option<NodeId>) {
-        for node_id in &self.children {
-            self.check_id_recursive(id_value, found, *node_id);
-        }
-    }
-
-    fn check_id_recursive(&self, id_value: &str, found: &mut Option<NodeId>, node_id: NodeId) {
-        if let Some(Node::Element(e)) = self.nodes.get(node_id) {
-            for attr in &e.attributes {
-                if let Attribute::Id(val) = attr {
-                    if val == id_value {
-                        *found = Some(node_id);
-                        return;
-                    }
-                }
-            }
-        }
-
-        if found.is_some() {
-            return;
-        }
-
-        if let Some(Node::Element(e)) = self.nodes.get(node_id) {
-            for child_id in &e.children {
-                self.check_id_recursive(id_value, found, *child_id);
-                if found.is_some() {
-                    return;
-                }
-            }
-        }
+        self.depth_first().find(|&node_id| {
+            matches!(
+                self.nodes.get(node_id),
+                Some(Node::Element(e)) if e.attributes.iter().any(|attr| matches!(attr, Attribute::Id(val) if val == id_value))
+            )
+        })
     }
 
     /// Finds all element nodes matching the given [`ElementType`].
@@ -345,38 +351,7 @@ impl AST {
     /// Performs a depth-first traversal of the entire tree.
     /// Returns an empty vector if no matches are found.
     pub fn find_by_type(&self, element_type: crate::element::ElementType) -> Vec<NodeId> {
-        let mut result = Vec::new();
-        self.find_by_type_recursive(element_type, &mut result);
-        result
-    }
-
-    fn find_by_type_recursive(
-        &self,
-        element_type: crate::element::ElementType,
-        result: &mut Vec<NodeId>,
-    ) {
-        for node_id in &self.children {
-            self.check_type_recursive(element_type, result, *node_id);
-        }
-    }
-
-    fn check_type_recursive(
-        &self,
-        element_type: crate::element::ElementType,
-        result: &mut Vec<NodeId>,
-        node_id: NodeId,
-    ) {
-        if let Some(Node::Element(e)) = self.nodes.get(node_id) {
-            if e.element_type == element_type {
-                result.push(node_id);
-            }
-        }
-
-        if let Some(Node::Element(e)) = self.nodes.get(node_id) {
-            for child_id in &e.children {
-                self.check_type_recursive(element_type, result, *child_id);
-            }
-        }
+        self.find_all(|node| matches!(node, Node::Element(e) if e.element_type == element_type))
     }
 
     /// Finds all nodes matching the given predicate.
@@ -386,35 +361,9 @@ impl AST {
     where
         F: Fn(&Node) -> bool,
     {
-        let mut result = Vec::new();
-        self.find_all_recursive(&predicate, &mut result);
-        result
-    }
-
-    fn find_all_recursive<F>(&self, predicate: &F, result: &mut Vec<NodeId>)
-    where
-        F: Fn(&Node) -> bool,
-    {
-        for node_id in &self.children {
-            self.check_all_recursive(predicate, result, *node_id);
-        }
-    }
-
-    fn check_all_recursive<F>(&self, predicate: &F, result: &mut Vec<NodeId>, node_id: NodeId)
-    where
-        F: Fn(&Node) -> bool,
-    {
-        if let Some(node) = self.nodes.get(node_id) {
-            if predicate(node) {
-                result.push(node_id);
-            }
-        }
-
-        if let Some(Node::Element(e)) = self.nodes.get(node_id) {
-            for child_id in &e.children {
-                self.check_all_recursive(predicate, result, *child_id);
-            }
-        }
+        self.depth_first()
+            .filter(|&node_id| self.nodes.get(node_id).is_some_and(&predicate))
+            .collect()
     }
 
     fn get_parent_id(&self, id: NodeId) -> Option<NodeId> {
@@ -422,7 +371,7 @@ impl AST {
         node.parent_id()
     }
 
-    fn set_parent(&mut self, id: NodeId, parent: Option<NodeId>) {
+    pub(crate) fn set_parent(&mut self, id: NodeId, parent: Option<NodeId>) {
         if let Some(Node::Text(text)) = self.nodes.get_mut(id) {
             text.parent = parent;
         }
@@ -435,6 +384,15 @@ impl AST {
         if let Some(Node::CData(cdata)) = self.nodes.get_mut(id) {
             cdata.parent = parent;
         }
+        if let Some(Node::XmlDeclaration(declaration)) = self.nodes.get_mut(id) {
+            declaration.parent = parent;
+        }
+        if let Some(Node::Doctype(doctype)) = self.nodes.get_mut(id) {
+            doctype.parent = parent;
+        }
+        if let Some(Node::ProcessingInstruction(instruction)) = self.nodes.get_mut(id) {
+            instruction.parent = parent;
+        }
     }
 
     fn get_children(&self, id: NodeId) -> Option<&Vec<NodeId>> {
@@ -806,19 +764,29 @@ impl AST {
     /// Validates the entire AST tree.
     ///
     /// Checks parent pointer consistency and element child constraints
-    /// (via [`is_allowed_as_child`]).
+    /// (via [`is_allowed_as_child`]), and reports unknown elements and attributes.
     ///
     /// [`is_allowed_as_child`]: crate::element::Element::is_allowed_as_child
     pub fn validate(&self) -> Vec<ValidationIssue> {
         let mut issues = Vec::new();
 
-        for node_id in &self.children {
-            self.validate_node(*node_id, None, &mut issues);
+        // pairs of a node and the parent it was found under, in document order
+        let mut pending: Vec<_> = self.children.iter().rev().map(|&id| (id, None)).collect();
+        while let Some((node_id, parent_id)) = pending.pop() {
+            self.validate_node(node_id, parent_id, &mut issues);
+            pending.extend(
+                self.get_children(node_id)
+                    .into_iter()
+                    .flatten()
+                    .rev()
+                    .map(|&child| (child, Some(node_id))),
+            );
         }
 
         issues
     }
 
+    /// Checks one node found under `parent_id`, without its children.
     fn validate_node(
         &self,
         node_id: NodeId,
@@ -864,8 +832,14 @@ impl AST {
         }
 
         if let Node::Element(element) = node {
-            for child_id in &element.children {
-                self.validate_node(*child_id, Some(node_id), issues);
+            if let ElementType::Unknown(name) = &element.element_type {
+                issues.push(ValidationIssue::UnknownElement(node_id, name.clone()));
+            }
+
+            for attribute in &element.attributes {
+                if let Attribute::Unknown(name, _) = attribute {
+                    issues.push(ValidationIssue::UnknownAttribute(node_id, name.clone()));
+                }
             }
         }
     }
@@ -885,6 +859,42 @@ impl AST {
         true
     }
 
+    /// Returns where the node was found in the source, or `None` if it was not parsed.
+    pub fn node_span(&self, id: NodeId) -> Option<&Span> {
+        self.spans.get(id).map(|node_span| &node_span.span)
+    }
+
+    /// Returns where the named attribute of an element was found in the source.
+    pub fn attribute_span(&self, element_id: NodeId, name: &str) -> Option<&Span> {
+        self.spans
+            .get(element_id)?
+            .attributes
+            .iter()
+            .find(|attribute| attribute.name == name)
+            .map(|attribute| &attribute.span)
+    }
+
+    /// Returns `true` if whitespace in the content of element `id` is significant
+    /// and must be kept as written.
+    ///
+    /// That is the case inside text content elements, where it separates words,
+    /// and where the nearest `white-space` or `xml:space` preserves it.
+    pub fn is_whitespace_significant(&self, id: NodeId) -> bool {
+        let mut preserves = None;
+        let mut current = Some(id);
+
+        while let Some(Node::Element(element)) = current.and_then(|id| self.nodes.get(id)) {
+            if element.element_type.is_text_content() {
+                return true;
+            }
+
+            preserves = preserves.or_else(|| element.preserves_whitespace());
+            current = element.parent;
+        }
+
+        preserves.unwrap_or(false)
+    }
+
     /// Returns the first root-level node ID, or `None` if there are no root nodes.
     pub fn find_root(&self) -> Option<NodeId> {
         self.children.first().copied()
@@ -915,6 +925,7 @@ impl AST {
     ) -> NodeId {
         let mut new_element = Element {
             element_type: new_element_type,
+            prefix: None,
             attributes: vec![],
             children: vec![],
             parent: None,
@@ -989,9 +1000,31 @@ pub enum ValidationIssue {
     ///
     /// Fields: `(parent_id, missing_node_id)`.
     MissingNode(Option<NodeId>, NodeId),
+
+    /// An element that is not a known SVG element.
+    ///
+    /// Fields: `(element_id, name)`.
+    UnknownElement(NodeId, String),
+
+    /// An attribute that is not a known SVG attribute.
+    ///
+    /// Fields: `(element_id, name)`.
+    UnknownAttribute(NodeId, String),
 }
 
 impl ValidationIssue {
+    /// Returns the node the issue is about, for looking up its [`AST::node_span`].
+    pub fn node_id(&self) -> Option<NodeId> {
+        match self {
+            ValidationIssue::ParentMismatch(node_id, _, _) => Some(*node_id),
+            ValidationIssue::ChildNotAllowed(_, _, child_id, _) => Some(*child_id),
+            ValidationIssue::DuplicateChild(element_id, _) => Some(*element_id),
+            ValidationIssue::MissingNode(parent_id, _) => *parent_id,
+            ValidationIssue::UnknownElement(element_id, _) => Some(*element_id),
+            ValidationIssue::UnknownAttribute(element_id, _) => Some(*element_id),
+        }
+    }
+
     /// Returns a human-readable description of the validation issue.
     pub fn message(&self) -> String {
         match self {
@@ -1019,19 +1052,44 @@ impl ValidationIssue {
                     parent_id, node_id
                 )
             }
+            ValidationIssue::UnknownElement(element_id, name) => {
+                format!("Unknown element <{}> (node {:?})", name, element_id)
+            }
+            ValidationIssue::UnknownAttribute(element_id, name) => {
+                format!("Unknown attribute {:?} on node {:?}", name, element_id)
+            }
+        }
+    }
+}
+
+/// Clones a node without its children and its parent.
+fn shallow_clone(node: &Node) -> Node {
+    let mut clone = node.clone();
+    match &mut clone {
+        Node::Element(element) => {
+            element.children.clear();
+            element.parent = None;
         }
+        Node::Text(TextNode { parent, .. })
+        | Node::Comment(CommentNode { parent, .. })
+        | Node::CData(CDataNode { parent, .. })
+        | Node::XmlDeclaration(XmlDeclarationNode { parent, .. })
+        | Node::Doctype(DoctypeNode { parent, .. })
+        | Node::ProcessingInstruction(ProcessingInstructionNode { parent, .. }) => *parent = None,
     }
+    clone
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::element::attributes::Fill;
-    use crate::element::types::{Color, Paint as TypePaint, Percentage};
+    use crate::element::types::{Color, Paint as TypePaint};
 
     fn make_element(element_type: ElementType) -> Element {
         Element {
             element_type,
+            prefix: None,
             attributes: vec![],
             children: vec![],
             parent: None,
@@ -2327,6 +2385,27 @@ mod tests {
         assert!(invalid_count > 0);
     }
 
+    #[test]
+    fn validate_should_report_unknown_elements_and_attributes() {
+        let mut ast = build_sample_svg();
+        let svg_id = ast.children[0];
+
+        let mut blink = make_element(ElementType::Unknown("blink".to_string()));
+        blink
+            .attributes
+            .push(Attribute::Unknown("speed".to_string(), "fast".to_string()));
+        let blink_id = ast.insert_node(Node::Element(blink));
+        ast.append_child(svg_id, blink_id);
+
+        assert_eq!(
+            ast.validate(),
+            vec![
+                ValidationIssue::UnknownElement(blink_id, "blink".to_string()),
+                ValidationIssue::UnknownAttribute(blink_id, "speed".to_string()),
+            ]
+        );
+    }
+
     #[test]
     fn validate_should_find_duplicate_children() {
         let mut ast = build_sample_svg();
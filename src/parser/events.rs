@@ -0,0 +1,1172 @@
+//! A streaming, SAX-style view of a document.
+//!
+//! [`Events`] turns the tokens of a [`Lexer`] into [`Event`]s without building
+//! an [`AST`](crate::AST). Memory use is bounded by the nesting depth of the
+//! document rather than its size. [`Parser`](crate::Parser) builds the AST on
+//! top of it.
+
+use std::{
+    borrow::Cow,
+    collections::{HashMap, VecDeque},
+    ops::Range,
+};
+
+use crate::{
+    element::ElementType,
+    element::attributes::{Attribute, Path, Quote},
+    entity::{self, DecodeError, Entities},
+    error::{Construct, Diagnostic, ParseError, ParseErrorKind, Severity},
+    lexer::{Lexer, TokenSource},
+    namespace::{
+        self, QualifiedName, SVG_NAMESPACE, XLINK_NAMESPACE, XML_NAMESPACE, XMLNS_NAMESPACE,
+    },
+    parser::{
+        ParseMode,
+        ast::{DoctypeNode, ProcessingInstructionNode, XmlDeclarationNode},
+        prolog,
+    },
+    token::{Token, TokenKind},
+};
+
+/// The default number of elements that may be nested in each other.
+pub const DEFAULT_MAX_DEPTH: usize = 1024;
+
+/// A piece of the document, in source order.
+///
+/// Every `span` is the byte range of the event in the source.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Event<'input> {
+    /// A start tag, or an empty-element tag (`<rect/>`), which is immediately
+    /// followed by its [`Event::EndElement`].
+    StartElement {
+        ty: ElementType,
+        /// The prefix of an SVG element written as e.g. `<svg:rect>`.
+        prefix: Option<String>,
+        attributes: Vec<Attribute>,
+        /// Where each attribute was found, in the same order as `attributes`.
+        sources: Vec<SourceAttribute<'input>>,
+        span: Range<usize>,
+    },
+    /// The end of an element: its closing tag, or the `/>` of an empty-element
+    /// tag. An element closed in lenient mode has an empty span where it was
+    /// closed, i.e. at the end of input or before the closing tag of a parent.
+    EndElement { ty: ElementType, span: Range<usize> },
+    /// Text content, with references decoded.
+    Text {
+        content: Cow<'input, str>,
+        span: Range<usize>,
+    },
+    /// The content of a comment.
+    Comment {
+        content: Cow<'input, str>,
+        span: Range<usize>,
+    },
+    /// The content of a CDATA section.
+    CData {
+        content: Cow<'input, str>,
+        span: Range<usize>,
+    },
+    XmlDeclaration {
+        declaration: XmlDeclarationNode,
+        span: Range<usize>,
+    },
+    Doctype {
+        doctype: DoctypeNode,
+        span: Range<usize>,
+    },
+    ProcessingInstruction {
+        instruction: ProcessingInstructionNode,
+        span: Range<usize>,
+    },
+}
+
+impl Event<'_> {
+    /// Returns the byte range of the event in the source.
+    pub fn span(&self) -> &Range<usize> {
+        match self {
+            Event::StartElement { span, .. }
+            | Event::EndElement { span, .. }
+            | Event::Text { span, .. }
+            | Event::Comment { span, .. }
+            | Event::CData { span, .. }
+            | Event::XmlDeclaration { span, .. }
+            | Event::Doctype { span, .. }
+            | Event::ProcessingInstruction { span, .. } => span,
+        }
+    }
+}
+
+/// Where an attribute of an [`Event::StartElement`] was found in the source.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SourceAttribute<'input> {
+    /// The attribute name as written in the source.
+    pub name: Cow<'input, str>,
+    /// From the start of the name to the end of the value, including quotes.
+    pub span: Range<usize>,
+    /// The quote character that delimited the value.
+    pub quote: Quote,
+}
+
+#[derive(Debug, Default, PartialEq)]
+enum State {
+    #[default]
+    Text, // outside of any tag
+    TagOpen, // after <
+    TagName,
+    AttributeName,
+    AttributeEquals,
+    AttributeValueOpeningQuote,
+    AttributeValue,
+    AttributeValueClosingQuote,
+    TagClose, // after </
+    TagCloseName,
+}
+
+impl State {
+    /// Token kinds accepted in this state, used for error reporting.
+    fn expected(&self) -> &'static [TokenKind] {
+        match self {
+            State::Text => &[
+                TokenKind::LessThan,
+                TokenKind::LessThanSlash,
+                TokenKind::Text,
+                TokenKind::Comment,
+                TokenKind::CData,
+                TokenKind::XmlDeclaration,
+                TokenKind::Doctype,
+                TokenKind::ProcessingInstruction,
+            ],
+            State::TagOpen | State::TagClose => &[TokenKind::Identifier],
+            State::TagName | State::AttributeValueClosingQuote => &[
+                TokenKind::Identifier,
+                TokenKind::GreaterThan,
+                TokenKind::SlashGreaterThan,
+            ],
+            State::AttributeName => &[
+                TokenKind::Equals,
+                TokenKind::Identifier,
+                TokenKind::GreaterThan,
+                TokenKind::SlashGreaterThan,
+            ],
+            State::AttributeEquals | State::AttributeValue => &[TokenKind::Quote],
+            State::AttributeValueOpeningQuote => &[TokenKind::Literal, TokenKind::Quote],
+            State::TagCloseName => &[TokenKind::GreaterThan],
+        }
+    }
+
+    /// Returns `true` while inside a start tag, after its name.
+    fn is_in_start_tag(&self) -> bool {
+        matches!(
+            self,
+            State::TagName
+                | State::AttributeName
+                | State::AttributeEquals
+                | State::AttributeValueOpeningQuote
+                | State::AttributeValue
+                | State::AttributeValueClosingQuote
+        )
+    }
+}
+
+/// An element whose closing tag has not been seen yet.
+#[derive(Debug)]
+struct OpenElement<'input> {
+    ty: ElementType,
+    /// The tag name as written in the source.
+    name: Cow<'input, str>,
+    /// Namespaces declared on the element with `xmlns` / `xmlns:prefix`.
+    namespaces: Vec<(Option<Cow<'input, str>>, Cow<'input, str>)>,
+}
+
+/// An element that encloses a re-parsed fragment of a document.
+#[derive(Debug)]
+pub(crate) struct Ancestor {
+    pub(crate) ty: ElementType,
+    /// The tag name as written in the source.
+    pub(crate) name: String,
+    /// Namespaces declared on the element, by prefix.
+    pub(crate) namespaces: Vec<(Option<String>, String)>,
+}
+
+/// A start tag whose attributes are still being read.
+///
+/// The element is only created at the closing `>`, once every `xmlns`
+/// declaration that may affect its own name and attributes is known.
+#[derive(Debug)]
+struct StartTag<'input> {
+    name: Cow<'input, str>,
+    name_span: Range<usize>,
+    /// Offset of the opening `<`.
+    start: usize,
+    attributes: Vec<RawAttribute<'input>>,
+}
+
+/// An attribute as written in the source, before namespace resolution.
+#[derive(Debug)]
+struct RawAttribute<'input> {
+    name: Cow<'input, str>,
+    /// The value with references decoded.
+    value: Cow<'input, str>,
+    span: Range<usize>,
+    quote: Quote,
+}
+
+/// An iterator over the [`Event`]s of a document.
+///
+/// In [`ParseMode::Strict`] the first problem is returned as an error and the
+/// iteration ends. In [`ParseMode::Lenient`] no error is returned; problems are
+/// recorded as [`Diagnostic`]s and the events are repaired, e.g. every open
+/// element gets an [`Event::EndElement`] at the end of input.
+#[derive(Debug)]
+pub struct Events<'input, L = Lexer<'input>> {
+    pub(crate) lexer: L,
+    pub(crate) mode: ParseMode,
+    pub(crate) diagnostics: Vec<Diagnostic>,
+    /// Entities declared in the DOCTYPE, used to decode text and attribute values.
+    entities: Entities,
+    /// Number of bytes declared entities may still expand to in this document.
+    expansion_budget: usize,
+    current_state: State,
+    element_stack: Vec<OpenElement<'input>>,
+    /// The namespaces in scope by prefix, `""` for the default namespace: the
+    /// URIs bound by the open elements, innermost last.
+    scopes: HashMap<Cow<'input, str>, Vec<Cow<'input, str>>>,
+    start_tag: Option<StartTag<'input>>,
+    /// Name of the attribute being parsed and the offset where it starts.
+    current_attribute: Option<(Cow<'input, str>, usize)>,
+    /// The quote character that opened the current attribute value.
+    current_quote: Quote,
+    /// Offset of the `<` or `</` that opened the current tag.
+    tag_start: usize,
+    /// Events ready to be returned. Held back while a closing tag is read, so the
+    /// spans of the elements it closes can be extended up to its `>`.
+    queue: VecDeque<Event<'input>>,
+    finished: bool,
+    /// Whether no token has been read yet, so an XML declaration may follow.
+    at_start: bool,
+    /// Number of enclosing elements on the stack that are not part of the input.
+    base_depth: usize,
+    /// Number of elements that may be open at once, including `base_depth`.
+    pub(crate) max_depth: usize,
+}
+
+impl<'input, L: TokenSource<'input>> Events<'input, L> {
+    /// Creates an event stream over the tokens of `lexer`.
+    pub fn new(lexer: L, mode: ParseMode) -> Self {
+        Self {
+            lexer,
+            mode,
+            diagnostics: vec![],
+            entities: Entities::default(),
+            expansion_budget: entity::MAX_EXPANSION_BYTES,
+            current_state: State::default(),
+            element_stack: vec![],
+            scopes: HashMap::new(),
+            start_tag: None,
+            current_attribute: None,
+            current_quote: Quote::default(),
+            tag_start: 0,
+            queue: VecDeque::new(),
+            finished: false,
+            at_start: true,
+            base_depth: 0,
+            max_depth: DEFAULT_MAX_DEPTH,
+        }
+    }
+
+    /// Creates an event stream over a fragment of a document, read as the
+    /// content of `ancestors` (outermost first).
+    ///
+    /// Closing tags in the fragment cannot close the ancestors, and elements
+    /// still open at its end are unterminated.
+    pub(crate) fn fragment(
+        lexer: L,
+        mode: ParseMode,
+        ancestors: Vec<Ancestor>,
+        entities: Entities,
+    ) -> Self {
+        let mut events = Self::new(lexer, mode);
+        events.entities = entities;
+        events.at_start = false;
+        events.base_depth = ancestors.len();
+        let element_stack: Vec<_> = ancestors
+            .into_iter()
+            .map(|ancestor| OpenElement {
+                ty: ancestor.ty,
+                name: Cow::Owned(ancestor.name),
+                namespaces: ancestor
+                    .namespaces
+                    .into_iter()
+                    .map(|(prefix, uri)| (prefix.map(Cow::Owned), Cow::Owned(uri)))
+                    .collect(),
+            })
+            .collect();
+        for open in &element_stack {
+            events.bind(&open.namespaces);
+        }
+        events.element_stack = element_stack;
+
+        events
+    }
+
+    /// Sets the number of elements that may be nested in each other, which is
+    /// [`DEFAULT_MAX_DEPTH`] by default.
+    ///
+    /// A deeper element is a [`ParseErrorKind::NestingTooDeep`] error; in
+    /// lenient mode, the events end before it.
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Returns the problems recorded so far in lenient mode.
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    /// Returns the problems recorded in lenient mode.
+    pub fn into_diagnostics(self) -> Vec<Diagnostic> {
+        self.diagnostics
+    }
+
+    /// Reads tokens until there is an event to return or the input ends.
+    fn fill(&mut self) -> Result<(), ParseError> {
+        while !self.finished && (self.queue.is_empty() || self.current_state == State::TagCloseName)
+        {
+            match self.lexer.advance() {
+                Ok(Some(token)) => self.consume(token)?,
+                Ok(None) => self.finish()?,
+                Err(error) => {
+                    // lexer errors only happen at the end of input
+                    self.report(Severity::Error, error)?;
+                    self.finish()?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn consume(&mut self, token: Token<'input>) -> Result<(), ParseError> {
+        let at_start = std::mem::replace(&mut self.at_start, false);
+
+        match (&self.current_state, token.kind) {
+            (State::Text, TokenKind::LessThan) => {
+                self.tag_start = token.span.start;
+                self.current_state = State::TagOpen;
+            }
+            (State::TagOpen, TokenKind::Identifier) => {
+                self.current_state = State::TagName;
+                self.start_tag = Some(StartTag {
+                    name: token.value,
+                    name_span: token.span,
+                    start: self.tag_start,
+                    attributes: vec![],
+                });
+            }
+            (State::TagName | State::AttributeValueClosingQuote, TokenKind::Identifier) => {
+                self.current_attribute = Some((token.value, token.span.start));
+                self.current_state = State::AttributeName;
+            }
+            (State::AttributeName, TokenKind::Equals) => {
+                self.current_state = State::AttributeEquals;
+            }
+            (State::AttributeName, TokenKind::Identifier) => {
+                self.push_empty_attribute();
+
+                self.current_state = State::AttributeName;
+                self.current_attribute = Some((token.value, token.span.start));
+            }
+            (State::AttributeName, TokenKind::GreaterThan) => {
+                self.push_empty_attribute();
+
+                self.current_state = State::Text;
+                self.open_element(token.span.end)?;
+            }
+            (State::AttributeEquals, TokenKind::Quote) => {
+                self.current_quote =
+                    Quote::from_byte(token.value.as_bytes()[0]).unwrap_or_default();
+                self.current_state = State::AttributeValueOpeningQuote;
+            }
+            (State::AttributeValueOpeningQuote, TokenKind::Quote) => {
+                self.current_attribute = None;
+                self.current_state = State::AttributeValueClosingQuote;
+            }
+            (State::AttributeValueOpeningQuote, TokenKind::Literal) => {
+                let (attribute_name, start) = self.current_attribute.take().unwrap_or_default();
+                let value = self.decode(token.value, token.span.clone())?;
+                // the literal is always followed by its closing quote
+                self.push_attribute(
+                    attribute_name,
+                    value,
+                    start..token.span.end + 1,
+                    self.current_quote,
+                );
+
+                self.current_state = State::AttributeValue;
+            }
+            (State::AttributeValue, TokenKind::Quote) => {
+                self.current_state = State::AttributeValueClosingQuote;
+            }
+            (State::TagName | State::AttributeValueClosingQuote, TokenKind::GreaterThan) => {
+                // here we are in a <tagname>
+                self.current_state = State::Text;
+                self.open_element(token.span.end)?;
+            }
+            (
+                State::TagName | State::AttributeValueClosingQuote | State::AttributeName,
+                TokenKind::SlashGreaterThan,
+            ) => {
+                // here we are in a <tagname/>
+                if self.current_state == State::AttributeName {
+                    self.push_empty_attribute();
+                }
+
+                self.current_state = State::Text;
+                self.open_element(token.span.end)?;
+                if !self.finished {
+                    self.close_innermost(token.span);
+                }
+            }
+            (State::Text, TokenKind::LessThanSlash) => {
+                self.tag_start = token.span.start;
+                self.current_state = State::TagClose;
+            }
+            (State::TagClose, TokenKind::Identifier) => {
+                self.current_state = State::TagCloseName;
+                self.close_tag(&token.value, token.span)?;
+            }
+            (State::TagCloseName, TokenKind::GreaterThan) => {
+                self.current_state = State::Text;
+                self.end_close_tag(token.span.end);
+            }
+            (State::TagCloseName, TokenKind::SlashGreaterThan) => {
+                // `</g/>` is a typo for `</g>`, the element was already closed
+                self.report(
+                    Severity::Warning,
+                    ParseError::unexpected(
+                        token.kind,
+                        token.span.clone(),
+                        State::TagCloseName.expected(),
+                    ),
+                )?;
+
+                self.current_state = State::Text;
+                self.end_close_tag(token.span.end);
+            }
+            (State::Text, TokenKind::Text) => {
+                let content = self.decode(token.value, token.span.clone())?;
+                self.queue.push_back(Event::Text {
+                    content,
+                    span: token.span,
+                });
+            }
+            (State::Text, TokenKind::CData) => {
+                self.queue.push_back(Event::CData {
+                    content: trim(token.value, 9, 3),
+                    span: token.span,
+                });
+            }
+            (State::Text, TokenKind::Comment) => {
+                self.queue.push_back(Event::Comment {
+                    content: trim(token.value, 4, 3),
+                    span: token.span,
+                });
+            }
+            // only the very start of the document may declare it; in lenient
+            // mode, a declaration anywhere else is dropped
+            (State::Text, TokenKind::XmlDeclaration) if !at_start => {
+                self.report(
+                    Severity::Error,
+                    ParseError::unexpected(token.kind, token.span, &[]),
+                )?;
+            }
+            (State::Text, TokenKind::XmlDeclaration) => {
+                match prolog::xml_declaration(&token.value) {
+                    Some(declaration) => self.queue.push_back(Event::XmlDeclaration {
+                        declaration,
+                        span: token.span,
+                    }),
+                    None => self.report_malformed(Construct::XmlDeclaration, token.span)?,
+                }
+            }
+            (State::Text, TokenKind::Doctype) => match prolog::doctype(&token.value) {
+                Some(doctype) => {
+                    if let Some(internal_subset) = &doctype.internal_subset {
+                        self.entities = Entities::from_internal_subset(internal_subset);
+                    }
+
+                    self.queue.push_back(Event::Doctype {
+                        doctype,
+                        span: token.span,
+                    })
+                }
+                None => self.report_malformed(Construct::Doctype, token.span)?,
+            },
+            (State::Text, TokenKind::ProcessingInstruction) => {
+                match prolog::processing_instruction(&token.value) {
+                    Some(instruction) => self.queue.push_back(Event::ProcessingInstruction {
+                        instruction,
+                        span: token.span,
+                    }),
+                    None => self.report_malformed(Construct::ProcessingInstruction, token.span)?,
+                }
+            }
+            (state, kind) => {
+                self.report(
+                    Severity::Error,
+                    ParseError::unexpected(kind, token.span.clone(), state.expected()),
+                )?;
+
+                self.recover(token)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Resynchronizes the state machine after an unexpected token in lenient mode.
+    fn recover(&mut self, token: Token<'input>) -> Result<(), ParseError> {
+        match token.kind {
+            // `<rect x=5>`: treat the identifier as an unquoted value
+            TokenKind::Identifier if self.current_state == State::AttributeEquals => {
+                let (attribute_name, start) = self.current_attribute.take().unwrap_or_default();
+                let value = self.decode(token.value, token.span.clone())?;
+                self.push_attribute(
+                    attribute_name,
+                    value,
+                    start..token.span.end,
+                    Quote::default(),
+                );
+
+                self.current_state = State::AttributeValueClosingQuote;
+            }
+            TokenKind::GreaterThan => {
+                if self.current_state.is_in_start_tag() {
+                    self.open_element(token.span.end)?;
+                }
+
+                self.current_attribute = None;
+                self.current_state = State::Text;
+            }
+            TokenKind::SlashGreaterThan => {
+                self.current_attribute = None;
+
+                if self.current_state.is_in_start_tag() {
+                    self.open_element(token.span.end)?;
+                    if !self.finished {
+                        self.close_innermost(token.span);
+                    }
+                }
+
+                self.current_state = State::Text;
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Handles the end of input.
+    ///
+    /// In lenient mode, an unfinished start tag is completed and every element
+    /// that is still open is closed automatically.
+    fn finish(&mut self) -> Result<(), ParseError> {
+        let eof = self.lexer.end();
+        self.finished = true;
+
+        if self.current_state != State::Text {
+            self.report(
+                Severity::Error,
+                ParseError::unterminated(Construct::Tag, eof..eof),
+            )?;
+
+            self.open_element(eof)?;
+            self.end_close_tag(eof);
+            self.current_state = State::Text;
+        }
+
+        while self.element_stack.len() > self.base_depth {
+            let open = &self.element_stack[self.element_stack.len() - 1];
+            self.report(
+                Severity::Error,
+                ParseError::unterminated(Construct::Element(open.ty.clone()), eof..eof),
+            )?;
+            self.close_implicitly(eof);
+        }
+
+        Ok(())
+    }
+
+    /// Ends the input early at `offset`, closing every open element there.
+    fn stop(&mut self, offset: usize) {
+        self.finished = true;
+        self.current_state = State::Text;
+
+        while self.element_stack.len() > self.base_depth {
+            self.close_implicitly(offset);
+        }
+    }
+
+    /// Returns `error` in strict mode, or records it as a diagnostic in lenient mode.
+    fn report(&mut self, severity: Severity, error: ParseError) -> Result<(), ParseError> {
+        match self.mode {
+            ParseMode::Strict => Err(error),
+            ParseMode::Lenient => {
+                self.diagnostics.push(Diagnostic { severity, error });
+                Ok(())
+            }
+        }
+    }
+
+    /// Replaces character and entity references in a text or attribute value.
+    ///
+    /// The expansions of all values of the document together are limited to
+    /// [`MAX_EXPANSION_BYTES`](entity::MAX_EXPANSION_BYTES). In lenient mode, a
+    /// value with an invalid reference or that expands past the limit is kept
+    /// as written.
+    fn decode(
+        &mut self,
+        value: Cow<'input, str>,
+        span: Range<usize>,
+    ) -> Result<Cow<'input, str>, ParseError> {
+        let budget = &mut self.expansion_budget;
+        let decoded = match value {
+            Cow::Borrowed(text) => self
+                .entities
+                .decode_within(text, budget)
+                .map_err(|error| (error, Cow::Borrowed(text))),
+            Cow::Owned(text) => {
+                let decoded =
+                    self.entities
+                        .decode_within(&text, budget)
+                        .map(|decoded| match decoded {
+                            Cow::Owned(decoded) => Some(decoded),
+                            Cow::Borrowed(_) => None,
+                        });
+
+                match decoded {
+                    Ok(decoded) => Ok(Cow::Owned(decoded.unwrap_or(text))),
+                    Err(error) => Err((error, Cow::Owned(text))),
+                }
+            }
+        };
+
+        match decoded {
+            Ok(decoded) => Ok(decoded),
+            Err((error, value)) => {
+                let kind = match error {
+                    DecodeError::InvalidReference(reference) => {
+                        ParseErrorKind::InvalidReference(reference)
+                    }
+                    DecodeError::TooLarge(_) => {
+                        ParseErrorKind::ExpansionTooLarge(entity::MAX_EXPANSION_BYTES)
+                    }
+                };
+                self.report(Severity::Error, ParseError::new(kind, span))?;
+                Ok(value)
+            }
+        }
+    }
+
+    /// Reports a construct that could not be parsed; in lenient mode it is dropped.
+    fn report_malformed(
+        &mut self,
+        construct: Construct,
+        span: Range<usize>,
+    ) -> Result<(), ParseError> {
+        self.report(
+            Severity::Error,
+            ParseError::new(ParseErrorKind::MalformedConstruct(construct), span),
+        )
+    }
+
+    /// Adds `name`=`value` to the current start tag.
+    ///
+    /// `span` covers the whole attribute and `quote` is the delimiter used in the source.
+    fn push_attribute(
+        &mut self,
+        name: Cow<'input, str>,
+        value: Cow<'input, str>,
+        span: Range<usize>,
+        quote: Quote,
+    ) {
+        if let Some(tag) = &mut self.start_tag {
+            tag.attributes.push(RawAttribute {
+                name,
+                value,
+                span,
+                quote,
+            });
+        }
+    }
+
+    /// Adds the pending attribute with an empty value (e.g. `<rect autofocus/>`).
+    fn push_empty_attribute(&mut self) {
+        if let Some((name, start)) = self.current_attribute.take() {
+            let end = start + name.len();
+            self.push_attribute(name, Cow::Borrowed(""), start..end, Quote::default());
+        }
+    }
+
+    /// Emits the [`Event::StartElement`] for the current start tag, which ends
+    /// at `end`, and pushes the element onto the stack of open elements.
+    ///
+    /// In lenient mode, invalid attributes are dropped. An element nested
+    /// deeper than `max_depth` ends the input there, to bound the memory spent
+    /// on hostile documents.
+    fn open_element(&mut self, end: usize) -> Result<(), ParseError> {
+        let Some(tag) = self.start_tag.take() else {
+            return Ok(());
+        };
+
+        if self.element_stack.len() >= self.max_depth {
+            let error = ParseError::new(
+                ParseErrorKind::NestingTooDeep(self.max_depth),
+                tag.start..end,
+            );
+            self.report(Severity::Error, error)?;
+            self.stop(tag.start);
+            return Ok(());
+        }
+
+        let namespaces: Vec<_> = tag
+            .attributes
+            .iter()
+            .filter_map(|attribute| {
+                let prefix = namespace::declared_prefix(&attribute.name)?;
+                Some((
+                    prefix.map(|prefix| Cow::Owned(prefix.to_string())),
+                    attribute.value.clone(),
+                ))
+            })
+            .collect();
+
+        let (ty, prefix) = self.resolve_element(&tag.name, tag.name_span, &namespaces)?;
+        let span = tag.start..end;
+
+        if let Some(parent) = self.element_stack.last()
+            && !parent.ty.allows_child(&ty)
+        {
+            let error = ParseError::new(
+                ParseErrorKind::DisallowedChild {
+                    parent: parent.ty.clone(),
+                    child: ty.clone(),
+                },
+                span.clone(),
+            );
+            self.report(Severity::Warning, error)?;
+        }
+
+        self.bind(&namespaces);
+        self.element_stack.push(OpenElement {
+            ty: ty.clone(),
+            name: tag.name,
+            namespaces,
+        });
+
+        let mut attributes = Vec::with_capacity(tag.attributes.len());
+        let mut sources = Vec::with_capacity(tag.attributes.len());
+        for raw in tag.attributes {
+            if let Some(attribute) = self.resolve_attribute(&raw)? {
+                attributes.push(attribute);
+                sources.push(SourceAttribute {
+                    name: raw.name,
+                    span: raw.span,
+                    quote: raw.quote,
+                });
+            }
+        }
+
+        self.queue.push_back(Event::StartElement {
+            ty,
+            prefix,
+            attributes,
+            sources,
+            span,
+        });
+
+        Ok(())
+    }
+
+    /// Brings the namespaces declared by an element that opens into scope.
+    fn bind(&mut self, namespaces: &[(Option<Cow<'input, str>>, Cow<'input, str>)]) {
+        // in reverse, so that the first of duplicate declarations is in scope
+        for (prefix, uri) in namespaces.iter().rev() {
+            let prefix = prefix.clone().unwrap_or_default();
+            self.scopes.entry(prefix).or_default().push(uri.clone());
+        }
+    }
+
+    /// Takes the namespaces declared by an element that closes out of scope.
+    fn unbind(&mut self, namespaces: &[(Option<Cow<'input, str>>, Cow<'input, str>)]) {
+        for (prefix, _) in namespaces {
+            if let Some(uris) = self.scopes.get_mut(prefix.as_deref().unwrap_or_default()) {
+                uris.pop();
+            }
+        }
+    }
+
+    /// Returns the namespace bound to `prefix` (or the default namespace) by
+    /// `declared` or the open elements, or `None` if there is none.
+    fn namespace_uri(
+        &self,
+        prefix: Option<&str>,
+        declared: &[(Option<Cow<'input, str>>, Cow<'input, str>)],
+    ) -> Option<Cow<'input, str>> {
+        if prefix == Some("xml") {
+            return Some(Cow::Borrowed(XML_NAMESPACE));
+        }
+
+        declared
+            .iter()
+            .find(|(declared, _)| declared.as_deref() == prefix)
+            .map(|(_, uri)| uri)
+            .or_else(|| self.scopes.get(prefix.unwrap_or_default())?.last())
+            .filter(|uri| !uri.is_empty())
+            .cloned()
+    }
+
+    /// Resolves an element name to its type and prefix, given the namespaces
+    /// the element itself declares.
+    ///
+    /// Names in the SVG namespace that are not known become [`ElementType::Unknown`].
+    fn resolve_element(
+        &mut self,
+        name: &str,
+        span: Range<usize>,
+        declared: &[(Option<Cow<'input, str>>, Cow<'input, str>)],
+    ) -> Result<(ElementType, Option<String>), ParseError> {
+        let (prefix, local_name) = namespace::split(name);
+        let namespace = self.namespace_uri(prefix, declared);
+
+        if let Some(prefix) = prefix
+            && namespace.is_none()
+        {
+            // kept as a foreign element without a namespace
+            self.report(
+                Severity::Error,
+                ParseError::new(ParseErrorKind::UnboundPrefix(prefix.to_string()), span),
+            )?;
+        } else if let None | Some(SVG_NAMESPACE) = namespace.as_deref() {
+            let ty = local_name
+                .parse()
+                .unwrap_or_else(|()| ElementType::Unknown(local_name.to_string()));
+
+            return Ok((ty, prefix.map(str::to_string)));
+        }
+
+        Ok((
+            ElementType::Foreign(Box::new(QualifiedName::new(name, namespace.as_deref()))),
+            None,
+        ))
+    }
+
+    /// Converts an attribute as written to an [`Attribute`], resolving its prefix.
+    ///
+    /// Returns `None` for an invalid attribute that was dropped in lenient mode.
+    fn resolve_attribute(
+        &mut self,
+        raw: &RawAttribute<'input>,
+    ) -> Result<Option<Attribute>, ParseError> {
+        let attribute = match namespace::split(&raw.name) {
+            (None, "d") => {
+                // rendered up to the first error, so the path before it is kept
+                let (path, error) = Path::parse(&raw.value);
+                if let Some(error) = error {
+                    self.report(
+                        Severity::Error,
+                        ParseError::new(ParseErrorKind::InvalidPathData(error), raw.span.clone()),
+                    )?;
+                }
+
+                Some(Attribute::D(path))
+            }
+            (None, _) => (&*raw.name, &*raw.value).try_into().ok(),
+            (Some("xmlns"), _) => Some(Attribute::Foreign(
+                QualifiedName::new(&*raw.name, Some(XMLNS_NAMESPACE)),
+                raw.value.to_string(),
+            )),
+            (Some(prefix), local_name) => {
+                let namespace = self.namespace_uri(Some(prefix), &[]);
+
+                if namespace.is_none() {
+                    self.report(
+                        Severity::Error,
+                        ParseError::new(
+                            ParseErrorKind::UnboundPrefix(prefix.to_string()),
+                            raw.span.clone(),
+                        ),
+                    )?;
+                }
+
+                match (namespace.as_deref(), prefix, local_name) {
+                    (Some(XML_NAMESPACE), _, "space") => {
+                        raw.value.parse().ok().map(Attribute::XmlSpace)
+                    }
+                    (Some(XLINK_NAMESPACE), "xlink", "href") => {
+                        Some(Attribute::XlinkHref(raw.value.to_string()))
+                    }
+                    (namespace, _, _) => Some(Attribute::Foreign(
+                        QualifiedName::new(&*raw.name, namespace),
+                        raw.value.to_string(),
+                    )),
+                }
+            }
+        };
+
+        if attribute.is_none() {
+            self.report(
+                Severity::Error,
+                ParseError::new(
+                    ParseErrorKind::InvalidAttribute {
+                        name: raw.name.to_string(),
+                        value: raw.value.to_string(),
+                    },
+                    raw.span.clone(),
+                ),
+            )?;
+        }
+
+        Ok(attribute)
+    }
+
+    /// Closes the element named by a closing tag.
+    ///
+    /// In lenient mode, elements left open inside it are closed as well, and a
+    /// closing tag without a matching open element is dropped.
+    fn close_tag(&mut self, name: &str, span: Range<usize>) -> Result<(), ParseError> {
+        let closable = &self.element_stack[self.base_depth..];
+        let expected = closable.last().map(|open| open.name.to_string());
+
+        if expected.as_deref() == Some(name) {
+            self.close_innermost(span);
+            return Ok(());
+        }
+
+        let matching = closable
+            .iter()
+            .rposition(|open| open.name == name)
+            .map(|index| self.base_depth + index);
+
+        self.report(
+            Severity::Error,
+            ParseError::new(
+                ParseErrorKind::MismatchedCloseTag {
+                    expected,
+                    found: name.to_string(),
+                },
+                span.clone(),
+            ),
+        )?;
+
+        if let Some(index) = matching {
+            while self.element_stack.len() > index + 1 {
+                self.close_implicitly(self.tag_start);
+            }
+            self.close_innermost(span);
+        }
+
+        Ok(())
+    }
+
+    /// Pops the innermost open element and emits its [`Event::EndElement`],
+    /// which ends at the end of `span`.
+    fn close_innermost(&mut self, span: Range<usize>) {
+        let Some(open) = self.element_stack.pop() else {
+            return;
+        };
+        self.unbind(&open.namespaces);
+
+        let start = match self.current_state {
+            State::TagCloseName => self.tag_start,
+            _ => span.start,
+        };
+
+        self.queue.push_back(Event::EndElement {
+            ty: open.ty,
+            span: start..span.end,
+        });
+    }
+
+    /// Pops the innermost open element and emits an [`Event::EndElement`] with
+    /// an empty span at `offset`, for an element without a closing tag.
+    fn close_implicitly(&mut self, offset: usize) {
+        let Some(open) = self.element_stack.pop() else {
+            return;
+        };
+        self.unbind(&open.namespaces);
+
+        self.queue.push_back(Event::EndElement {
+            ty: open.ty,
+            span: offset..offset,
+        });
+    }
+
+    /// Extends the element closed by the current closing tag up to its `>`.
+    fn end_close_tag(&mut self, end: usize) {
+        for event in &mut self.queue {
+            if let Event::EndElement { span, .. } = event
+                && span.start < span.end
+            {
+                span.end = end;
+            }
+        }
+    }
+}
+
+impl<'input, L: TokenSource<'input>> Iterator for Events<'input, L> {
+    type Item = Result<Event<'input>, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Err(error) = self.fill() {
+            self.finished = true;
+            self.queue.clear();
+            return Some(Err(error));
+        }
+
+        self.queue.pop_front().map(Ok)
+    }
+}
+
+/// Strips the `start` and `end` delimiters from a token value, e.g. `<!--` and `-->`.
+///
+/// A value shorter than both delimiters, where they overlap, becomes empty.
+fn trim(value: Cow<'_, str>, start: usize, end: usize) -> Cow<'_, str> {
+    let start = start.min(value.len());
+    let end = value.len().saturating_sub(end).max(start);
+
+    match value {
+        Cow::Borrowed(value) => Cow::Borrowed(&value[start..end]),
+        Cow::Owned(mut value) => {
+            value.truncate(end);
+            value.drain(..start);
+            Cow::Owned(value)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::Input;
+
+    fn events(svg: &str, mode: ParseMode) -> Events<'_> {
+        Events::new(Lexer::new(Input::new(svg)), mode)
+    }
+
+    #[test]
+    fn events_should_follow_source_order() {
+        let events: Vec<_> = events(
+            r#"<svg viewBox="0 0 10 10"><!--c--><rect/>a &amp; b</svg>"#,
+            ParseMode::Strict,
+        )
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+        assert_eq!(
+            events,
+            vec![
+                Event::StartElement {
+                    ty: ElementType::Svg,
+                    prefix: None,
+                    attributes: vec![("viewBox", "0 0 10 10").try_into().unwrap()],
+                    sources: vec![SourceAttribute {
+                        name: Cow::Borrowed("viewBox"),
+                        span: 5..24,
+                        quote: Quote::Double,
+                    }],
+                    span: 0..25,
+                },
+                Event::Comment {
+                    content: Cow::Borrowed("c"),
+                    span: 25..33,
+                },
+                Event::StartElement {
+                    ty: ElementType::Rect,
+                    prefix: None,
+                    attributes: vec![],
+                    sources: vec![],
+                    span: 33..40,
+                },
+                Event::EndElement {
+                    ty: ElementType::Rect,
+                    span: 38..40,
+                },
+                Event::Text {
+                    content: Cow::Borrowed("a & b"),
+                    span: 40..49,
+                },
+                Event::EndElement {
+                    ty: ElementType::Svg,
+                    span: 49..55,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn events_should_allow_scanning_without_a_tree() {
+        let images = events(
+            r#"<svg><g><image href="a.png"/></g><image href="b.png"/></svg>"#,
+            ParseMode::Strict,
+        )
+        .filter(|event| {
+            matches!(
+                event,
+                Ok(Event::StartElement {
+                    ty: ElementType::Image,
+                    ..
+                })
+            )
+        })
+        .count();
+
+        assert_eq!(images, 2);
+    }
+
+    #[test]
+    fn events_should_stop_at_the_first_error_in_strict_mode() {
+        let mut events = events("<svg><g></svg>", ParseMode::Strict);
+
+        assert!(matches!(
+            events.next(),
+            Some(Ok(Event::StartElement { .. }))
+        ));
+        assert!(matches!(
+            events.next(),
+            Some(Ok(Event::StartElement { .. }))
+        ));
+        assert!(matches!(
+            events.next(),
+            Some(Err(ParseError {
+                kind: ParseErrorKind::MismatchedCloseTag { .. },
+                ..
+            }))
+        ));
+        assert_eq!(events.next(), None);
+    }
+
+    #[test]
+    fn events_should_close_open_elements_in_lenient_mode() {
+        let mut events = events("<svg><g></svg><text>", ParseMode::Lenient);
+        let ends: Vec<_> = events
+            .by_ref()
+            .map(Result::unwrap)
+            .filter_map(|event| match event {
+                Event::EndElement { ty, span } => Some((ty, span)),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(
+            ends,
+            vec![
+                (ElementType::G, 8..8),
+                (ElementType::Svg, 8..14),
+                (ElementType::Text, 20..20),
+            ]
+        );
+        assert_eq!(events.diagnostics().len(), 2);
+    }
+}
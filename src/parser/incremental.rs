@@ -0,0 +1,471 @@
+//! Re-parsing a document after a text edit.
+//!
+//! [`AST::reparse`] finds the innermost element whose content contains the
+//! edit and re-parses only its children around the edit. Nodes outside of
+//! them keep their [`NodeId`]s, and their spans are moved to match the new
+//! source.
+
+use std::ops::Range;
+
+use crate::{
+    element::attributes::Attribute,
+    entity::Entities,
+    error::ParseError,
+    lexer::{Input, Lexer},
+    namespace::XMLNS_NAMESPACE,
+    parser::{
+        Parser, TreeBuilder,
+        ast::{AST, Node, NodeId},
+        events::{Ancestor, DEFAULT_MAX_DEPTH, Events},
+    },
+    span::{LineIndex, Span},
+    token::TokenKind,
+};
+
+/// A change to the source: the bytes in `range` are replaced by `replacement`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TextEdit {
+    /// Byte range in the old source.
+    pub range: Range<usize>,
+    pub replacement: String,
+}
+
+impl TextEdit {
+    pub fn new(range: Range<usize>, replacement: impl Into<String>) -> Self {
+        Self {
+            range,
+            replacement: replacement.into(),
+        }
+    }
+
+    /// Maps an offset in the old source at or after the edit to the new source.
+    fn shift(&self, offset: usize) -> usize {
+        offset + self.replacement.len() - self.range.len()
+    }
+
+    /// End of the replacement in the new source.
+    fn new_end(&self) -> usize {
+        self.range.start + self.replacement.len()
+    }
+}
+
+/// The children of an element that are re-parsed.
+struct Fragment {
+    /// The elements enclosing the fragment, outermost first.
+    ancestors: Vec<Ancestor>,
+    parent: NodeId,
+    /// Indices of the replaced children of `parent`.
+    children: Range<usize>,
+    /// Byte range of the fragment in the new source.
+    range: Range<usize>,
+}
+
+impl AST {
+    /// Updates the tree after `edit` was applied to the source it was parsed
+    /// from; `source` is the edited text.
+    ///
+    /// Only the children of the innermost element containing the edit that
+    /// overlap it are parsed again; all other nodes keep their IDs. Edits
+    /// outside of any element's content, or that change the structure around
+    /// them, fall back to parsing the whole source. On error the tree is left
+    /// unchanged.
+    ///
+    /// The source is parsed in the mode and with the nesting limit the tree
+    /// was parsed with; a tree from [`Parser::parse_lenient`] is re-parsed
+    /// leniently, without returning its diagnostics.
+    pub fn reparse(&mut self, edit: &TextEdit, source: &str) -> Result<(), ParseError> {
+        let Some(fragment) = self.find_fragment(edit, source) else {
+            return self.parse_all(source);
+        };
+
+        let mut input = Input::new(&source[..fragment.range.end]);
+        input.offset = fragment.range.start;
+        let mut events = Events::fragment(
+            Lexer::new(input),
+            self.mode,
+            fragment.ancestors,
+            self.entities(),
+        )
+        .with_max_depth(self.max_depth.unwrap_or(DEFAULT_MAX_DEPTH));
+
+        // e.g. a closing tag of an ancestor, or an element left open, which
+        // lenient mode repairs inside the fragment only
+        let events = match events.by_ref().collect::<Result<Vec<_>, _>>() {
+            Ok(collected) if events.diagnostics().is_empty() => collected,
+            _ => return self.parse_all(source),
+        };
+
+        let old_children: Vec<_> = self
+            .nodes
+            .get(fragment.parent)
+            .and_then(Node::as_element)
+            .map(|parent| parent.children.clone())
+            .unwrap_or_default();
+        for &child in &old_children[fragment.children.clone()] {
+            self.remove_subtree(child);
+        }
+        let retained = old_children.len() - fragment.children.len();
+
+        let mut line_index = LineIndex::new(source.as_bytes());
+        self.shift_spans(edit, &line_index);
+
+        let mut builder = TreeBuilder {
+            ast: std::mem::take(self),
+            open_elements: vec![fragment.parent],
+        };
+        for event in events {
+            builder.push(event, &mut line_index);
+        }
+        *self = builder.ast;
+
+        // the new children were appended, move them where the old ones were
+        if let Some(Node::Element(parent)) = self.nodes.get_mut(fragment.parent) {
+            let added = parent.children.len() - retained;
+            parent.children[fragment.children.start..].rotate_right(added);
+        }
+        self.update_source(source, fragment.parent);
+
+        Ok(())
+    }
+
+    fn parse_all(&mut self, source: &str) -> Result<(), ParseError> {
+        // a tree that keeps its source keeps the new one
+        let (ast, _) = Parser::new(Lexer::new(Input::new(source)))
+            .with_lossless(self.source.is_some())
+            .with_max_depth(self.max_depth.unwrap_or(DEFAULT_MAX_DEPTH))
+            .run(self.mode)?;
+        *self = ast;
+
+        Ok(())
+    }
+
+    /// Finds the children to re-parse, or `None` if the whole source has to be parsed.
+    fn find_fragment(&self, edit: &TextEdit, source: &str) -> Option<Fragment> {
+        let new_end = edit.new_end();
+        if edit.range.start > edit.range.end || new_end > source.len() {
+            return None;
+        }
+
+        let mut ancestors = vec![];
+        let mut parent = None;
+        let mut content = 0..0;
+        let mut children = &self.children;
+
+        while let Some((id, name, range)) = children
+            .iter()
+            .find_map(|&child| self.enclosing_content(child, edit, source))
+        {
+            let element = self.nodes.get(id)?.as_element()?;
+            ancestors.push(Ancestor {
+                ty: element.element_type.clone(),
+                name,
+                namespaces: element
+                    .attributes
+                    .iter()
+                    .filter_map(|attribute| match attribute {
+                        Attribute::Xmlns(uri) => Some((None, uri.clone())),
+                        Attribute::Foreign(name, uri)
+                            if name.namespace.as_deref() == Some(XMLNS_NAMESPACE) =>
+                        {
+                            Some((Some(name.local_name().to_string()), uri.clone()))
+                        }
+                        _ => None,
+                    })
+                    .collect(),
+            });
+
+            parent = Some(id);
+            content = range;
+            children = &element.children;
+        }
+
+        // edits in the prolog or around the root element may change everything
+        let parent = parent?;
+
+        let span = |id: NodeId| self.spans.get(id).map(|span| span.span.range());
+        let is_text = |id: NodeId| matches!(self.nodes.get(id), Some(Node::Text(_)));
+
+        // the children overlapping the edit, or the position where it inserts
+        let mut start = children
+            .iter()
+            .position(|&id| span(id).is_some_and(|span| span.end > edit.range.start))
+            .unwrap_or(children.len());
+        let mut end = children
+            .iter()
+            .rposition(|&id| span(id).is_some_and(|span| span.start < edit.range.end))
+            .map_or(0, |index| index + 1)
+            .max(start);
+
+        // adjacent text is merged with text produced by the edit
+        while start > 0 && is_text(children[start - 1]) {
+            start -= 1;
+        }
+        while end < children.len() && is_text(children[end]) {
+            end += 1;
+        }
+
+        let range_start = match start {
+            0 => content.start,
+            _ => span(children[start - 1])?.end,
+        };
+        let range_end = match children.get(end) {
+            Some(&next) => edit.shift(span(next)?.start),
+            None => content.end,
+        };
+
+        Some(Fragment {
+            ancestors,
+            parent,
+            children: start..end,
+            range: range_start..range_end,
+        })
+    }
+
+    /// Returns the element `id`, its name as written and the range of its
+    /// content in the new source, if the content contains the edit.
+    fn enclosing_content(
+        &self,
+        id: NodeId,
+        edit: &TextEdit,
+        source: &str,
+    ) -> Option<(NodeId, String, Range<usize>)> {
+        self.nodes.get(id)?.as_element()?;
+        let span = self.spans.get(id)?.span.range();
+        if span.start >= edit.range.start || span.end <= edit.range.end {
+            return None;
+        }
+
+        let (name, content_start) = start_tag(source, span.start)?;
+        let end = edit.shift(span.end);
+        let content_end = source[..end].rfind("</")?;
+
+        let is_closing_tag = source[content_end + 2..end]
+            .strip_prefix(&*name)
+            .is_some_and(|rest| rest.trim_start().starts_with('>'));
+
+        (is_closing_tag && content_start <= edit.range.start && content_end >= edit.new_end())
+            .then(|| (id, name.to_string(), content_start..content_end))
+    }
+
+    /// Returns the entities declared in the DOCTYPE.
+    fn entities(&self) -> Entities {
+        self.children
+            .iter()
+            .find_map(|&id| match self.nodes.get(id) {
+                Some(Node::Doctype(doctype)) => doctype.internal_subset.as_deref(),
+                _ => None,
+            })
+            .map(Entities::from_internal_subset)
+            .unwrap_or_default()
+    }
+
+    /// Removes a node and all of its descendants.
+    fn remove_subtree(&mut self, id: NodeId) {
+        let mut pending = vec![id];
+        while let Some(id) = pending.pop() {
+            if let Some(Node::Element(element)) = self.nodes.get(id) {
+                pending.extend(&element.children);
+            }
+            self.remove_node(id);
+        }
+    }
+
+    /// Moves every span after the edit to the new source.
+    ///
+    /// For an insertion, spans ending where it is inserted stay in place while
+    /// spans starting there move.
+    fn shift_spans(&mut self, edit: &TextEdit, line_index: &LineIndex) {
+        let shift = |span: &mut Span| {
+            if span.start.offset >= edit.range.end {
+                span.start = line_index.location(edit.shift(span.start.offset));
+            }
+            if span.end.offset >= edit.range.end && span.end.offset > edit.range.start {
+                span.end = line_index.location(edit.shift(span.end.offset));
+            }
+        };
+
+        for (_, node_span) in self.spans.iter_mut() {
+            shift(&mut node_span.span);
+            if let Some(end_tag) = &mut node_span.end_tag {
+                shift(end_tag);
+            }
+
+            for attribute in &mut node_span.attributes {
+                shift(&mut attribute.span);
+            }
+        }
+    }
+}
+
+/// Lexes the start tag at `start` and returns its name and end, or `None` for
+/// an empty-element tag.
+fn start_tag(source: &str, start: usize) -> Option<(String, usize)> {
+    let mut input = Input::new(source);
+    input.offset = start;
+    let mut lexer = Lexer::new(input);
+
+    lexer.advance().ok()??;
+    let name = lexer.advance().ok()??;
+    if name.kind != TokenKind::Identifier {
+        return None;
+    }
+
+    loop {
+        let token = lexer.advance().ok()??;
+        match token.kind {
+            TokenKind::GreaterThan => return Some((name.value.into_owned(), token.span.end)),
+            TokenKind::SlashGreaterThan => return None,
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ParseErrorKind, element::ElementType};
+
+    fn parse(source: &str) -> AST {
+        Parser::new(Lexer::new(Input::new(source))).parse().unwrap()
+    }
+
+    /// Applies `edit` to `source`, re-parses, and checks the result against a full parse.
+    fn edit(ast: &mut AST, source: &str, edit: &TextEdit) -> String {
+        let mut edited = source.to_string();
+        edited.replace_range(edit.range.clone(), &edit.replacement);
+
+        ast.reparse(edit, &edited).unwrap();
+        assert_eq!(ast.to_svg(), parse(&edited).to_svg());
+
+        edited
+    }
+
+    #[test]
+    fn reparse_should_keep_ids_of_untouched_nodes() {
+        let source = "<svg>\n  <g id=\"a\"><rect/></g>\n  <g id=\"b\"><circle/></g>\n</svg>";
+        let mut ast = parse(source);
+        let svg = ast.find_root().unwrap();
+        let a = ast.find_by_id("a").unwrap();
+        let rect = ast.find_by_type(ElementType::Rect)[0];
+        let circle = ast.find_by_type(ElementType::Circle)[0];
+
+        let start = source.find("<circle/>").unwrap();
+        edit(
+            &mut ast,
+            source,
+            &TextEdit::new(start..start + 9, "<ellipse/><line/>"),
+        );
+
+        assert_eq!(ast.find_root(), Some(svg));
+        assert_eq!(ast.find_by_id("a"), Some(a));
+        assert_eq!(ast.find_by_type(ElementType::Rect), vec![rect]);
+        assert!(!ast.nodes.contains_key(circle));
+
+        let b = ast.find_by_id("b").unwrap();
+        let children = ast.nodes[b].as_element().unwrap().children.clone();
+        assert_eq!(children.len(), 2);
+        assert_eq!(
+            ast.nodes[children[1]].as_element().unwrap().element_type,
+            ElementType::Line
+        );
+    }
+
+    #[test]
+    fn reparse_should_shift_spans_after_the_edit() {
+        let source = "<svg>\n<g><text>hi</text></g>\n<rect x=\"1\"/>\n</svg>";
+        let mut ast = parse(source);
+        let text = ast.find_by_type(ElementType::Text)[0];
+        let rect = ast.find_by_type(ElementType::Rect)[0];
+
+        let start = source.find("hi").unwrap();
+        let edited = edit(
+            &mut ast,
+            source,
+            &TextEdit::new(start..start + 2, "hello\nworld"),
+        );
+
+        let text_span = ast.node_span(text).unwrap();
+        assert_eq!(&edited[text_span.range()], "<text>hello\nworld</text>");
+
+        let rect_span = ast.node_span(rect).unwrap();
+        assert_eq!(&edited[rect_span.range()], "<rect x=\"1\"/>");
+        assert_eq!((rect_span.start.line, rect_span.start.column), (4, 1));
+
+        let x = ast.attribute_span(rect, "x").unwrap();
+        assert_eq!(&edited[x.range()], "x=\"1\"");
+
+        let svg = ast.find_root().unwrap();
+        assert_eq!(ast.node_span(svg).unwrap().range(), 0..edited.len());
+    }
+
+    #[test]
+    fn reparse_should_merge_adjacent_text() {
+        let source = "<svg><text>one <tspan>two</tspan> three</text></svg>";
+        let mut ast = parse(source);
+        let tspan = ast.find_by_type(ElementType::TSpan)[0];
+
+        let start = source.find("three").unwrap();
+        edit(&mut ast, source, &TextEdit::new(start..start, "and "));
+
+        let text = ast.find_by_type(ElementType::Text)[0];
+        let children = &ast.nodes[text].as_element().unwrap().children;
+        assert_eq!(children.len(), 3);
+        assert_eq!(children[1], tspan);
+        assert_eq!(ast.nodes[children[2]].as_text(), Some(" and three"));
+    }
+
+    #[test]
+    fn reparse_should_fall_back_to_a_full_parse() {
+        // the edit closes the enclosing element
+        let source = "<svg><g><rect/><circle/></g></svg>";
+        let mut ast = parse(source);
+        let start = source.find("<circle/>").unwrap();
+        edit(&mut ast, source, &TextEdit::new(start..start, "</g><g>"));
+        assert_eq!(ast.find_by_type(ElementType::G).len(), 2);
+
+        // the edit is inside the start tag of the root element
+        let source = "<svg id=\"a\"><rect/></svg>";
+        let mut ast = parse(source);
+        edit(&mut ast, source, &TextEdit::new(9..10, "b"));
+        assert!(ast.find_by_id("b").is_some());
+    }
+
+    #[test]
+    fn reparse_should_keep_the_parse_mode_and_max_depth() {
+        let source = "<svg><text>a</text><g></svg>";
+        let (mut ast, _) = Parser::new(Lexer::new(Input::new(source))).parse_lenient();
+
+        let start = source.find('a').unwrap();
+        let edited = source.replace(">a<", "><g>a<");
+        ast.reparse(&TextEdit::new(start..start, "<g>"), &edited)
+            .unwrap();
+        assert_eq!(ast.find_by_type(ElementType::G).len(), 2);
+
+        let source = "<svg><text>a</text></svg>";
+        let mut ast = Parser::new(Lexer::new(Input::new(source)))
+            .with_max_depth(2)
+            .parse()
+            .unwrap();
+
+        let start = source.find('a').unwrap();
+        let edited = source.replace(">a<", "><tspan/><");
+        let error = ast
+            .reparse(&TextEdit::new(start..start + 1, "<tspan/>"), &edited)
+            .unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::NestingTooDeep(2));
+    }
+
+    #[test]
+    fn reparse_should_leave_the_tree_unchanged_on_error() {
+        let source = "<svg><g><rect/></g></svg>";
+        let mut ast = parse(source);
+        let before = ast.to_svg();
+
+        let start = source.find("<rect/>").unwrap();
+        let edited = source.replace("<rect/>", "<rect>");
+        let error = ast.reparse(&TextEdit::new(start..start + 7, "<rect>"), &edited);
+
+        assert!(error.is_err());
+        assert_eq!(ast.to_svg(), before);
+    }
+}
@@ -0,0 +1,609 @@
+//! Serializing a document back to the exact text it was parsed from.
+//!
+//! [`AST::to_svg_lossless`] copies the source of every node that is unchanged
+//! since it was parsed, including the whitespace between tags and attributes.
+//! Changed nodes are written anew, but keep the text around them: an edited
+//! attribute keeps its position and quotes, and a new child takes the
+//! indentation of its siblings.
+
+use std::{
+    fmt::{self, Write},
+    ops::Range,
+};
+
+use slotmap::SecondaryMap;
+
+use crate::{
+    Element,
+    encoding::is_utf8_label,
+    entity::escape_text,
+    parser::ast::{AST, Node, NodeId},
+    serialize::{SerializeOptions, Serializer},
+    span::NodeSpan,
+};
+
+/// The text a document was parsed from and its nodes as they were parsed.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Source {
+    text: String,
+    /// Root-level nodes as parsed.
+    children: Vec<NodeId>,
+    originals: SecondaryMap<NodeId, Original>,
+}
+
+/// A node as it was parsed, and where.
+///
+/// The span is kept after the node is removed, for the text around it.
+#[derive(Clone, Debug)]
+struct Original {
+    node: Node,
+    span: NodeSpan,
+}
+
+impl AST {
+    /// Serializes the AST so that an unmodified document reproduces its source
+    /// byte for byte.
+    ///
+    /// Modified nodes only change locally. A document that was not parsed from
+    /// a string with [`Parser::with_lossless`](crate::Parser::with_lossless),
+    /// e.g. one built through the API or read with
+    /// [`Parser::parse_reader`](crate::Parser::parse_reader), is written as by
+    /// [`to_svg`](AST::to_svg).
+    pub fn to_svg_lossless(&self) -> String {
+        let Some(source) = &self.source else {
+            return self.to_svg();
+        };
+
+        let mut s = String::new();
+        let mut writer = LosslessWriter {
+            ast: self,
+            source,
+            unchanged: SecondaryMap::new(),
+        };
+        let pieces = writer.children(&self.children, &source.children, 0..source.text.len());
+        writer.write(pieces, &mut s).unwrap();
+        s
+    }
+
+    /// Keeps `text` as the source of the tree, and its nodes as parsed.
+    pub(crate) fn record_source(&mut self, text: &str) {
+        let mut source = Source {
+            text: text.to_string(),
+            children: self.children.clone(),
+            originals: SecondaryMap::new(),
+        };
+        for (id, span) in &self.spans {
+            if let Some(node) = self.nodes.get(id) {
+                let original = Original {
+                    node: node.clone(),
+                    span: span.clone(),
+                };
+                source.originals.insert(id, original);
+            }
+        }
+
+        self.source = Some(source);
+    }
+
+    /// Replaces the source of the tree after a re-parse of children of `parent`.
+    ///
+    /// `parent` and the new children are recorded as they are now, and the
+    /// spans of all other nodes are moved with the tree.
+    pub(crate) fn update_source(&mut self, text: &str, parent: NodeId) {
+        let Some(source) = &mut self.source else {
+            return;
+        };
+
+        text.clone_into(&mut source.text);
+        source
+            .originals
+            .retain(|id, _| self.nodes.contains_key(id) && id != parent);
+        for (id, span) in &self.spans {
+            let Some(node) = self.nodes.get(id) else {
+                continue;
+            };
+
+            match source.originals.get_mut(id) {
+                Some(original) => original.span.clone_from(span),
+                None => {
+                    let original = Original {
+                        node: node.clone(),
+                        span: span.clone(),
+                    };
+                    source.originals.insert(id, original);
+                }
+            }
+        }
+    }
+}
+
+struct LosslessWriter<'a> {
+    ast: &'a AST,
+    source: &'a Source,
+    /// Whether a node and all of its descendants are unchanged, once known.
+    unchanged: SecondaryMap<NodeId, bool>,
+}
+
+impl<'a> LosslessWriter<'a> {
+    fn original(&self, id: NodeId) -> Option<&'a Original> {
+        self.source.originals.get(id)
+    }
+
+    /// Returns `true` if the subtree of `id` can be copied from the source.
+    fn is_unchanged(&mut self, id: NodeId) -> bool {
+        // nodes to check, and whether their children have been checked
+        let mut pending = vec![(id, false)];
+        while let Some((id, children_checked)) = pending.pop() {
+            if self.unchanged.contains_key(id) {
+                continue;
+            }
+
+            let node = match (self.ast.nodes.get(id), self.original(id)) {
+                (Some(node), Some(original)) if is_same(node, &original.node) => node,
+                _ => {
+                    self.unchanged.insert(id, false);
+                    continue;
+                }
+            };
+            let children = match node {
+                Node::Element(element) => &element.children[..],
+                _ => &[],
+            };
+
+            if children_checked {
+                let unchanged = children
+                    .iter()
+                    .all(|&child| self.unchanged.get(child) == Some(&true));
+                self.unchanged.insert(id, unchanged);
+            } else {
+                pending.push((id, true));
+                pending.extend(children.iter().map(|&child| (child, false)));
+            }
+        }
+
+        self.unchanged.get(id) == Some(&true)
+    }
+
+    /// Writes `pieces` in order, with the pieces each node expands to.
+    fn write(&mut self, pieces: Vec<Piece<'a>>, f: &mut impl Write) -> fmt::Result {
+        let mut pending = pieces;
+        pending.reverse();
+
+        while let Some(piece) = pending.pop() {
+            match piece {
+                Piece::Text(text) => f.write_str(text)?,
+                Piece::Node(id) => {
+                    let rest = self.write_node(id, f)?;
+                    pending.extend(rest.into_iter().rev());
+                }
+                Piece::EndTag(element) => {
+                    write!(f, "</")?;
+                    element.write_name(f)?;
+                    write!(f, ">")?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Writes the start of a node, and returns the pieces that follow it.
+    fn write_node(&mut self, id: NodeId, f: &mut impl Write) -> Result<Vec<Piece<'a>>, fmt::Error> {
+        let ast = self.ast;
+        let Some(node) = ast.nodes.get(id) else {
+            return Ok(Vec::new());
+        };
+
+        if self.is_unchanged(id) {
+            let range = self.source.originals[id].span.span.range();
+            f.write_str(&self.source.text[range])?;
+            return Ok(Vec::new());
+        }
+
+        match (node, self.original(id)) {
+            (Node::Element(element), original) => self.write_element(element, id, original, f),
+            (Node::Text(text_node), _) => {
+                write!(f, "{}", escape_text(&text_node.content))?;
+                Ok(Vec::new())
+            }
+            (node, _) => {
+                Serializer::new(ast, &SerializeOptions::default(), f)
+                    .write_inline(Some(id), node)?;
+                Ok(Vec::new())
+            }
+        }
+    }
+
+    /// Writes the start tag of an element that changed itself or has a changed
+    /// descendant, and returns its content and end tag.
+    fn write_element(
+        &mut self,
+        element: &'a Element,
+        id: NodeId,
+        original: Option<&'a Original>,
+        f: &mut impl Write,
+    ) -> Result<Vec<Piece<'a>>, fmt::Error> {
+        let Some(Original {
+            node: Node::Element(original_element),
+            span,
+        }) = original
+        else {
+            // a new element, whose children may still come from the source
+            Serializer::new(self.ast, &SerializeOptions::default(), f)
+                .write_start_tag(Some(id), element)?;
+            if element.children.is_empty() {
+                write!(f, "/>")?;
+                return Ok(Vec::new());
+            }
+            write!(f, ">")?;
+            let mut pieces = self.children(&element.children, &[], 0..0);
+            pieces.push(Piece::EndTag(element));
+            return Ok(pieces);
+        };
+
+        let text = &self.source.text[..];
+        let range = span.span.range();
+        let tag = StartTag::find(text, range.clone(), span);
+
+        if element.element_type == original_element.element_type
+            && element.prefix == original_element.prefix
+        {
+            f.write_str(&text[range.start..tag.name_end])?;
+        } else {
+            write!(f, "<")?;
+            element.write_name(f)?;
+        }
+
+        if element.attributes == original_element.attributes {
+            f.write_str(&text[tag.name_end..tag.attributes_end])?;
+        } else {
+            write_attributes(element, original_element, span, text, f)?;
+        }
+
+        // the whitespace before `>` or `/>`
+        let closing = &text[tag.attributes_end..tag.end];
+        let whitespace = closing.trim_end_matches(['/', '>']);
+        f.write_str(whitespace)?;
+
+        // `</name>` as written, unless the element was closed in lenient mode
+        let end_tag = span
+            .end_tag
+            .as_ref()
+            .filter(|_| !tag.is_self_closing)
+            .map(|end_tag| end_tag.range());
+
+        if element.children.is_empty() {
+            // `/>`, or `></name>` of an element that was already empty
+            match original_element.children.is_empty() && (tag.is_self_closing || end_tag.is_some())
+            {
+                true => f.write_str(&text[tag.attributes_end + whitespace.len()..range.end])?,
+                false => write!(f, "/>")?,
+            }
+            return Ok(Vec::new());
+        }
+        write!(f, ">")?;
+
+        let content = match &end_tag {
+            Some(end_tag) => tag.end..end_tag.start,
+            None => tag.end..range.end.max(tag.end),
+        };
+
+        let mut pieces = self.children(&element.children, &original_element.children, content);
+        pieces.push(match end_tag {
+            Some(end_tag) => Piece::Text(&text[end_tag]),
+            None => Piece::EndTag(element),
+        });
+        Ok(pieces)
+    }
+
+    /// Returns `children` with the source text between them.
+    ///
+    /// Text between two children is kept while they are still adjacent; a
+    /// child without an original neighbour gets the text before the first
+    /// original child, which is usually the indentation.
+    fn children(
+        &self,
+        children: &[NodeId],
+        original_children: &[NodeId],
+        content: Range<usize>,
+    ) -> Vec<Piece<'a>> {
+        let source = self.source;
+        let text = &source.text[..];
+        let start = |id: NodeId| source.originals.get(id).map(|o| o.span.span.start.offset);
+        let end = |id: NodeId| source.originals.get(id).map(|o| o.span.span.end.offset);
+        let indentation = original_children
+            .first()
+            .and_then(|&first| start(first))
+            .map_or("", |first| &text[content.start..first]);
+
+        let mut pieces = Vec::with_capacity(children.len() * 2 + 1);
+        let mut previous = None;
+        for &child in children {
+            let index = original_children.iter().position(|&id| id == child);
+            let before = match index {
+                Some(0) if previous.is_none() => Some(content.start),
+                Some(index) if index > 0 && previous == Some(original_children[index - 1]) => {
+                    end(original_children[index - 1])
+                }
+                _ => None,
+            };
+
+            pieces.push(match (before, start(child)) {
+                (Some(before), Some(start)) if before <= start => Piece::Text(&text[before..start]),
+                _ => Piece::Text(indentation),
+            });
+            pieces.push(Piece::Node(child));
+            previous = Some(child);
+        }
+
+        // the text before the closing tag
+        let last_end = match original_children.last() {
+            Some(&last) => end(last),
+            None => Some(content.start),
+        };
+        if let Some(last_end) = last_end.filter(|&last_end| last_end <= content.end) {
+            pieces.push(Piece::Text(&text[last_end..content.end]));
+        }
+        pieces
+    }
+}
+
+/// Output of [`LosslessWriter`] that is still to be written.
+enum Piece<'a> {
+    /// Text copied from the source.
+    Text(&'a str),
+    Node(NodeId),
+    /// `</name>` of an element.
+    EndTag(&'a Element),
+}
+
+/// Offsets in the start tag of an element.
+struct StartTag {
+    /// End of `<name`.
+    name_end: usize,
+    /// End of the last attribute, or `name_end`.
+    attributes_end: usize,
+    /// End of the `>` or `/>`.
+    end: usize,
+    is_self_closing: bool,
+}
+
+impl StartTag {
+    fn find(text: &str, range: Range<usize>, span: &NodeSpan) -> Self {
+        let name_end = text[range.start + 1..range.end]
+            .find(|c: char| c.is_ascii_whitespace() || c == '/' || c == '>')
+            .map_or(range.end, |i| range.start + 1 + i);
+        let attributes_end = span
+            .attributes
+            .iter()
+            .map(|attribute| attribute.span.end.offset)
+            .max()
+            .unwrap_or(name_end);
+        let end = text[attributes_end..range.end]
+            .find('>')
+            .map_or(range.end, |i| attributes_end + i + 1);
+
+        Self {
+            name_end,
+            attributes_end,
+            end,
+            is_self_closing: text[..end].ends_with("/>"),
+        }
+    }
+}
+
+/// Writes the attributes of a changed element, copying those that did not
+/// change with the whitespace before them.
+fn write_attributes(
+    element: &Element,
+    original: &Element,
+    span: &NodeSpan,
+    text: &str,
+    f: &mut impl Write,
+) -> fmt::Result {
+    for attribute in &element.attributes {
+        let name = attribute.name();
+        let source = span.attributes.iter().find(|source| source.name == name);
+
+        let Some(source) = source else {
+            write!(f, " ")?;
+            attribute.write_svg_quoted(f, Default::default())?;
+            continue;
+        };
+
+        let range = source.span.range();
+        let whitespace = text[..range.start].len()
+            - text[..range.start]
+                .trim_end_matches(|c: char| c.is_ascii_whitespace())
+                .len();
+        f.write_str(&text[range.start - whitespace..range.start])?;
+
+        if original.attributes.contains(attribute) {
+            f.write_str(&text[range])?;
+        } else {
+            attribute.write_svg_quoted(f, source.quote)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns `true` if `node` has the same content as `original`, wherever it is.
+fn is_same(node: &Node, original: &Node) -> bool {
+    match (node, original) {
+        (Node::Element(a), Node::Element(b)) => {
+            a.element_type == b.element_type
+                && a.prefix == b.prefix
+                && a.attributes == b.attributes
+                && a.children == b.children
+        }
+        (Node::Text(a), Node::Text(b)) => a.content == b.content,
+        (Node::Comment(a), Node::Comment(b)) => a.content == b.content,
+        (Node::CData(a), Node::CData(b)) => a.content == b.content,
+        (Node::ProcessingInstruction(a), Node::ProcessingInstruction(b)) => {
+            a.target == b.target && a.data == b.data
+        }
+        // the output is UTF-8, so the declaration of a transcoded source is rewritten
+        (Node::XmlDeclaration(a), Node::XmlDeclaration(b)) => {
+            a.version == b.version
+                && a.encoding == b.encoding
+                && a.encoding.as_deref().is_none_or(is_utf8_label)
+                && a.standalone == b.standalone
+        }
+        (Node::Doctype(a), Node::Doctype(b)) => {
+            a.name == b.name
+                && a.external_id == b.external_id
+                && a.internal_subset == b.internal_subset
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Parser,
+        element::{ElementType, attributes::Attribute},
+        lexer::{Input, Lexer},
+        parser::{ast::TextNode, incremental::TextEdit},
+    };
+
+    const SOURCE: &str = r##"<?xml version="1.0" encoding="UTF-8"?>
+<!-- drawn by hand -->
+<svg xmlns="http://www.w3.org/2000/svg"   viewBox = '0 0 10 10'>
+	<g id="a" fill="#F00">
+		<circle cx="1"  r="2"/>
+		<rect
+		  id='r' x="1"   width="2" ></rect>
+	</g>
+	<text>Tom &amp; Jerry</text>
+</svg>
+"##;
+
+    fn parse(source: &str) -> AST {
+        Parser::new(Lexer::new(Input::new(source)))
+            .with_lossless(true)
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn to_svg_lossless_should_reproduce_an_unmodified_source() {
+        assert_eq!(parse(SOURCE).to_svg_lossless(), SOURCE);
+    }
+
+    #[test]
+    fn to_svg_lossless_should_only_change_modified_attributes() {
+        let mut ast = parse(SOURCE);
+        let rect = ast.find_by_id("r").unwrap();
+        ast.set_attribute(rect, Attribute::Id("s".to_string()));
+        ast.remove_attribute(rect, "width");
+        ast.add_attribute(rect, Attribute::Data("data-x".to_string(), "1".to_string()));
+
+        let expected = SOURCE.replace(
+            "id='r' x=\"1\"   width=\"2\" ></rect>",
+            "id='s' x=\"1\" data-x=\"1\" ></rect>",
+        );
+        assert_eq!(ast.to_svg_lossless(), expected);
+    }
+
+    #[test]
+    fn to_svg_lossless_should_keep_siblings_of_changed_children() {
+        let mut ast = parse(SOURCE);
+        let g = ast.find_by_id("a").unwrap();
+        let path = ast.find_by_type(ElementType::Circle)[0];
+        ast.remove_node(path);
+        let text = ast.insert_node(Node::Text(TextNode {
+            content: "<new>".to_string(),
+            parent: None,
+        }));
+        ast.append_child(g, text);
+
+        let expected = SOURCE
+            .replace("\t\t<circle cx=\"1\"  r=\"2\"/>\n", "")
+            .replace("</rect>\n\t</g>", "</rect>\n\t\t&lt;new&gt;\n\t</g>");
+        assert_eq!(ast.to_svg_lossless(), expected);
+
+        // a self-closing element that gets children
+        let source = "<svg>\n  <g />\n</svg>";
+        let mut ast = parse(source);
+        let g = ast.find_by_type(ElementType::G)[0];
+        let rect = ast.insert_node(Node::Element(Element {
+            element_type: ElementType::Rect,
+            prefix: None,
+            attributes: vec![],
+            children: vec![],
+            parent: None,
+        }));
+        ast.append_child(g, rect);
+        assert_eq!(ast.to_svg_lossless(), "<svg>\n  <g ><rect/></g>\n</svg>");
+
+        // a new first child
+        let svg = ast.find_root().unwrap();
+        let circle = ast.insert_node(Node::Element(Element {
+            element_type: ElementType::Circle,
+            prefix: None,
+            attributes: vec![],
+            children: vec![],
+            parent: None,
+        }));
+        ast.prepend_child(svg, circle);
+        assert_eq!(
+            ast.to_svg_lossless(),
+            "<svg>\n  <circle/>\n  <g ><rect/></g>\n</svg>"
+        );
+    }
+
+    #[test]
+    fn to_svg_lossless_should_follow_reparse() {
+        let mut ast = parse(SOURCE);
+        let start = SOURCE.find("Tom").unwrap();
+        let edit = TextEdit::new(start..start + 3, "Spike");
+        let mut edited = SOURCE.to_string();
+        edited.replace_range(edit.range.clone(), &edit.replacement);
+
+        ast.reparse(&edit, &edited).unwrap();
+        assert_eq!(ast.to_svg_lossless(), edited);
+
+        let rect = ast.find_by_id("r").unwrap();
+        ast.set_attribute(rect, Attribute::Id("s".to_string()));
+        assert_eq!(ast.to_svg_lossless(), edited.replace("id='r'", "id='s'"));
+    }
+
+    #[test]
+    fn to_svg_lossless_should_close_elements_closed_in_lenient_mode() {
+        let cases = [
+            (
+                "<svg><g><rect><title>t</title></g></svg>",
+                r#"<svg><g><rect id="r"><title>t</title></rect></g></svg>"#,
+            ),
+            (
+                "<svg><rect><title>t</title>",
+                r#"<svg><rect id="r"><title>t</title></rect></svg>"#,
+            ),
+            ("<svg><rect></svg>", r#"<svg><rect id="r"/></svg>"#),
+        ];
+
+        for (source, expected) in cases {
+            let (mut ast, _) = Parser::new(Lexer::new(Input::new(source)))
+                .with_lossless(true)
+                .parse_lenient();
+            assert_eq!(ast.to_svg_lossless(), source);
+
+            let rect = ast.find_by_type(ElementType::Rect)[0];
+            ast.set_attribute(rect, Attribute::Id("r".to_string()));
+            assert_eq!(ast.to_svg_lossless(), expected, "{source}");
+        }
+    }
+
+    #[test]
+    fn to_svg_lossless_should_fall_back_without_a_source() {
+        let ast = Parser::parse_reader(SOURCE.as_bytes()).unwrap();
+        assert_eq!(ast.to_svg_lossless(), ast.to_svg());
+
+        let ast = Parser::new(Lexer::new(Input::new(SOURCE))).parse().unwrap();
+        assert!(ast.source.is_none());
+        assert_eq!(ast.to_svg_lossless(), ast.to_svg());
+    }
+}
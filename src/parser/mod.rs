@@ -1,385 +1,297 @@
+use std::{io::Read, ops::Range};
+
 use crate::{
     Element,
-    parser::ast::{AST, CommentNode, Node, NodeId, TextNode},
-    token::{Token, TokenKind},
+    error::{Diagnostic, ParseError},
+    lexer::{Lexer, StreamLexer},
+    parser::{
+        ast::{AST, CDataNode, CommentNode, Node, NodeId, TextNode},
+        events::{Event, Events},
+    },
+    span::{AttributeSpan, LineIndex, Locate, NodeSpan},
 };
 
 pub mod ast;
+pub mod events;
+pub mod incremental;
+pub mod lossless;
+pub(crate) mod prolog;
 
-pub struct Parser<'input> {
-    lexer: crate::lexer::Lexer<'input>,
+/// How the parser reacts to malformed input.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ParseMode {
+    /// Stop at the first problem and return it as a [`ParseError`].
+    #[default]
+    Strict,
+    /// Record every problem as a [`Diagnostic`], recover, and keep building the AST.
+    Lenient,
 }
 
-#[derive(Debug, Default, PartialEq)]
-enum State {
-    #[default]
-    Text, // outside of any tag
-    TagOpen, // after <
-    TagName,
-    AttributeName,
-    AttributeEquals,
-    AttributeValueOpeningQuote,
-    AttributeValue,
-    AttributeValueClosingQuote,
-    TagClose, // after </
-    TagCloseName,
+pub struct Parser<'input> {
+    events: Events<'input>,
+    /// Whether the AST keeps its source for [`AST::to_svg_lossless`].
+    lossless: bool,
 }
 
+/// Builds an [`AST`] from [`Event`]s.
+///
+/// Spans are converted to lines and columns by the [`Locate`] passed with
+/// each event.
 #[derive(Debug, Default)]
-struct StateMachine {
+struct TreeBuilder {
     ast: AST,
-    current_state: State,
-    element_stack: Vec<NodeId>,
-    current_attribute: Option<String>,
+    /// Elements whose [`Event::EndElement`] has not been seen yet.
+    open_elements: Vec<NodeId>,
 }
 
-impl StateMachine {
-    fn consume(&mut self, token: Token) {
-        match (&self.current_state, token.kind) {
-            (State::Text, TokenKind::LessThan) => {
-                self.current_state = State::TagOpen;
-            }
-            (State::TagOpen, TokenKind::Identifier) => {
-                let Ok(element_type) = token.value.parse() else {
-                    panic!(
-                        "Parse error, expected a valid element name, got: {}",
-                        &token.value
-                    );
-                };
-
-                let element = Element {
-                    element_type,
-                    attributes: vec![],
-                    children: vec![],
-                    parent: None,
-                };
-
-                self.current_state = State::TagName;
-                let node_id = self.ast.nodes.insert(Node::Element(element));
-                self.element_stack.push(node_id);
-            }
-            (State::TagName | State::AttributeValueClosingQuote, TokenKind::Identifier) => {
-                self.current_attribute = Some(token.value);
-                self.current_state = State::AttributeName;
-            }
-            (State::AttributeName, TokenKind::Equals) => {
-                self.current_state = State::AttributeEquals;
-            }
-            (State::AttributeName, TokenKind::Identifier) => {
-                let attribute_name = self.current_attribute.take().unwrap();
-                let attribute_value = "".to_string();
-
-                let Ok(attribute) = (&attribute_name, &attribute_value).try_into() else {
-                    panic!("Invalid attribute, got: {}", attribute_name);
-                };
+impl TreeBuilder {
+    fn push(&mut self, event: Event<'_>, locate: &mut impl Locate) {
+        match event {
+            Event::StartElement {
+                ty,
+                prefix,
+                attributes,
+                sources,
+                span,
+            } => {
+                let node_id = self.insert(
+                    Node::Element(Element {
+                        element_type: ty,
+                        prefix,
+                        attributes,
+                        children: vec![],
+                        parent: None,
+                    }),
+                    span,
+                    locate,
+                );
 
-                let node_id = self.element_stack.last().unwrap();
-
-                let Node::Element(en) = self.ast.nodes.get_mut(*node_id).unwrap() else {
-                    panic!("Node is supposed to be of type Element at this point");
-                };
-
-                en.attributes.push(attribute);
+                if let Some(node_span) = self.ast.spans.get_mut(node_id) {
+                    node_span.attributes = sources
+                        .into_iter()
+                        .map(|source| AttributeSpan {
+                            name: source.name.to_string(),
+                            span: locate.span(source.span),
+                            quote: source.quote,
+                        })
+                        .collect();
+                }
 
-                self.current_state = State::AttributeName;
-                self.current_attribute = Some(token.value);
+                self.open_elements.push(node_id);
             }
-            (State::AttributeName, TokenKind::GreaterThan) => {
-                let attribute_name = self.current_attribute.take().unwrap();
-                let attribute_value = "".to_string();
-
-                let Ok(attribute) = (&attribute_name, &attribute_value).try_into() else {
-                    panic!("Invalid attribute, got: {}", attribute_name);
-                };
-
-                let node_id = self.element_stack.last().unwrap();
-
-                let Node::Element(en) = self.ast.nodes.get_mut(*node_id).unwrap() else {
-                    panic!("Node is supposed to be of type Element at this point");
-                };
-
-                en.attributes.push(attribute);
+            Event::EndElement { span, .. } => {
+                if let Some(node_id) = self.open_elements.pop() {
+                    self.end_element(node_id, span, locate);
+                }
+            }
+            Event::Text { content, span } => {
+                // indentation between elements is formatting, not content
+                if content.trim_ascii().is_empty() && !self.preserves_whitespace() {
+                    return;
+                }
 
-                self.current_state = State::Text;
+                let node = Node::Text(TextNode {
+                    content: content.into_owned(),
+                    parent: None,
+                });
+                self.insert(node, span, locate);
             }
-            (State::AttributeEquals, TokenKind::Quote) => {
-                self.current_state = State::AttributeValueOpeningQuote;
+            Event::CData { content, span } => {
+                let node = Node::CData(CDataNode {
+                    content: content.to_string(),
+                    parent: None,
+                });
+                self.insert(node, span, locate);
             }
-            (State::AttributeValueOpeningQuote, TokenKind::Quote) => {
-                self.current_state = State::AttributeValueClosingQuote;
+            Event::Comment { content, span } => {
+                let node = Node::Comment(CommentNode {
+                    content: content.to_string(),
+                    parent: None,
+                });
+                self.insert(node, span, locate);
             }
-            (State::AttributeValueOpeningQuote, TokenKind::Literal) => {
-                let attribute_name = self.current_attribute.take().unwrap();
-                let attribute_value = token.value;
-
-                let Ok(attribute) = (&attribute_name, &attribute_value).try_into() else {
-                    panic!(
-                        "Invalid attribute, got: {}={}",
-                        attribute_name, attribute_value
-                    );
-                };
-
-                let node_id = self.element_stack.last().unwrap();
-
-                let Node::Element(en) = self.ast.nodes.get_mut(*node_id).unwrap() else {
-                    panic!("Node is supposed to be of type Element at this point");
-                };
-
-                en.attributes.push(attribute);
-
-                self.current_state = State::AttributeValue;
-                self.current_attribute = None;
+            Event::XmlDeclaration { declaration, span } => {
+                self.insert(Node::XmlDeclaration(declaration), span, locate);
             }
-            (State::AttributeValue, TokenKind::Quote) => {
-                self.current_state = State::AttributeValueClosingQuote;
+            Event::Doctype { doctype, span } => {
+                self.insert(Node::Doctype(doctype), span, locate);
             }
-            (State::TagName | State::AttributeValueClosingQuote, TokenKind::GreaterThan) => {
-                // here we are in a <tagname>
-                self.current_state = State::Text;
+            Event::ProcessingInstruction { instruction, span } => {
+                self.insert(Node::ProcessingInstruction(instruction), span, locate);
             }
+        }
+    }
 
-            (
-                State::TagName | State::AttributeValueClosingQuote | State::AttributeName,
-                TokenKind::SlashGreaterThan,
-            ) => {
-                // here we are in a <tagname/>
-                if self.current_state == State::AttributeName {
-                    let attribute_name = self.current_attribute.take().unwrap();
-                    let attribute_value = "".to_string();
-
-                    let Ok(attribute) = (&attribute_name, &attribute_value).try_into() else {
-                        panic!("Invalid attribute, got: {}", attribute_name);
-                    };
-
-                    let node_id = self.element_stack.last().unwrap();
-
-                    let Node::Element(en) = self.ast.nodes.get_mut(*node_id).unwrap() else {
-                        panic!("Node is supposed to be of type Element at this point");
-                    };
-
-                    en.attributes.push(attribute);
-                }
-
-                self.current_state = State::Text;
-
-                let element_id = self.element_stack.pop().unwrap();
-                if let Some(last_element_id) = self.element_stack.last() {
-                    let child_type = match self.ast.nodes.get(element_id).unwrap() {
-                        Node::Element(e) => e.element_type,
-                        _ => panic!("Node is supposed to be of type Element at this point"),
-                    };
-
-                    let Node::Element(last_en) = self.ast.nodes.get_mut(*last_element_id).unwrap()
-                    else {
-                        panic!("Node is supposed to be of type Element at this point");
-                    };
-
-                    if !last_en.is_allowed_as_child(&child_type) {
-                        panic!(
-                            "<{}> is not allowed as a child of <{}>",
-                            child_type, last_en.element_type
-                        );
-                    }
+    /// Inserts a node, records its span and appends it to the children of the
+    /// innermost open element, or to the root.
+    fn insert(&mut self, node: Node, range: Range<usize>, locate: &mut impl Locate) -> NodeId {
+        let node_id = self.ast.nodes.insert(node);
+        self.ast.spans.insert(
+            node_id,
+            NodeSpan {
+                span: locate.span(range),
+                ..Default::default()
+            },
+        );
 
-                    let parent_id = *last_element_id;
-                    let child_id = element_id;
-                    {
-                        last_en.children.push(child_id);
-                    }
-                    if let Some(Node::Element(en)) = self.ast.nodes.get_mut(child_id) {
-                        en.parent = Some(parent_id);
-                    }
-                } else {
-                    self.ast.children.push(element_id);
+        match self.open_elements.last() {
+            Some(&parent_id) => {
+                if let Some(Node::Element(parent)) = self.ast.nodes.get_mut(parent_id) {
+                    parent.children.push(node_id);
                 }
+                self.ast.set_parent(node_id, Some(parent_id));
             }
-            (State::Text, TokenKind::LessThanSlash) => {
-                self.current_state = State::TagClose;
-            }
-            (State::TagClose, TokenKind::Identifier) => {
-                let Some(element_id) = self.element_stack.pop() else {
-                    panic!("Element stack is empty");
-                };
-
-                let child_type = match self.ast.nodes.get(element_id).unwrap() {
-                    Node::Element(e) => {
-                        if e.element_type.as_str() != token.value {
-                            panic!(
-                                "Element stack does not match closing tag {} != {}",
-                                e.element_type, token.value
-                            );
-                        }
-                        e.element_type
-                    }
-                    _ => panic!("Node is supposed to be of type Element at this point"),
-                };
-
-                if let Some(last_element_id) = self.element_stack.last_mut() {
-                    let Node::Element(last_en) = self.ast.nodes.get_mut(*last_element_id).unwrap()
-                    else {
-                        panic!("Node is supposed to be of type Element at this point");
-                    };
-
-                    if !last_en.is_allowed_as_child(&child_type) {
-                        panic!(
-                            "<{}> is not allowed as a child of <{}>",
-                            child_type, last_en.element_type
-                        );
-                    }
-
-                    let parent_id = *last_element_id;
-                    let child_id = element_id;
-                    {
-                        last_en.children.push(child_id);
-                    }
-                    if let Some(Node::Element(en)) = self.ast.nodes.get_mut(child_id) {
-                        en.parent = Some(parent_id);
-                    }
-                } else {
-                    self.ast.children.push(element_id);
-                }
+            None => self.ast.children.push(node_id),
+        }
 
-                self.current_state = State::TagCloseName;
-            }
-            (State::TagCloseName, TokenKind::GreaterThan) => {
-                self.current_state = State::Text;
-            }
-            (State::TagCloseName, TokenKind::SlashGreaterThan) => {
-                // TODO: This is not a self closing element, it has a typo, we should be able to
-                // parse this correctly, but we also need to emit a warning in the CLI
-                unimplemented!("Tag close name should not be followed by a slash");
-            }
-            (State::TagCloseName, TokenKind::Identifier) => {
-                // TODO: error
-                panic!("Unexpected identifier after tag close name");
-            }
-            (State::Text, TokenKind::Text) => {
-                let node_id = self.ast.nodes.insert(Node::Text(TextNode {
-                    content: token.value,
-                    parent: None,
-                }));
+        node_id
+    }
 
-                let last_element_id = self.element_stack.last().unwrap();
+    /// Returns `true` if whitespace in the innermost open element is significant.
+    fn preserves_whitespace(&self) -> bool {
+        self.open_elements
+            .last()
+            .is_some_and(|&id| self.ast.is_whitespace_significant(id))
+    }
 
-                let Node::Element(last_en) = self.ast.nodes.get_mut(*last_element_id).unwrap()
-                else {
-                    panic!("Node is supposed to be of type Element at this point");
-                };
+    /// Moves the end of an element's span to the end of its closing tag.
+    ///
+    /// An element closed in lenient mode, with an empty `span`, ends with its
+    /// last child instead.
+    fn end_element(&mut self, node_id: NodeId, span: Range<usize>, locate: &mut impl Locate) {
+        let last_child = match self.ast.nodes.get(node_id) {
+            Some(Node::Element(element)) => element.children.last().copied(),
+            _ => None,
+        };
+        let last_child_end = last_child
+            .and_then(|id| self.ast.spans.get(id))
+            .map(|node_span| node_span.span.end);
 
-                let parent_id = *last_element_id;
-                let child_id = node_id;
-                {
-                    last_en.children.push(child_id);
-                }
-                if let Some(Node::Text(tn)) = self.ast.nodes.get_mut(child_id) {
-                    tn.parent = Some(parent_id);
-                }
-            }
-            (
-                State::Text,
-                TokenKind::GreaterThan
-                | TokenKind::SlashGreaterThan
-                | TokenKind::Identifier
-                | TokenKind::Literal
-                | TokenKind::Quote
-                | TokenKind::Equals,
-            ) => {
-                unreachable!("Unexpected token after tag close name");
-            }
-            (State::TagCloseName, TokenKind::Literal) => {
-                unreachable!("Unexpected literal after tag close name");
-            }
-            (_, TokenKind::Comment) => {
-                let content = &token.value[4..token.value.len() - 3];
-                let node_id = self.ast.nodes.insert(Node::Comment(CommentNode {
-                    content: content.to_string(),
-                    parent: None,
-                }));
-
-                if let Some(last_element_id) = self.element_stack.last() {
-                    let Node::Element(last_en) = self.ast.nodes.get_mut(*last_element_id).unwrap()
-                    else {
-                        panic!("Node is supposed to be of type Element at this point");
-                    };
-                    let parent_id = *last_element_id;
-                    let child_id = node_id;
-                    {
-                        last_en.children.push(child_id);
-                    }
-                    if let Some(Node::Comment(cn)) = self.ast.nodes.get_mut(child_id) {
-                        cn.parent = Some(parent_id);
-                    }
-                } else {
-                    self.ast.children.push(node_id);
-                }
-            }
-            (State::TagOpen, TokenKind::LessThan | TokenKind::LessThanSlash) => {
-                panic!("Unexpected < after tag open (sequence <<) ");
-            }
-            (_, TokenKind::Text) => {
-                unreachable!("Unexpected text token");
-            }
-            (State::TagOpen, TokenKind::GreaterThan | TokenKind::SlashGreaterThan) => {
-                panic!("Unexpected > after tag open (sequence <>) ");
-            }
-            (_, TokenKind::Literal) => {
-                unreachable!("Unexpected literal token");
-            }
-            (_, TokenKind::Quote) => {
-                unreachable!("Unexpected quote token");
-            }
-            (_, TokenKind::LessThan | TokenKind::LessThanSlash) => {
-                unreachable!("Unexpected opening tag token");
-            }
-            (_, TokenKind::Equals) => {
-                unreachable!("Unexpected equals token");
-            }
-            (State::AttributeEquals, _) => {
-                unreachable!("Unexpected token after attribute equals");
-            }
-            (
-                State::AttributeValueOpeningQuote | State::AttributeValue,
-                TokenKind::GreaterThan | TokenKind::SlashGreaterThan | TokenKind::Identifier,
-            ) => {
-                unreachable!("Unexpected > after attribute value opening quote");
-            }
-            (State::TagClose, TokenKind::GreaterThan | TokenKind::SlashGreaterThan) => {
-                panic!("unexpected > after a tag close (sequence </> or <> or even <//>)");
+        let Some(node_span) = self.ast.spans.get_mut(node_id) else {
+            return;
+        };
+        if span.is_empty() {
+            if let Some(end) = last_child_end {
+                node_span.span.end = end;
             }
+        } else if span.end > node_span.span.end.offset {
+            let end_tag = locate.span(span);
+            node_span.span.end = end_tag.end;
+            node_span.end_tag = Some(end_tag);
         }
     }
 }
 
 impl<'input> Parser<'input> {
-    pub fn new(lexer: crate::lexer::Lexer<'input>) -> Self {
-        Self { lexer }
+    pub fn new(lexer: Lexer<'input>) -> Self {
+        Self {
+            events: Events::new(lexer, ParseMode::Strict),
+            lossless: false,
+        }
     }
 
-    pub fn parse(&mut self) -> Option<ast::AST> {
-        let mut state_machine = StateMachine::default();
+    /// Sets the number of elements that may be nested in each other, which is
+    /// [`DEFAULT_MAX_DEPTH`](events::DEFAULT_MAX_DEPTH) by default.
+    ///
+    /// Deeper documents fail to parse with [`ParseErrorKind::NestingTooDeep`];
+    /// lenient parsing keeps the elements before the first one that is too
+    /// deep.
+    ///
+    /// [`ParseErrorKind::NestingTooDeep`]: crate::ParseErrorKind::NestingTooDeep
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.events.max_depth = max_depth;
+        self
+    }
+
+    /// Sets whether the [`AST`] keeps the source text and its nodes as parsed,
+    /// which [`AST::to_svg_lossless`] needs to reproduce the source.
+    ///
+    /// Off by default, as it doubles the memory of the parsed document.
+    pub fn with_lossless(mut self, lossless: bool) -> Self {
+        self.lossless = lossless;
+        self
+    }
+
+    /// Parses the whole input into an [`AST`].
+    ///
+    /// Stops at the first problem and returns it as a [`ParseError`].
+    pub fn parse(&mut self) -> Result<AST, ParseError> {
+        let (ast, _) = self.run(ParseMode::Strict)?;
+
+        Ok(ast)
+    }
+
+    /// Parses the whole input into a best-effort [`AST`], never failing.
+    ///
+    /// Every problem is recorded as a [`Diagnostic`] and the parser recovers:
+    /// unclosed elements are closed at the end of input, stray closing tags are
+    /// dropped, disallowed children are kept, and `</g/>` is read as `</g>`.
+    pub fn parse_lenient(&mut self) -> (AST, Vec<Diagnostic>) {
+        self.run(ParseMode::Lenient)
+            .expect("lenient parsing records errors as diagnostics")
+    }
 
-        while let Some(token) = self.lexer.advance() {
-            state_machine.consume(token);
+    /// Returns the input as a stream of [`Event`]s instead of building an [`AST`].
+    pub fn into_events(self, mode: ParseMode) -> Events<'input> {
+        let mut events = self.events;
+        events.mode = mode;
+        events
+    }
+
+    fn run(&mut self, mode: ParseMode) -> Result<(AST, Vec<Diagnostic>), ParseError> {
+        self.events.mode = mode;
+        let mut line_index = LineIndex::new(self.events.lexer.input.bytes);
+        let mut builder = TreeBuilder::default();
+
+        for event in &mut self.events {
+            builder.push(event?, &mut line_index);
         }
+        if self.lossless {
+            builder.ast.record_source(self.events.lexer.input.source());
+        }
+        builder.ast.mode = mode;
+        builder.ast.max_depth = Some(self.events.max_depth);
+
+        Ok((builder.ast, std::mem::take(&mut self.events.diagnostics)))
+    }
+}
+
+impl Parser<'_> {
+    /// Parses a document read from `reader` into an [`AST`], without reading
+    /// all of it into memory first.
+    ///
+    /// The encoding is detected and UTF-16 and ISO-8859-1 documents are
+    /// transcoded, as by [`encoding::decode`](crate::encoding::decode). Stops
+    /// at the first problem, including a failed read, and returns it as a
+    /// [`ParseError`].
+    pub fn parse_reader<R: Read>(reader: R) -> Result<AST, ParseError> {
+        let mut events = Events::new(StreamLexer::new(reader), ParseMode::Strict);
+        let mut builder = TreeBuilder::default();
 
-        // TODO: make this an actual error
-        assert_eq!(state_machine.element_stack.len(), 0);
+        while let Some(event) = events.next() {
+            // the lexer only keeps the text that the pending events refer to
+            builder.push(event?, &mut events.lexer);
+        }
 
-        Some(state_machine.ast)
+        Ok(builder.ast)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::element::attributes::Attribute;
     use crate::element::ElementType;
+    use crate::element::attributes::{Attribute, PathError, PathErrorKind, XmlSpace};
+    use crate::error::{Construct, Diagnostic, ParseError, ParseErrorKind, Severity};
     use crate::lexer::Input;
-    use crate::Parser;
+    use crate::namespace::{QualifiedName, XMLNS_NAMESPACE};
+    use crate::parser::ast::{AST, Node};
+    use crate::token::TokenKind;
+    use crate::{Parser, SerializeOptions};
 
     #[test]
     fn parse_data_attribute() {
         let svg = r#"<rect data-test="value" x="10" y="10"/>"#;
-        let lexer = crate::Lexer::new(Input::new(svg.as_bytes()));
+        let lexer = crate::Lexer::new(Input::new(svg));
         let ast = Parser::new(lexer).parse().unwrap();
 
         let rects = ast.find_by_type(ElementType::Rect);
@@ -404,10 +316,10 @@ mod tests {
     #[test]
     fn parse_multiple_data_attributes() {
         let svg = r#"<rect data-foo="bar" data-baz="qux" data-foo-bar="baz-qux"/>"#;
-        let lexer = crate::Lexer::new(Input::new(svg.as_bytes()));
+        let lexer = crate::Lexer::new(Input::new(svg));
         let ast = Parser::new(lexer).parse().unwrap();
 
-      let rects = ast.find_by_type(ElementType::Rect);
+        let rects = ast.find_by_type(ElementType::Rect);
         assert_eq!(rects.len(), 1);
         let rect = rects[0];
         let rect_node = ast.nodes.get(rect).unwrap();
@@ -436,4 +348,968 @@ mod tests {
             panic!("Expected Data attribute at index 2");
         }
     }
+
+    fn parse_err(svg: &str) -> ParseError {
+        let lexer = crate::Lexer::new(Input::new(svg));
+        Parser::new(lexer).parse().unwrap_err()
+    }
+
+    #[test]
+    fn parse_keeps_unknown_elements_and_attributes() {
+        let ast =
+            parse_ok(r#"<svg><blink speed="fast"><rect x="5" vendor-hint="a"/></blink></svg>"#);
+
+        let blink_id = ast.find_by_type(ElementType::Unknown("blink".to_string()))[0];
+        assert_eq!(
+            ast.get_attribute(blink_id, "speed"),
+            Some(&Attribute::Unknown("speed".to_string(), "fast".to_string()))
+        );
+
+        let output = ast.to_svg();
+        assert!(output.contains(r#"<blink speed="fast">"#));
+        assert!(output.contains(r#"vendor-hint="a"/>"#));
+        assert!(output.contains("</blink>"));
+    }
+
+    #[test]
+    fn parse_mismatched_close_tag_returns_error() {
+        let error = parse_err(r#"<svg><g></svg>"#);
+
+        assert_eq!(
+            error.kind,
+            ParseErrorKind::MismatchedCloseTag {
+                expected: Some("g".to_string()),
+                found: "svg".to_string(),
+            }
+        );
+        assert_eq!(error.span, 10..13);
+    }
+
+    #[test]
+    fn parse_stray_close_tag_returns_error() {
+        let error = parse_err(r#"<svg/></g>"#);
+
+        assert_eq!(
+            error.kind,
+            ParseErrorKind::MismatchedCloseTag {
+                expected: None,
+                found: "g".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_disallowed_child_returns_error() {
+        let error = parse_err(r#"<svg><circle r="1"><rect/></circle></svg>"#);
+
+        assert_eq!(
+            error.kind,
+            ParseErrorKind::DisallowedChild {
+                parent: ElementType::Circle,
+                child: ElementType::Rect,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_invalid_attribute_returns_error() {
+        let error = parse_err(r#"<svg version="one"/>"#);
+
+        assert_eq!(
+            error.kind,
+            ParseErrorKind::InvalidAttribute {
+                name: "version".to_string(),
+                value: "one".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_unexpected_token_reports_expected_and_found() {
+        let error = parse_err(r#"<svg><></svg>"#);
+
+        assert_eq!(error.kind, ParseErrorKind::UnexpectedToken);
+        assert_eq!(error.found, Some(TokenKind::GreaterThan));
+        assert_eq!(error.expected, vec![TokenKind::Identifier]);
+        assert_eq!(error.span, 6..7);
+    }
+
+    #[test]
+    fn parse_unterminated_constructs_return_errors() {
+        assert_eq!(
+            parse_err(r#"<svg><!-- never closed"#).kind,
+            ParseErrorKind::UnterminatedConstruct(Construct::Comment)
+        );
+        assert_eq!(
+            parse_err(r#"<svg id="abc/>"#).kind,
+            ParseErrorKind::UnterminatedConstruct(Construct::AttributeValue)
+        );
+        assert_eq!(
+            parse_err(r#"<svg><g>"#).kind,
+            ParseErrorKind::UnterminatedConstruct(Construct::Element(ElementType::G))
+        );
+    }
+
+    #[test]
+    fn parse_comment_opener_does_not_close_the_comment() {
+        for svg in ["<!-->", "<!--->", "<svg><!--></svg>"] {
+            let comment = ParseErrorKind::UnterminatedConstruct(Construct::Comment);
+
+            assert_eq!(parse_err(svg).kind, comment, "{svg}");
+            assert_eq!(
+                Parser::parse_reader(svg.as_bytes()).unwrap_err().kind,
+                comment
+            );
+            assert!(
+                kinds(&parse_lenient(svg).1).contains(&(Severity::Error, comment)),
+                "{svg}"
+            );
+        }
+    }
+
+    fn parse_lenient(svg: &str) -> (String, Vec<Diagnostic>) {
+        let lexer = crate::Lexer::new(Input::new(svg));
+        let (ast, diagnostics): (AST, _) = Parser::new(lexer).parse_lenient();
+        (ast.to_svg(), diagnostics)
+    }
+
+    fn kinds(diagnostics: &[Diagnostic]) -> Vec<(Severity, ParseErrorKind)> {
+        diagnostics
+            .iter()
+            .map(|d| (d.severity, d.error.kind.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn lenient_closes_unclosed_elements_at_eof() {
+        let (svg, diagnostics) = parse_lenient(r#"<svg><g><rect/>"#);
+
+        assert_eq!(svg, parse_lenient(r#"<svg><g><rect/></g></svg>"#).0);
+        assert_eq!(
+            kinds(&diagnostics),
+            vec![
+                (
+                    Severity::Error,
+                    ParseErrorKind::UnterminatedConstruct(Construct::Element(ElementType::G))
+                ),
+                (
+                    Severity::Error,
+                    ParseErrorKind::UnterminatedConstruct(Construct::Element(ElementType::Svg))
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn lenient_recovers_from_mismatched_close_tags() {
+        let (svg, diagnostics) = parse_lenient(r#"<svg><g><rect></g></text></svg>"#);
+
+        assert_eq!(svg, parse_lenient(r#"<svg><g><rect/></g></svg>"#).0);
+        assert_eq!(
+            kinds(&diagnostics),
+            vec![
+                (
+                    Severity::Error,
+                    ParseErrorKind::MismatchedCloseTag {
+                        expected: Some("rect".to_string()),
+                        found: "g".to_string(),
+                    }
+                ),
+                (
+                    Severity::Error,
+                    ParseErrorKind::MismatchedCloseTag {
+                        expected: Some("svg".to_string()),
+                        found: "text".to_string(),
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn lenient_accepts_slash_in_close_tag() {
+        let (svg, diagnostics) = parse_lenient(r#"<svg><g></g/></svg>"#);
+
+        assert_eq!(svg, parse_lenient(r#"<svg><g></g></svg>"#).0);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+        assert_eq!(
+            diagnostics[0].error.found,
+            Some(TokenKind::SlashGreaterThan)
+        );
+        assert_eq!(diagnostics[0].error.expected, vec![TokenKind::GreaterThan]);
+    }
+
+    #[test]
+    fn lenient_drops_invalid_attributes() {
+        let (svg, diagnostics) = parse_lenient(r#"<svg><rect version="one" x="5"/></svg>"#);
+
+        assert_eq!(svg, parse_lenient(r#"<svg><rect x="5"/></svg>"#).0);
+        assert_eq!(
+            kinds(&diagnostics),
+            vec![(
+                Severity::Error,
+                ParseErrorKind::InvalidAttribute {
+                    name: "version".to_string(),
+                    value: "one".to_string(),
+                }
+            )]
+        );
+    }
+
+    #[test]
+    fn lenient_allows_anything_inside_unknown_elements() {
+        let (svg, diagnostics) =
+            parse_lenient(r#"<svg><circle><blink><rect/></blink></circle></svg>"#);
+
+        assert!(diagnostics.is_empty());
+        assert!(svg.contains("<rect/>"));
+    }
+
+    #[test]
+    fn lenient_keeps_disallowed_children_as_warnings() {
+        let (svg, diagnostics) = parse_lenient(r#"<svg><circle><rect/></circle></svg>"#);
+
+        assert!(svg.contains("<rect"));
+        assert_eq!(
+            kinds(&diagnostics),
+            vec![(
+                Severity::Warning,
+                ParseErrorKind::DisallowedChild {
+                    parent: ElementType::Circle,
+                    child: ElementType::Rect,
+                }
+            )]
+        );
+    }
+
+    #[test]
+    fn lenient_reports_every_problem_in_order() {
+        let (_, diagnostics) = parse_lenient(r#"<svg><><rect x=5/><!-- open"#);
+
+        let found: Vec<_> = diagnostics.iter().map(|d| d.error.kind.clone()).collect();
+        assert_eq!(
+            found,
+            vec![
+                ParseErrorKind::UnexpectedToken,
+                ParseErrorKind::UnexpectedToken,
+                ParseErrorKind::UnterminatedConstruct(Construct::Comment),
+                ParseErrorKind::UnterminatedConstruct(Construct::Element(ElementType::Svg)),
+            ]
+        );
+    }
+
+    #[test]
+    fn lenient_matches_strict_on_valid_input() {
+        let source = r#"<svg><g id="a"><rect x="5"/></g></svg>"#;
+        let lexer = crate::Lexer::new(Input::new(source));
+        let strict = Parser::new(lexer).parse().unwrap().to_svg();
+        let (lenient, diagnostics) = parse_lenient(source);
+
+        assert_eq!(strict, lenient);
+        assert!(diagnostics.is_empty());
+    }
+
+    fn parse_ok(svg: &str) -> AST {
+        let lexer = crate::Lexer::new(Input::new(svg));
+        Parser::new(lexer).parse().unwrap()
+    }
+
+    #[test]
+    fn parse_records_node_spans() {
+        let source = "<svg>\n  <g id=\"a\">\n    <rect/>\n  </g>\n  <!-- note -->\n</svg>";
+        let ast = parse_ok(source);
+
+        let svg_id = ast.children[0];
+        let g_id = ast.find_by_id("a").unwrap();
+        let rect_id = ast.find_by_type(ElementType::Rect)[0];
+        let comment_id = ast.find_all(|node| node.as_comment().is_some())[0];
+
+        let slice = |id| &source[ast.node_span(id).unwrap().range()];
+        assert_eq!(slice(svg_id), source);
+        assert_eq!(slice(g_id), "<g id=\"a\">\n    <rect/>\n  </g>");
+        assert_eq!(slice(rect_id), "<rect/>");
+        assert_eq!(slice(comment_id), "<!-- note -->");
+
+        let rect = ast.node_span(rect_id).unwrap();
+        assert_eq!((rect.start.line, rect.start.column), (3, 5));
+        assert_eq!((rect.end.line, rect.end.column), (3, 12));
+
+        let g = ast.node_span(g_id).unwrap();
+        assert_eq!((g.end.line, g.end.column), (4, 7));
+    }
+
+    #[test]
+    fn parse_records_attribute_spans() {
+        let source = "<svg>\n<rect x=\"5\" data-foo=\"bar\"/></svg>";
+        let ast = parse_ok(source);
+        let rect_id = ast.find_by_type(ElementType::Rect)[0];
+
+        let x = ast.attribute_span(rect_id, "x").unwrap();
+        assert_eq!(&source[x.range()], "x=\"5\"");
+        assert_eq!((x.start.line, x.start.column), (2, 7));
+
+        let data = ast.attribute_span(rect_id, "data-foo").unwrap();
+        assert_eq!(&source[data.range()], "data-foo=\"bar\"");
+        assert!(ast.attribute_span(rect_id, "y").is_none());
+    }
+
+    #[test]
+    fn parse_records_text_spans() {
+        let source = "<svg><text>Hello</text></svg>";
+        let ast = parse_ok(source);
+        let text_id = ast.find_all(|node| node.as_text().is_some())[0];
+
+        assert_eq!(ast.node_span(text_id).unwrap().range(), 11..16);
+    }
+
+    #[test]
+    fn validation_issues_point_at_source() {
+        let source = "<svg>\n<circle>\n<rect/></circle></svg>";
+        let (ast, _) = {
+            let lexer = crate::Lexer::new(Input::new(source));
+            Parser::new(lexer).parse_lenient()
+        };
+
+        let issues = ast.validate();
+        assert_eq!(issues.len(), 1);
+
+        let span = ast.node_span(issues[0].node_id().unwrap()).unwrap();
+        assert_eq!(&source[span.range()], "<rect/>");
+        assert_eq!((span.start.line, span.start.column), (3, 1));
+    }
+
+    #[test]
+    fn parse_single_quoted_attributes() {
+        let ast = parse_ok(r#"<svg><rect id='a' data-label='say "hi"'/></svg>"#);
+        let rect_id = ast.find_by_id("a").unwrap();
+
+        assert_eq!(
+            ast.get_attribute(rect_id, "data-label"),
+            Some(&Attribute::Data(
+                "data-label".to_string(),
+                r#"say "hi""#.to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn single_quote_does_not_close_double_quoted_value() {
+        let ast = parse_ok(r#"<svg><rect id="a" data-label="it's"/></svg>"#);
+        let rect_id = ast.find_by_id("a").unwrap();
+
+        assert_eq!(
+            ast.get_attribute(rect_id, "data-label"),
+            Some(&Attribute::Data(
+                "data-label".to_string(),
+                "it's".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn to_svg_keeps_attribute_quotes() {
+        let source = r#"<svg id='a' data-x="1" data-y='"'/>"#;
+
+        assert_eq!(parse_ok(source).to_svg(), format!("{source}\n"));
+    }
+
+    #[test]
+    fn parse_splits_attributes_on_any_whitespace() {
+        let ast = parse_ok("<svg>\n<rect\n  id=\"a\"\tx=\"1\"/></svg>");
+        let rect_id = ast.find_by_id("a").unwrap();
+
+        assert!(ast.has_attribute(rect_id, "x"));
+    }
+
+    #[test]
+    fn parse_keeps_text_whitespace() {
+        let ast = parse_ok("<svg>\n  <text>  a <tspan>b</tspan> <tspan>c</tspan></text>\n</svg>");
+        let svg = ast.find_root().unwrap();
+        let text = ast.find_by_type(ElementType::Text)[0];
+
+        // indentation between elements is dropped, spaces in text content are not
+        assert_eq!(ast.nodes[svg].as_element().unwrap().children, vec![text]);
+        let children = &ast.nodes[text].as_element().unwrap().children;
+        assert_eq!(ast.nodes[children[0]].as_text(), Some("  a "));
+        assert_eq!(ast.nodes[children[2]].as_text(), Some(" "));
+    }
+
+    #[test]
+    fn parse_keeps_whitespace_where_it_is_preserved() {
+        for source in [
+            r#"<svg><g xml:space="preserve"> <desc> </desc></g></svg>"#,
+            r#"<svg><g white-space="pre"> <desc> </desc></g></svg>"#,
+            r#"<svg><g style="fill: red; white-space: pre-wrap"> <desc> </desc></g></svg>"#,
+        ] {
+            let ast = parse_ok(source);
+            assert_eq!(ast.find_all(|node| node.as_text() == Some(" ")).len(), 2);
+        }
+
+        // the nearest declaration wins
+        let source = r#"<svg xml:space="preserve"><g xml:space="default"> </g></svg>"#;
+        assert!(
+            parse_ok(source)
+                .find_all(|node| node.as_text().is_some())
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn to_svg_does_not_reindent_significant_whitespace() {
+        let source =
+            "<svg>\n  <text id=\"t\">  a <tspan>b</tspan><tspan>c</tspan>\n</text>\n</svg>";
+        let svg = parse_ok(source).to_svg();
+
+        assert!(svg.contains("<text id=\"t\">  a <tspan>b</tspan><tspan>c</tspan>\n</text>"));
+        assert_eq!(parse_ok(&svg).to_svg(), svg);
+    }
+
+    #[test]
+    fn attribute_write_svg_quoted_escapes_quote() {
+        let attribute = Attribute::Data("data-x".to_string(), "it's".to_string());
+        let mut output = String::new();
+        attribute
+            .write_svg_quoted(&mut output, crate::element::attributes::Quote::Single)
+            .unwrap();
+
+        assert_eq!(output, "data-x='it&apos;s'");
+    }
+
+    #[test]
+    fn parse_cdata_section() {
+        let source = "<svg><style><![CDATA[rect > circle { fill: red; } /* <g> */]]></style></svg>";
+        let ast = parse_ok(source);
+        let style_id = ast.find_by_type(ElementType::Style)[0];
+        let Some(Node::Element(style)) = ast.get_node(style_id) else {
+            panic!("expected <style> element");
+        };
+
+        assert_eq!(style.children.len(), 1);
+        let cdata_id = style.children[0];
+        assert_eq!(
+            ast.get_node(cdata_id).and_then(Node::as_cdata),
+            Some("rect > circle { fill: red; } /* <g> */")
+        );
+        assert_eq!(ast.get_node(cdata_id).unwrap().parent_id(), Some(style_id));
+        assert_eq!(
+            &source[ast.node_span(cdata_id).unwrap().range()],
+            "<![CDATA[rect > circle { fill: red; } /* <g> */]]>"
+        );
+    }
+
+    #[test]
+    fn to_svg_writes_cdata_verbatim() {
+        let ast = parse_ok("<svg><script><![CDATA[if (a < b && c) {}]]></script></svg>");
+
+        assert!(ast.to_svg().contains("<![CDATA[if (a < b && c) {}]]>"));
+    }
+
+    #[test]
+    fn parse_unterminated_cdata_returns_error() {
+        assert_eq!(
+            parse_err("<svg><style><![CDATA[rect {}</style></svg>").kind,
+            ParseErrorKind::UnterminatedConstruct(Construct::CData)
+        );
+    }
+
+    #[test]
+    fn parse_prolog_nodes() {
+        let source = r#"<?xml version="1.0" encoding="UTF-8" standalone="no"?>
+<?xml-stylesheet href="style.css" type="text/css"?>
+<!DOCTYPE svg PUBLIC "-//W3C//DTD SVG 1.1//EN" "http://www.w3.org/Graphics/SVG/1.1/DTD/svg11.dtd" [
+  <!ENTITY ns_svg "http://www.w3.org/2000/svg">
+  <!-- a > inside a comment -->
+]>
+<svg><?custom-pi data?></svg>"#;
+        let ast = parse_ok(source);
+
+        assert_eq!(ast.children.len(), 4);
+        let nodes: Vec<_> = ast.children.iter().map(|id| &ast.nodes[*id]).collect();
+        assert!(
+            matches!(nodes[0], Node::XmlDeclaration(d) if d.encoding.as_deref() == Some("UTF-8"))
+        );
+        assert!(matches!(nodes[1], Node::ProcessingInstruction(p) if p.target == "xml-stylesheet"));
+        assert!(matches!(nodes[2], Node::Doctype(d) if d.name == "svg"
+            && d.internal_subset.as_deref().is_some_and(|s| s.contains("ns_svg"))));
+
+        let Node::Element(svg) = nodes[3] else {
+            panic!("expected <svg> element");
+        };
+        assert!(matches!(
+            &ast.nodes[svg.children[0]],
+            Node::ProcessingInstruction(p) if p.target == "custom-pi" && p.data == "data"
+        ));
+    }
+
+    #[test]
+    fn to_svg_preserves_prolog_nodes() {
+        let source = r#"<?xml version="1.0" encoding="UTF-8"?>
+<?xml-stylesheet href="style.css"?>
+<!DOCTYPE svg PUBLIC "-//W3C//DTD SVG 1.1//EN" "svg11.dtd" [<!ENTITY a "b">]>
+<svg/>
+"#;
+        let output = parse_ok(source).to_svg();
+
+        assert_eq!(output, source);
+        assert_eq!(parse_ok(&output).to_svg(), output);
+    }
+
+    #[test]
+    fn parse_rejects_a_comment_inside_a_tag() {
+        for (svg, expected) in [
+            (
+                r#"<svg><rect <!-- c --> x="1"/></svg>"#,
+                r#"<svg><rect x="1"/></svg>"#,
+            ),
+            (r#"<svg><rect/></svg <!-- c -->>"#, r#"<svg><rect/></svg>"#),
+        ] {
+            let error = parse_err(svg);
+            assert_eq!(error.kind, ParseErrorKind::UnexpectedToken, "{svg}");
+            assert_eq!(error.found, Some(TokenKind::Comment));
+
+            let (output, diagnostics) = parse_lenient(svg);
+            assert_eq!(
+                kinds(&diagnostics),
+                vec![(Severity::Error, ParseErrorKind::UnexpectedToken)]
+            );
+            assert_eq!(output, parse_ok(expected).to_svg(), "{svg}");
+        }
+    }
+
+    #[test]
+    fn parse_rejects_an_xml_declaration_after_the_start() {
+        let declaration = r#"<?xml version="1.0"?>"#;
+
+        // the number of declarations kept in lenient mode
+        for (svg, kept) in [
+            (format!("{declaration}{declaration}<svg/>"), 1),
+            (format!("<svg/>{declaration}"), 0),
+            (format!(" {declaration}<svg/>"), 0),
+        ] {
+            let error = parse_err(&svg);
+            assert_eq!(error.kind, ParseErrorKind::UnexpectedToken, "{svg}");
+            assert_eq!(error.found, Some(TokenKind::XmlDeclaration));
+
+            let (output, diagnostics) = parse_lenient(&svg);
+            assert_eq!(diagnostics.len(), 1, "{svg}");
+            assert_eq!(output.matches("<?xml").count(), kept, "{svg}");
+        }
+    }
+
+    #[test]
+    fn parse_malformed_prolog_returns_errors() {
+        assert_eq!(
+            parse_err(r#"<?xml encoding="UTF-8"?><svg/>"#).kind,
+            ParseErrorKind::MalformedConstruct(Construct::XmlDeclaration)
+        );
+        assert_eq!(
+            parse_err(r#"<!DOCTYPE svg [<!ENTITY a "b"><svg/>"#).kind,
+            ParseErrorKind::UnterminatedConstruct(Construct::Doctype)
+        );
+        assert_eq!(
+            parse_err(r#"<?xml-stylesheet href="a.css"<svg/>"#).kind,
+            ParseErrorKind::UnterminatedConstruct(Construct::ProcessingInstruction)
+        );
+    }
+
+    #[test]
+    fn parse_decodes_references() {
+        let ast = parse_ok(
+            r#"<svg><text id="t" data-label="a &amp; b &#x3C;&#62;">Tom &amp; Jerry &#169;</text></svg>"#,
+        );
+        let text_id = ast.find_by_id("t").unwrap();
+
+        assert_eq!(
+            ast.get_attribute(text_id, "data-label"),
+            Some(&Attribute::Data(
+                "data-label".to_string(),
+                "a & b <>".to_string()
+            ))
+        );
+        let Some(Node::Element(text)) = ast.get_node(text_id) else {
+            panic!("expected <text> element");
+        };
+        assert_eq!(
+            ast.get_node(text.children[0]).and_then(Node::as_text),
+            Some("Tom & Jerry ©")
+        );
+    }
+
+    #[test]
+    fn parse_decodes_doctype_entities() {
+        let ast = parse_ok(
+            r#"<!DOCTYPE svg [
+  <!ENTITY ns_svg "http://www.w3.org/2000/svg">
+  <!ENTITY ns_flows "http://ns.adobe.com/Flows/1.0/">
+]>
+<svg xmlns="&ns_svg;" data-flows="&ns_flows;"/>"#,
+        );
+        let svg_id = ast.find_by_type(ElementType::Svg)[0];
+
+        assert_eq!(
+            ast.get_attribute(svg_id, "xmlns"),
+            Some(&Attribute::Xmlns("http://www.w3.org/2000/svg".to_string()))
+        );
+        assert_eq!(
+            ast.get_attribute(svg_id, "data-flows"),
+            Some(&Attribute::Data(
+                "data-flows".to_string(),
+                "http://ns.adobe.com/Flows/1.0/".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn parse_unknown_reference_returns_error() {
+        let error = parse_err(r#"<svg data-x="&bogus;"/>"#);
+        assert_eq!(
+            error.kind,
+            ParseErrorKind::InvalidReference("&bogus;".to_string())
+        );
+
+        let (svg, diagnostics) = parse_lenient(r#"<svg data-x="&bogus;"/>"#);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(svg, "<svg data-x=\"&amp;bogus;\"/>\n");
+    }
+
+    #[test]
+    fn parse_keeps_path_data_before_the_first_error() {
+        let svg = r#"<svg><path d="M0 0 L10 10 L"/></svg>"#;
+        let error = PathError {
+            kind: PathErrorKind::ExpectedNumber,
+            position: 13,
+        };
+
+        assert_eq!(
+            parse_err(svg).kind,
+            ParseErrorKind::InvalidPathData(error.clone())
+        );
+
+        let (output, diagnostics) = parse_lenient(svg);
+        assert_eq!(output, "<svg>\n  <path d=\"M 0,0 L 10,10\"/>\n</svg>\n");
+        assert_eq!(
+            kinds(&diagnostics),
+            vec![(Severity::Error, ParseErrorKind::InvalidPathData(error))]
+        );
+        assert_eq!(diagnostics[0].error.span, 11..28);
+    }
+
+    #[test]
+    fn parse_limits_entity_expansion_across_the_document() {
+        let entity = "x".repeat(64 * 1024);
+        let within = format!(
+            r#"<!DOCTYPE svg [<!ENTITY big "{entity}">]><svg><text>&big;</text><text>&big;</text></svg>"#
+        );
+        assert_eq!(parse_lenient(&within).1, vec![]);
+
+        // each value is small, but together they expand to more than the limit
+        let texts = "<text>&big;&big;</text>".repeat(100);
+        let amplified = format!(r#"<!DOCTYPE svg [<!ENTITY big "{entity}">]><svg>{texts}</svg>"#);
+        let too_large = ParseErrorKind::ExpansionTooLarge(crate::entity::MAX_EXPANSION_BYTES);
+
+        assert_eq!(parse_err(&amplified).kind, too_large);
+
+        let (svg, diagnostics) = parse_lenient(&amplified);
+        assert!(!diagnostics.is_empty());
+        assert!(
+            diagnostics
+                .iter()
+                .all(|diagnostic| diagnostic.error.kind == too_large)
+        );
+        assert!(svg.len() < 2 * crate::entity::MAX_EXPANSION_BYTES);
+    }
+
+    #[test]
+    fn to_svg_escapes_values_set_through_the_api() {
+        let mut ast = parse_ok("<svg><text>x</text></svg>");
+        let svg_id = ast.children[0];
+        ast.set_attribute(
+            svg_id,
+            Attribute::Data("data-x".to_string(), r#"<"a" & 'b'>"#.to_string()),
+        );
+        let text_id = ast.find_by_type(ElementType::Text)[0];
+        let Some(Node::Element(text)) = ast.get_node(text_id) else {
+            panic!("expected <text> element");
+        };
+        let content_id = text.children[0];
+        *ast.get_node_mut(content_id).unwrap().as_text_mut().unwrap() = "1 < 2 & 3".to_string();
+
+        let output = ast.to_svg();
+        assert!(output.contains(r#"data-x="&lt;&quot;a&quot; &amp; 'b'>""#));
+        assert!(output.contains("1 &lt; 2 &amp; 3"));
+
+        let reparsed = parse_ok(&output);
+        assert_eq!(
+            reparsed.get_attribute(reparsed.children[0], "data-x"),
+            Some(&Attribute::Data(
+                "data-x".to_string(),
+                r#"<"a" & 'b'>"#.to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn parse_keeps_namespaced_elements_and_attributes() {
+        let source = r##"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" xmlns:inkscape="http://www.inkscape.org/namespaces/inkscape" xmlns:sodipodi="http://sodipodi.sourceforge.net/DTD/sodipodi-0.dtd" xml:space="preserve">
+<sodipodi:namedview id="base" inkscape:zoom="1.5"/>
+<g inkscape:label="Layer 1" inkscape:groupmode="layer">
+<use xlink:href="#base"/>
+</g>
+</svg>"##;
+        let ast = parse_ok(source);
+        let svg_id = ast.children[0];
+
+        assert_eq!(
+            ast.get_attribute(svg_id, "xml:space"),
+            Some(&Attribute::XmlSpace(XmlSpace::Preserve))
+        );
+        assert_eq!(
+            ast.get_attribute(svg_id, "xmlns:inkscape"),
+            Some(&Attribute::Foreign(
+                QualifiedName::new("xmlns:inkscape", Some(XMLNS_NAMESPACE)),
+                "http://www.inkscape.org/namespaces/inkscape".to_string()
+            ))
+        );
+
+        let named_view = QualifiedName::new(
+            "sodipodi:namedview",
+            Some("http://sodipodi.sourceforge.net/DTD/sodipodi-0.dtd"),
+        );
+        assert_eq!(
+            ast.find_by_type(ElementType::Foreign(Box::new(named_view)))
+                .len(),
+            1
+        );
+
+        let g_id = ast.find_by_type(ElementType::G)[0];
+        assert_eq!(
+            ast.get_attribute(g_id, "inkscape:label"),
+            Some(&Attribute::Foreign(
+                QualifiedName::new(
+                    "inkscape:label",
+                    Some("http://www.inkscape.org/namespaces/inkscape")
+                ),
+                "Layer 1".to_string()
+            ))
+        );
+
+        let use_id = ast.find_by_type(ElementType::Use)[0];
+        assert_eq!(
+            ast.get_attribute(use_id, "xlink:href"),
+            Some(&Attribute::XlinkHref("#base".to_string()))
+        );
+
+        let output = ast.to_svg();
+        for expected in [
+            r#"xmlns:sodipodi="http://sodipodi.sourceforge.net/DTD/sodipodi-0.dtd""#,
+            r#"xml:space="preserve""#,
+            r#"<sodipodi:namedview id="base" inkscape:zoom="1.5"/>"#,
+            r#"<g inkscape:label="Layer 1" inkscape:groupmode="layer">"#,
+            r##"<use xlink:href="#base"/>"##,
+        ] {
+            assert!(
+                output.contains(expected),
+                "{expected} missing from {output}"
+            );
+        }
+        assert_eq!(parse_ok(&output).to_svg(), output);
+    }
+
+    #[test]
+    fn parse_keeps_the_prefix_of_svg_elements() {
+        let ast = parse_ok(
+            r#"<svg:svg xmlns:svg="http://www.w3.org/2000/svg"><svg:rect id="r"/></svg:svg>"#,
+        );
+
+        let rect_id = ast.find_by_type(ElementType::Rect)[0];
+        let Some(Node::Element(rect)) = ast.get_node(rect_id) else {
+            panic!("expected <rect> element");
+        };
+        assert_eq!(rect.prefix.as_deref(), Some("svg"));
+
+        let output = ast.to_svg();
+        assert!(output.contains(r#"<svg:rect id="r"/>"#));
+        assert!(output.contains("</svg:svg>"));
+    }
+
+    #[test]
+    fn parse_rejects_undeclared_prefixes() {
+        let error = parse_err(r#"<svg><foo:bar/></svg>"#);
+        assert_eq!(error.kind, ParseErrorKind::UnboundPrefix("foo".to_string()));
+        assert_eq!(error.span, 6..13);
+
+        let error = parse_err(r#"<svg inkscape:label="x"/>"#);
+        assert_eq!(
+            error.kind,
+            ParseErrorKind::UnboundPrefix("inkscape".to_string())
+        );
+    }
+
+    #[test]
+    fn parse_scopes_namespaces_to_the_declaring_element() {
+        let ast =
+            parse_ok(r#"<svg xmlns:a="urn:one"><g xmlns:a="urn:two"><a:x/></g><a:x/><a:x/></svg>"#);
+        let foreign = |namespace| {
+            let name = QualifiedName::new("a:x", Some(namespace));
+            ast.find_by_type(ElementType::Foreign(Box::new(name))).len()
+        };
+        assert_eq!((foreign("urn:one"), foreign("urn:two")), (2, 1));
+
+        let error = parse_err(r#"<svg><g xmlns:a="urn:a"><a:x/></g><a:x/></svg>"#);
+        assert_eq!(error.kind, ParseErrorKind::UnboundPrefix("a".to_string()));
+        assert_eq!(error.span, 35..38);
+    }
+
+    #[test]
+    fn parse_lenient_keeps_undeclared_prefixes() {
+        let (svg, diagnostics) = parse_lenient(r#"<svg inkscape:label="x"><foo:bar/></svg>"#);
+
+        assert_eq!(
+            kinds(&diagnostics),
+            vec![
+                (
+                    Severity::Error,
+                    ParseErrorKind::UnboundPrefix("inkscape".to_string())
+                ),
+                (
+                    Severity::Error,
+                    ParseErrorKind::UnboundPrefix("foo".to_string())
+                ),
+            ]
+        );
+        assert!(svg.contains(r#"<svg inkscape:label="x">"#));
+        assert!(svg.contains("<foo:bar/>"));
+    }
+
+    #[test]
+    fn to_svg_keeps_length_units() {
+        let svg = r#"<rect x="1.5" y="-2" width="2" height="50%" stroke-width="0.5mm"/>"#;
+        let ast = Parser::new(crate::Lexer::new(Input::new(svg)))
+            .parse()
+            .unwrap();
+
+        assert_eq!(ast.to_svg(), format!("{svg}\n"));
+    }
+
+    #[test]
+    fn parse_reader_matches_parse() {
+        let mut svg = String::from("<?xml version=\"1.0\"?>\n<svg>\n");
+        for i in 0..5000 {
+            svg.push_str(&format!(
+                "  <g id=\"g{i}\"><!-- ž {i} --><text x='{i}'>žluť &amp; {i}</text></g>\n"
+            ));
+        }
+        svg.push_str("</svg>\n");
+
+        let expected = Parser::new(crate::Lexer::new(Input::new(&svg)))
+            .parse()
+            .unwrap();
+        let ast = Parser::parse_reader(svg.as_bytes()).unwrap();
+
+        assert_eq!(ast.to_svg(), expected.to_svg());
+        assert!(ast.spans.iter().eq(expected.spans.iter()));
+    }
+
+    #[test]
+    fn parse_skips_the_byte_order_mark() {
+        let svg = "\u{FEFF}<svg><rect/></svg>";
+        let ast = parse_ok(svg);
+
+        assert_eq!(ast.children.len(), 1);
+        assert_eq!(
+            ast.to_svg(),
+            Parser::parse_reader(svg.as_bytes()).unwrap().to_svg()
+        );
+        assert_eq!(ast.spans[ast.children[0]].span.start.offset, 3);
+
+        let lossless = Parser::new(crate::Lexer::new(Input::new(svg)))
+            .with_lossless(true)
+            .parse()
+            .unwrap();
+        assert_eq!(lossless.to_svg_lossless(), svg);
+    }
+
+    #[test]
+    fn parse_reader_reports_read_errors() {
+        struct Failing;
+
+        impl std::io::Read for Failing {
+            fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+                Err(std::io::Error::other("disk on fire"))
+            }
+        }
+
+        let error = Parser::parse_reader(Failing).unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::Io("disk on fire".to_string()));
+    }
+
+    #[test]
+    fn deep_nesting_does_not_overflow_the_stack() {
+        const DEPTH: usize = 10_000;
+        let nested = |depth| {
+            let rect = "<rect id=\"r\"/>";
+            format!("{}{rect}{}", "<g>".repeat(depth), "</g>".repeat(depth))
+        };
+        // on one line, without whitespace between the tags
+        let svg = nested(DEPTH);
+
+        let mut ast = Parser::new(crate::Lexer::new(Input::new(&svg)))
+            .with_max_depth(DEPTH + 1)
+            .with_lossless(true)
+            .parse()
+            .unwrap();
+
+        // far less stack than one frame per element
+        std::thread::Builder::new()
+            .stack_size(256 * 1024)
+            .spawn(move || {
+                let rect = ast.find_by_id("r").unwrap();
+                assert!(ast.validate().is_empty());
+                assert_eq!(
+                    ast.to_svg_with(&SerializeOptions::minified()),
+                    nested(DEPTH)
+                );
+                assert_eq!(ast.to_svg_lossless(), svg);
+
+                let root = ast.find_root().unwrap();
+                let child = ast.nodes[root].as_element().unwrap().children[0];
+                let clone = ast.clone_node(child);
+                ast.append_child(root, clone);
+                ast.remove_attribute(rect, "id");
+                assert_eq!(ast.find_by_type(ElementType::Rect).len(), 2);
+                assert_ne!(ast.find_by_id("r"), Some(rect));
+
+                let child = nested(DEPTH - 1);
+                let edited = child.replace(" id=\"r\"", "");
+                assert_eq!(
+                    ast.to_svg_with(&SerializeOptions::minified()),
+                    format!("<g>{edited}{child}</g>")
+                );
+                let edited = svg[3..svg.len() - 4].replace(" id=\"r\"", "");
+                assert_eq!(ast.to_svg_lossless(), format!("<g>{edited}{child}</g>"));
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+    }
+
+    #[test]
+    fn parse_limits_nesting_depth() {
+        let svg = "<svg><g><g><rect/></g></g><circle/></svg>";
+
+        let error = Parser::new(crate::Lexer::new(Input::new(svg)))
+            .with_max_depth(3)
+            .parse()
+            .unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::NestingTooDeep(3));
+        assert_eq!(error.span, 11..18);
+
+        let (ast, diagnostics) = Parser::new(crate::Lexer::new(Input::new(svg)))
+            .with_max_depth(3)
+            .parse_lenient();
+        assert_eq!(ast.to_svg(), "<svg>\n  <g>\n    <g/>\n  </g>\n</svg>\n");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].error.kind, ParseErrorKind::NestingTooDeep(3));
+    }
 }
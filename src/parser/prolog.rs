@@ -0,0 +1,195 @@
+//! Parsing of the XML declaration, DOCTYPE and processing instructions.
+//!
+//! The lexer emits each of these as a single token; the functions here split the
+//! token into the fields of the corresponding AST node.
+
+use crate::parser::ast::{DoctypeNode, ProcessingInstructionNode, XmlDeclarationNode};
+
+/// Parses `<?xml version="1.0" encoding="UTF-8" standalone="no"?>`.
+///
+/// Returns `None` if `version` is missing or a pseudo-attribute is malformed or unknown.
+pub(crate) fn xml_declaration(source: &str) -> Option<XmlDeclarationNode> {
+    let body = source.strip_prefix("<?xml")?.strip_suffix("?>")?;
+
+    let mut version = None;
+    let mut encoding = None;
+    let mut standalone = None;
+
+    for (name, value) in pseudo_attributes(body)? {
+        let slot = match name {
+            "version" => &mut version,
+            "encoding" => &mut encoding,
+            "standalone" => &mut standalone,
+            _ => return None,
+        };
+        *slot = Some(value.to_string());
+    }
+
+    Some(XmlDeclarationNode {
+        version: version?,
+        encoding,
+        standalone,
+        parent: None,
+    })
+}
+
+/// Parses `<!DOCTYPE name external-id [internal subset]>`.
+///
+/// Returns `None` if the root element name is missing.
+pub(crate) fn doctype(source: &str) -> Option<DoctypeNode> {
+    let body = source.strip_prefix("<!DOCTYPE")?.strip_suffix('>')?;
+    if !body.starts_with(|c: char| c.is_ascii_whitespace()) {
+        return None;
+    }
+
+    let body = body.trim_start();
+    let name_end = body
+        .find(|c: char| c.is_ascii_whitespace() || c == '[')
+        .unwrap_or(body.len());
+    let (name, rest) = body.split_at(name_end);
+    if name.is_empty() {
+        return None;
+    }
+
+    let (external_id, internal_subset) = match find_unquoted(rest, '[') {
+        Some(open) => {
+            let close = rest.rfind(']')?;
+            if close < open || !rest[close + 1..].trim().is_empty() {
+                return None;
+            }
+            (&rest[..open], Some(rest[open + 1..close].to_string()))
+        }
+        None => (rest, None),
+    };
+
+    let external_id = external_id.trim();
+
+    Some(DoctypeNode {
+        name: name.to_string(),
+        external_id: (!external_id.is_empty()).then(|| external_id.to_string()),
+        internal_subset,
+        parent: None,
+    })
+}
+
+/// Parses `<?target data?>`.
+///
+/// Returns `None` if the target is missing.
+pub(crate) fn processing_instruction(source: &str) -> Option<ProcessingInstructionNode> {
+    let body = source.strip_prefix("<?")?.strip_suffix("?>")?;
+    let target_end = body
+        .find(|c: char| c.is_ascii_whitespace())
+        .unwrap_or(body.len());
+    let (target, data) = body.split_at(target_end);
+    if target.is_empty() {
+        return None;
+    }
+
+    Some(ProcessingInstructionNode {
+        target: target.to_string(),
+        data: data.trim_start().to_string(),
+        parent: None,
+    })
+}
+
+/// Splits `name="value" name='value'` pairs, or returns `None` if they are malformed.
+fn pseudo_attributes(mut source: &str) -> Option<Vec<(&str, &str)>> {
+    let mut attributes = vec![];
+
+    loop {
+        source = source.trim_start();
+        if source.is_empty() {
+            return Some(attributes);
+        }
+
+        let (name, rest) = source.split_once('=')?;
+        let rest = rest.trim_start();
+        let quote = rest.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+        let rest = &rest[1..];
+        let end = rest.find(quote)?;
+
+        attributes.push((name.trim(), &rest[..end]));
+        source = &rest[end + 1..];
+    }
+}
+
+/// Returns the byte index of the first `needle` outside of quoted strings.
+fn find_unquoted(source: &str, needle: char) -> Option<usize> {
+    let mut quote = None;
+
+    for (i, c) in source.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == needle => return Some(i),
+            None => {}
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn xml_declaration_should_parse_pseudo_attributes() {
+        let declaration =
+            xml_declaration(r#"<?xml version="1.0" encoding='UTF-8' standalone="no"?>"#).unwrap();
+
+        assert_eq!(declaration.version, "1.0");
+        assert_eq!(declaration.encoding.as_deref(), Some("UTF-8"));
+        assert_eq!(declaration.standalone.as_deref(), Some("no"));
+    }
+
+    #[test]
+    fn xml_declaration_should_require_version() {
+        assert!(xml_declaration(r#"<?xml encoding="UTF-8"?>"#).is_none());
+        assert!(xml_declaration(r#"<?xml version="1.0" bogus="1"?>"#).is_none());
+        assert!(xml_declaration(r#"<?xml version=1.0?>"#).is_none());
+    }
+
+    #[test]
+    fn doctype_should_split_external_id_and_internal_subset() {
+        let doctype = doctype(
+            r#"<!DOCTYPE svg PUBLIC "-//W3C//DTD SVG 1.1//EN" "http://www.w3.org/Graphics/SVG/1.1/DTD/svg11.dtd" [
+	<!ENTITY ns_svg "http://www.w3.org/2000/svg">
+]>"#,
+        )
+        .unwrap();
+
+        assert_eq!(doctype.name, "svg");
+        assert_eq!(
+            doctype.external_id.as_deref(),
+            Some(
+                r#"PUBLIC "-//W3C//DTD SVG 1.1//EN" "http://www.w3.org/Graphics/SVG/1.1/DTD/svg11.dtd""#
+            )
+        );
+        assert_eq!(
+            doctype.internal_subset.as_deref(),
+            Some("\n\t<!ENTITY ns_svg \"http://www.w3.org/2000/svg\">\n")
+        );
+    }
+
+    #[test]
+    fn doctype_should_allow_name_only() {
+        let doctype = doctype("<!DOCTYPE svg>").unwrap();
+
+        assert_eq!(doctype.name, "svg");
+        assert_eq!(doctype.external_id, None);
+        assert_eq!(doctype.internal_subset, None);
+    }
+
+    #[test]
+    fn processing_instruction_should_split_target_and_data() {
+        let instruction =
+            processing_instruction(r#"<?xml-stylesheet href="style.css" type="text/css"?>"#)
+                .unwrap();
+
+        assert_eq!(instruction.target, "xml-stylesheet");
+        assert_eq!(instruction.data, r#"href="style.css" type="text/css""#);
+        assert!(processing_instruction("<? ?>").is_none());
+    }
+}
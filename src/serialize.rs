@@ -0,0 +1,803 @@
+//! Writing an [`AST`] back to SVG text.
+//!
+//! [`AST::to_svg`] pretty-prints with the default [`SerializeOptions`];
+//! [`AST::to_svg_with`] takes options for the indentation, attribute order,
+//! quotes and number precision. [`AST::write_to`] and [`AST::write_to_with`]
+//! stream the same output to an [`io::Write`] instead. Whitespace in text
+//! content and where `xml:space="preserve"` applies is written as it is,
+//! whatever the options.
+
+use std::{
+    cmp::Ordering,
+    fmt,
+    io::{self, BufWriter, Write as _},
+};
+
+use crate::{
+    Element,
+    element::attributes::{Attribute, Quote},
+    encoding::is_utf8_label,
+    entity::escape_text,
+    namespace::XMLNS_NAMESPACE,
+    parser::ast::{AST, Node, NodeId},
+};
+
+/// How the serializer formats its output.
+///
+/// The default reproduces [`AST::to_svg`]: two-space indentation, attributes
+/// in source order with the quotes they were written with, and the XML
+/// declaration only if the document had one.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SerializeOptions {
+    /// The indentation of each nesting level.
+    pub indent: Indent,
+    /// Writes everything on one line, without indentation, and path data in
+    /// its shortest form.
+    pub minify: bool,
+    pub attribute_order: AttributeOrder,
+    /// How elements without children are closed.
+    pub self_closing: SelfClosing,
+    /// The quote of every attribute value, or `None` for the quote it was
+    /// written with.
+    pub quote: Option<Quote>,
+    pub xml_declaration: XmlDeclarationMode,
+    /// The maximum number of decimal places of numbers in numeric attributes,
+    /// such as coordinates and path data, or `None` to write them as they are.
+    pub precision: Option<usize>,
+}
+
+impl SerializeOptions {
+    /// Options for the smallest output: one line, with no XML declaration.
+    pub fn minified() -> Self {
+        Self {
+            minify: true,
+            xml_declaration: XmlDeclarationMode::Omit,
+            ..Self::default()
+        }
+    }
+}
+
+/// The indentation of one nesting level.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Indent {
+    Spaces(usize),
+    Tabs,
+    /// Every node on its own line, without indentation.
+    None,
+}
+
+impl Default for Indent {
+    fn default() -> Self {
+        Indent::Spaces(2)
+    }
+}
+
+/// The order attributes are written in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AttributeOrder {
+    /// As they were parsed or added.
+    #[default]
+    Source,
+    /// Sorted by name.
+    Alphabetical,
+    /// Namespace declarations, `id`, `class` and `style` first, then
+    /// coordinates and sizes, then the rest sorted by name.
+    Canonical,
+}
+
+/// How an element without children is written.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SelfClosing {
+    /// `<rect/>`
+    #[default]
+    Compact,
+    /// `<rect />`
+    Spaced,
+    /// `<rect></rect>`
+    Never,
+}
+
+/// Whether the output starts with an XML declaration.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum XmlDeclarationMode {
+    /// Only if the document has one.
+    #[default]
+    Keep,
+    /// Always, adding `<?xml version="1.0" encoding="UTF-8"?>` if the document
+    /// has none.
+    Always,
+    /// Never, even if the document has one.
+    Omit,
+}
+
+impl AST {
+    /// Serializes the entire AST to an SVG string.
+    pub fn to_svg(&self) -> String {
+        self.to_svg_with(&SerializeOptions::default())
+    }
+
+    /// Serializes the entire AST to an SVG string formatted by `options`.
+    pub fn to_svg_with(&self, options: &SerializeOptions) -> String {
+        let mut s = String::new();
+        Serializer::new(self, options, &mut s)
+            .write_document()
+            .unwrap();
+        s
+    }
+
+    /// Serializes the entire AST to `writer`, like [`to_svg`](AST::to_svg).
+    ///
+    /// The output is buffered, so `writer` does not need to be. Nothing but the
+    /// buffer is held in memory.
+    pub fn write_to<W: io::Write>(&self, writer: W) -> io::Result<()> {
+        self.write_to_with(writer, &SerializeOptions::default())
+    }
+
+    /// Serializes the entire AST to `writer`, formatted by `options`.
+    pub fn write_to_with<W: io::Write>(
+        &self,
+        writer: W,
+        options: &SerializeOptions,
+    ) -> io::Result<()> {
+        let mut output = IoWriter {
+            inner: BufWriter::new(writer),
+            error: None,
+        };
+
+        let result = Serializer::new(self, options, &mut output).write_document();
+        match (result, output.error) {
+            (Ok(()), _) => output.inner.flush(),
+            (Err(_), Some(error)) => Err(error),
+            (Err(_), None) => Err(io::Error::other("formatting failed")),
+        }
+    }
+}
+
+/// Adapts an [`io::Write`] to the [`fmt::Write`] of [`Serializer`], keeping
+/// the I/O error that `fmt::Error` cannot carry.
+struct IoWriter<W: io::Write> {
+    inner: BufWriter<W>,
+    error: Option<io::Error>,
+}
+
+impl<W: io::Write> fmt::Write for IoWriter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.inner.write_all(s.as_bytes()).map_err(|error| {
+            self.error = Some(error);
+            fmt::Error
+        })
+    }
+}
+
+/// Writes nodes of an [`AST`] as formatted by [`SerializeOptions`].
+pub(crate) struct Serializer<'a, W> {
+    ast: &'a AST,
+    options: &'a SerializeOptions,
+    f: &'a mut W,
+}
+
+impl<'a, W: fmt::Write> Serializer<'a, W> {
+    pub(crate) fn new(ast: &'a AST, options: &'a SerializeOptions, f: &'a mut W) -> Self {
+        Self { ast, options, f }
+    }
+
+    /// Writes the root-level nodes.
+    pub(crate) fn write_document(&mut self) -> fmt::Result {
+        let ast = self.ast;
+        let has_declaration = ast
+            .children
+            .iter()
+            .any(|&id| matches!(ast.nodes.get(id), Some(Node::XmlDeclaration(_))));
+
+        if self.options.xml_declaration == XmlDeclarationMode::Always && !has_declaration {
+            write!(self.f, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+            self.newline()?;
+        }
+
+        for &id in &ast.children {
+            if let Some(node) = ast.nodes.get(id) {
+                self.write_node(Some(id), node, 0)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Writes a node at the nesting level `depth`, on lines of its own unless
+    /// the output is minified.
+    ///
+    /// `id` is where the node is in the tree, for the source information and
+    /// the ancestors of the node.
+    pub(crate) fn write_node(
+        &mut self,
+        id: Option<NodeId>,
+        node: &Node,
+        depth: usize,
+    ) -> fmt::Result {
+        self.write_steps(Step::Node {
+            id,
+            node,
+            depth: Some(depth),
+        })
+    }
+
+    /// Writes an element and its children at the nesting level `depth`, without
+    /// a line break after it.
+    ///
+    /// Content in which whitespace is significant is written as it is, without
+    /// indentation.
+    pub(crate) fn write_element(
+        &mut self,
+        id: Option<NodeId>,
+        element: &Element,
+        depth: usize,
+    ) -> fmt::Result {
+        self.write_steps(Step::Element {
+            id,
+            element,
+            depth: Some(depth),
+            newline: false,
+        })
+    }
+
+    /// Writes a node without adding any whitespace.
+    pub(crate) fn write_inline(&mut self, id: Option<NodeId>, node: &Node) -> fmt::Result {
+        self.write_steps(Step::Node {
+            id,
+            node,
+            depth: None,
+        })
+    }
+
+    /// Writes `first` and everything it expands to.
+    ///
+    /// Children are pushed onto an explicit stack instead of written
+    /// recursively, so any nesting depth fits in the call stack.
+    fn write_steps<'n>(&mut self, first: Step<'n>) -> fmt::Result
+    where
+        'a: 'n,
+    {
+        let ast = self.ast;
+        let mut steps = vec![first];
+        let mut is_outermost = true;
+
+        while let Some(step) = steps.pop() {
+            match step {
+                Step::Node { id, node, depth } => self.write_leaf(id, node, depth, &mut steps)?,
+                Step::Element {
+                    id,
+                    element,
+                    depth,
+                    newline,
+                } => {
+                    if let Some(depth) = depth {
+                        self.indent(depth)?;
+                    }
+                    self.write_start_tag(id, element)?;
+
+                    if element.children.is_empty() {
+                        self.write_empty_end(element)?;
+                        if newline {
+                            self.newline()?;
+                        }
+                        continue;
+                    }
+                    write!(self.f, ">")?;
+
+                    // any other element written with a depth has a parent in
+                    // which whitespace is not significant
+                    let is_significant = match id.filter(|_| is_outermost) {
+                        Some(id) => ast.is_whitespace_significant(id),
+                        None => {
+                            element.element_type.is_text_content()
+                                || element.preserves_whitespace() == Some(true)
+                        }
+                    };
+                    is_outermost = false;
+
+                    // children of significant content are written as they are
+                    let child_depth = depth.filter(|_| !is_significant);
+                    if child_depth.is_some() {
+                        self.newline()?;
+                    }
+                    steps.push(Step::End {
+                        element,
+                        depth: child_depth,
+                        newline,
+                    });
+                    steps.extend(element.children.iter().rev().filter_map(|&child_id| {
+                        ast.nodes.get(child_id).map(|child| Step::Node {
+                            id: Some(child_id),
+                            node: child,
+                            depth: child_depth.map(|depth| depth + 1),
+                        })
+                    }));
+                }
+                Step::End {
+                    element,
+                    depth,
+                    newline,
+                } => {
+                    if let Some(depth) = depth {
+                        self.indent(depth)?;
+                    }
+                    self.write_end_tag(element)?;
+                    if newline {
+                        self.newline()?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Writes a node other than an element, or pushes the step of an element.
+    ///
+    /// With a `depth`, the node is written on lines of its own.
+    fn write_leaf<'n>(
+        &mut self,
+        id: Option<NodeId>,
+        node: &'n Node,
+        depth: Option<usize>,
+        steps: &mut Vec<Step<'n>>,
+    ) -> fmt::Result {
+        if let Node::Element(element) = node {
+            steps.push(Step::Element {
+                id,
+                element,
+                depth,
+                newline: depth.is_some(),
+            });
+            return Ok(());
+        }
+
+        let Some(depth) = depth else {
+            return self.write_leaf_inline(node);
+        };
+        match node {
+            Node::XmlDeclaration(_) if self.options.xml_declaration == XmlDeclarationMode::Omit => {
+                return Ok(());
+            }
+            Node::Text(text_node) => {
+                // outside of text content, surrounding whitespace is only formatting
+                let content = text_node.content.trim_ascii();
+                if content.is_empty() {
+                    return Ok(());
+                }
+
+                self.indent(depth)?;
+                write!(self.f, "{}", escape_text(content))?;
+            }
+            node => {
+                self.indent(depth)?;
+                self.write_leaf_inline(node)?;
+            }
+        }
+
+        self.newline()
+    }
+
+    fn write_leaf_inline(&mut self, node: &Node) -> fmt::Result {
+        match node {
+            Node::Text(text_node) => write!(self.f, "{}", escape_text(&text_node.content)),
+            Node::Element(_) => Ok(()),
+            Node::Comment(comment_node) => write!(self.f, "<!--{}-->", comment_node.content),
+            Node::CData(cdata_node) => write!(self.f, "<![CDATA[{}]]>", cdata_node.content),
+            Node::XmlDeclaration(declaration) => {
+                write!(self.f, "<?xml version=\"{}\"", declaration.version)?;
+                if let Some(encoding) = &declaration.encoding {
+                    // the output is UTF-8, whatever the source was transcoded from
+                    let encoding = match is_utf8_label(encoding) {
+                        true => encoding,
+                        false => "UTF-8",
+                    };
+                    write!(self.f, " encoding=\"{}\"", encoding)?;
+                }
+                if let Some(standalone) = &declaration.standalone {
+                    write!(self.f, " standalone=\"{}\"", standalone)?;
+                }
+                write!(self.f, "?>")
+            }
+            Node::Doctype(doctype) => {
+                write!(self.f, "<!DOCTYPE {}", doctype.name)?;
+                if let Some(external_id) = &doctype.external_id {
+                    write!(self.f, " {}", external_id)?;
+                }
+                if let Some(internal_subset) = &doctype.internal_subset {
+                    write!(self.f, " [{}]", internal_subset)?;
+                }
+                write!(self.f, ">")
+            }
+            Node::ProcessingInstruction(instruction) => {
+                write!(self.f, "<?{}", instruction.target)?;
+                if !instruction.data.is_empty() {
+                    write!(self.f, " {}", instruction.data)?;
+                }
+                write!(self.f, "?>")
+            }
+        }
+    }
+
+    /// Writes `<name` and the attributes, keeping the quotes recorded for `id`
+    /// unless the options set one.
+    pub(crate) fn write_start_tag(&mut self, id: Option<NodeId>, element: &Element) -> fmt::Result {
+        let source = id.and_then(|id| self.ast.spans.get(id));
+
+        write!(self.f, "<")?;
+        element.write_name(self.f)?;
+
+        let mut attributes: Vec<_> = element.attributes.iter().collect();
+        match self.options.attribute_order {
+            AttributeOrder::Source => {}
+            AttributeOrder::Alphabetical => attributes.sort_by(|a, b| a.name().cmp(b.name())),
+            AttributeOrder::Canonical => attributes.sort_by(|a, b| canonical_order(a, b)),
+        }
+
+        for attribute in attributes {
+            let quote = self.options.quote.unwrap_or_else(|| {
+                source
+                    .and_then(|s| s.attributes.iter().find(|a| a.name == attribute.name()))
+                    .map(|a| a.quote)
+                    .unwrap_or_default()
+            });
+
+            write!(self.f, " ")?;
+            match attribute {
+                Attribute::D(path) | Attribute::Path(path) if self.options.minify => {
+                    let quote = quote.as_char();
+                    let data = path.encode(self.options.precision);
+                    write!(self.f, "{}={}{}{}", attribute.name(), quote, data, quote)?;
+                }
+                _ => attribute.write_svg_rounded(self.f, quote, self.options.precision)?,
+            }
+        }
+
+        Ok(())
+    }
+
+    fn write_empty_end(&mut self, element: &Element) -> fmt::Result {
+        match self.options.self_closing {
+            SelfClosing::Compact => write!(self.f, "/>"),
+            SelfClosing::Spaced => write!(self.f, " />"),
+            SelfClosing::Never => {
+                write!(self.f, ">")?;
+                self.write_end_tag(element)
+            }
+        }
+    }
+
+    fn write_end_tag(&mut self, element: &Element) -> fmt::Result {
+        write!(self.f, "</")?;
+        element.write_name(self.f)?;
+        write!(self.f, ">")
+    }
+
+    fn indent(&mut self, depth: usize) -> fmt::Result {
+        if self.options.minify {
+            return Ok(());
+        }
+
+        let (unit, count) = match self.options.indent {
+            Indent::Spaces(n) => (" ", n * depth),
+            Indent::Tabs => ("\t", depth),
+            Indent::None => ("", 0),
+        };
+        for _ in 0..count {
+            self.f.write_str(unit)?;
+        }
+
+        Ok(())
+    }
+
+    fn newline(&mut self) -> fmt::Result {
+        match self.options.minify {
+            true => Ok(()),
+            false => writeln!(self.f),
+        }
+    }
+}
+
+/// A pending piece of output of [`Serializer`].
+///
+/// A `depth` puts the piece on lines of its own at that nesting level; without
+/// one, it is written without adding whitespace.
+enum Step<'n> {
+    Node {
+        id: Option<NodeId>,
+        node: &'n Node,
+        depth: Option<usize>,
+    },
+    /// An element with its children, followed by a line break if `newline`.
+    Element {
+        id: Option<NodeId>,
+        element: &'n Element,
+        depth: Option<usize>,
+        newline: bool,
+    },
+    /// The end tag of an element with children.
+    End {
+        element: &'n Element,
+        depth: Option<usize>,
+        newline: bool,
+    },
+}
+
+/// Compares attributes for [`AttributeOrder::Canonical`].
+fn canonical_order(a: &Attribute, b: &Attribute) -> Ordering {
+    /// Attributes written first, in this order.
+    const FIRST: &[&str] = &[
+        "id",
+        "class",
+        "style",
+        "x",
+        "y",
+        "x1",
+        "y1",
+        "x2",
+        "y2",
+        "cx",
+        "cy",
+        "r",
+        "rx",
+        "ry",
+        "width",
+        "height",
+        "d",
+        "points",
+        "viewBox",
+        "transform",
+    ];
+
+    let rank = |attribute: &Attribute| match attribute {
+        Attribute::Xmlns(_) => 0,
+        Attribute::Foreign(name, _) if name.namespace.as_deref() == Some(XMLNS_NAMESPACE) => 1,
+        attribute => FIRST
+            .iter()
+            .position(|&name| name == attribute.name())
+            .map_or(FIRST.len() + 2, |position| position + 2),
+    };
+
+    rank(a).cmp(&rank(b)).then_with(|| a.name().cmp(b.name()))
+}
+
+/// Rounds the numbers in `value` with a fractional part to at most
+/// `precision` decimal places, and removes their trailing zeros.
+///
+/// Numbers are separated again where rounding drops the decimal point of a
+/// number followed by one like `.5`, as in path data.
+pub(crate) fn round_numbers(value: &str, precision: usize) -> String {
+    let bytes = value.as_bytes();
+    let mut output = String::with_capacity(value.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let start = i;
+        if matches!(bytes[i], b'+' | b'-') {
+            i += 1;
+        }
+        let digits = i;
+        while i < bytes.len() && bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+        let has_fraction = i + 1 < bytes.len() && bytes[i] == b'.' && bytes[i + 1].is_ascii_digit();
+        if has_fraction {
+            i += 1;
+            while i < bytes.len() && bytes[i].is_ascii_digit() {
+                i += 1;
+            }
+        }
+
+        // not a number with a fractional part, or one in an exponent
+        let is_exponent = i < bytes.len() && matches!(bytes[i], b'e' | b'E');
+        if !has_fraction || is_exponent {
+            if i == digits {
+                // a sign or any other character
+                let c = value[start..].chars().next().unwrap_or_default();
+                output.push(c);
+                i = start + c.len_utf8();
+            } else {
+                output.push_str(&value[start..i]);
+            }
+            continue;
+        }
+
+        let number: f64 = value[start..i].parse().unwrap_or_default();
+        let mut rounded = format!("{:.*}", precision, number);
+        if rounded.contains('.') {
+            rounded.truncate(rounded.trim_end_matches('0').trim_end_matches('.').len());
+        }
+        if rounded == "-0" {
+            rounded = "0".to_string();
+        }
+        // `.5` stays `.5`
+        if digits == i || bytes[digits] == b'.' {
+            if let Some(fraction) = rounded.strip_prefix("0.") {
+                rounded = format!(".{fraction}");
+            } else if let Some(fraction) = rounded.strip_prefix("-0.") {
+                rounded = format!("-.{fraction}");
+            }
+        }
+
+        // a number written right after another one, like `.5` in `1.2.5`, is
+        // only apart from it while that one has a decimal point
+        let previous = &output[output
+            .trim_end_matches(|c: char| c.is_ascii_digit() || c == '.')
+            .len()..];
+        let is_glued = match rounded.as_bytes()[0] {
+            b'.' => !previous.is_empty() && !previous.contains('.'),
+            b'0'..=b'9' => !previous.is_empty(),
+            _ => false,
+        };
+        if is_glued {
+            output.push(' ');
+        }
+        output.push_str(&rounded);
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Parser,
+        lexer::{Input, Lexer},
+    };
+
+    fn parse(source: &str) -> AST {
+        Parser::new(Lexer::new(Input::new(source))).parse().unwrap()
+    }
+
+    const SOURCE: &str = r#"<svg class="c" xmlns="http://www.w3.org/2000/svg" id='a'><g><rect/><text> a <tspan>b</tspan></text></g></svg>"#;
+
+    #[test]
+    fn to_svg_with_should_indent_and_minify() {
+        let ast = parse(SOURCE);
+
+        let tabs = SerializeOptions {
+            indent: Indent::Tabs,
+            ..Default::default()
+        };
+        assert_eq!(
+            ast.to_svg_with(&tabs),
+            "<svg class=\"c\" xmlns=\"http://www.w3.org/2000/svg\" id='a'>\n\t<g>\n\t\t<rect/>\n\t\t<text> a <tspan>b</tspan></text>\n\t</g>\n</svg>\n"
+        );
+
+        assert_eq!(ast.to_svg_with(&SerializeOptions::minified()), SOURCE);
+    }
+
+    #[test]
+    fn to_svg_with_should_order_and_quote_attributes() {
+        let ast = parse(SOURCE);
+        let mut options = SerializeOptions {
+            minify: true,
+            quote: Some(Quote::Double),
+            attribute_order: AttributeOrder::Alphabetical,
+            self_closing: SelfClosing::Never,
+            ..Default::default()
+        };
+        assert!(ast.to_svg_with(&options).starts_with(
+            r#"<svg class="c" id="a" xmlns="http://www.w3.org/2000/svg"><g><rect></rect>"#
+        ));
+
+        options.attribute_order = AttributeOrder::Canonical;
+        options.self_closing = SelfClosing::Spaced;
+        assert!(ast.to_svg_with(&options).starts_with(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" id="a" class="c"><g><rect />"#
+        ));
+    }
+
+    #[test]
+    fn to_svg_with_should_add_or_omit_the_xml_declaration() {
+        let mut options = SerializeOptions {
+            minify: true,
+            xml_declaration: XmlDeclarationMode::Always,
+            ..Default::default()
+        };
+        let expected = r#"<?xml version="1.0" encoding="UTF-8"?><svg/>"#;
+        assert_eq!(parse("<svg/>").to_svg_with(&options), expected);
+        assert_eq!(parse(expected).to_svg_with(&options), expected);
+
+        options.xml_declaration = XmlDeclarationMode::Omit;
+        assert_eq!(parse(expected).to_svg_with(&options), "<svg/>");
+    }
+
+    #[test]
+    fn to_svg_should_declare_utf8_for_a_transcoded_source() {
+        let source = crate::encoding::decode(
+            b"<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?><text>caf\xE9</text>",
+        )
+        .unwrap();
+        let ast = Parser::new(Lexer::new(Input::new(&source)))
+            .with_lossless(true)
+            .parse()
+            .unwrap();
+
+        let expected = r#"<?xml version="1.0" encoding="UTF-8"?><text>café</text>"#;
+        let options = SerializeOptions {
+            minify: true,
+            ..Default::default()
+        };
+        assert_eq!(ast.to_svg_with(&options), expected);
+        assert_eq!(ast.to_svg_lossless(), expected);
+
+        let output = ast.to_svg();
+        assert_eq!(crate::encoding::decode(output.as_bytes()).unwrap(), output);
+    }
+
+    #[test]
+    fn to_svg_with_should_round_numeric_attributes() {
+        let ast = parse(r#"<svg><rect id="a1.234" width="1.23456" x="-0.0001"/></svg>"#);
+        let options = SerializeOptions {
+            minify: true,
+            precision: Some(2),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            ast.to_svg_with(&options),
+            r#"<svg><rect id="a1.234" width="1.23" x="0"/></svg>"#
+        );
+    }
+
+    #[test]
+    fn to_svg_with_should_shorten_path_data_when_minified() {
+        let ast = parse(r#"<svg><path d="M 30,1 L 70,1 L 99.004,30 Z"/></svg>"#);
+        let options = SerializeOptions {
+            precision: Some(2),
+            ..SerializeOptions::minified()
+        };
+
+        assert_eq!(
+            ast.to_svg_with(&options),
+            r#"<svg><path d="M30 1h40l29 29z"/></svg>"#
+        );
+    }
+
+    #[test]
+    fn round_numbers_should_keep_numbers_apart() {
+        assert_eq!(
+            round_numbers("M1.25.5L-3.999,2e-5 10", 1),
+            "M1.2.5L-4,2e-5 10"
+        );
+        assert_eq!(round_numbers("1.04.5", 1), "1 .5");
+        assert_eq!(
+            round_numbers("translate(1.50 -2.000)", 3),
+            "translate(1.5 -2)"
+        );
+    }
+
+    #[test]
+    fn write_to_should_match_to_svg() {
+        let ast = parse(SOURCE);
+
+        let mut output = Vec::new();
+        ast.write_to(&mut output).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), ast.to_svg());
+
+        let mut output = Vec::new();
+        let options = SerializeOptions::minified();
+        ast.write_to_with(&mut output, &options).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), SOURCE);
+    }
+
+    #[test]
+    fn write_to_should_return_write_errors() {
+        struct Full;
+
+        impl io::Write for Full {
+            fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+                Err(io::Error::new(io::ErrorKind::StorageFull, "no space left"))
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let error = parse(SOURCE).write_to(Full).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::StorageFull);
+    }
+}
@@ -0,0 +1,222 @@
+//! Source locations for nodes and attributes.
+//!
+//! The parser records a [`Span`] for every node and attribute it creates, so
+//! diagnostics and validation results can point at the exact spot in the file.
+
+use std::ops::Range;
+
+use crate::element::attributes::Quote;
+
+/// A position in the source.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Location {
+    /// Byte offset from the start of the source.
+    pub offset: usize,
+    /// Line number, starting at 1.
+    pub line: usize,
+    /// Column number in characters, starting at 1.
+    pub column: usize,
+}
+
+/// A range in the source, from `start` (inclusive) to `end` (exclusive).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Span {
+    pub start: Location,
+    pub end: Location,
+}
+
+impl Span {
+    /// Returns the byte range covered by this span.
+    pub fn range(&self) -> Range<usize> {
+        self.start.offset..self.end.offset
+    }
+}
+
+/// The source location of an attribute.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AttributeSpan {
+    /// The attribute name as written in the source.
+    pub name: String,
+    /// From the start of the name to the end of the value, including quotes.
+    pub span: Span,
+    /// The quote character that delimited the value, kept for serialization.
+    pub quote: Quote,
+}
+
+/// The source location of a node and its attributes.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NodeSpan {
+    /// From the opening `<` to the end of the closing tag, or the text itself.
+    pub span: Span,
+    /// Attributes in source order. Empty for anything but elements.
+    pub attributes: Vec<AttributeSpan>,
+    /// The closing tag of an element. `None` for an empty-element tag, an
+    /// element closed in lenient mode, or anything but an element.
+    pub end_tag: Option<Span>,
+}
+
+/// Maps byte offsets to line and column numbers.
+#[derive(Debug)]
+pub struct LineIndex<'a> {
+    bytes: &'a [u8],
+    /// Byte offset where each line starts.
+    line_starts: Vec<usize>,
+    /// The last two locations looked up through [`Locate`], where the next
+    /// lookup on the same line continues from.
+    ///
+    /// Two, because the spans of an element are looked up before the spans of
+    /// its attributes.
+    cursors: [Location; 2],
+}
+
+impl<'a> LineIndex<'a> {
+    /// Creates a line index for the given source.
+    pub fn new(bytes: &'a [u8]) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(memchr::memchr_iter(b'\n', bytes).map(|i| i + 1))
+            .collect();
+        let start = Location {
+            offset: 0,
+            line: 1,
+            column: 1,
+        };
+
+        Self {
+            bytes,
+            line_starts,
+            cursors: [start; 2],
+        }
+    }
+
+    /// Returns the location of the given byte offset.
+    ///
+    /// Columns count UTF-8 characters rather than bytes.
+    pub fn location(&self, offset: usize) -> Location {
+        self.location_after(offset, &[])
+    }
+
+    /// Returns the span covering the given byte range.
+    pub fn span(&self, range: Range<usize>) -> Span {
+        Span {
+            start: self.location(range.start),
+            end: self.location(range.end),
+        }
+    }
+
+    /// Returns the location of `offset`, counting its column from the last of
+    /// `cursors` before it on its line, or from the start of the line.
+    fn location_after(&self, offset: usize, cursors: &[Location]) -> Location {
+        let offset = offset.min(self.bytes.len());
+        let line = self.line_starts.partition_point(|&start| start <= offset);
+        let line_start = self.line_starts[line - 1];
+
+        let (start, column) = cursors
+            .iter()
+            .filter(|cursor| (line_start..=offset).contains(&cursor.offset))
+            .max()
+            .map_or((line_start, 1), |cursor| (cursor.offset, cursor.column));
+        let characters = self.bytes[start..offset]
+            .iter()
+            .filter(|&&byte| byte & 0xC0 != 0x80)
+            .count();
+
+        Location {
+            offset,
+            line,
+            column: column + characters,
+        }
+    }
+}
+
+/// Maps byte offsets to [`Location`]s while a tree is built.
+pub(crate) trait Locate {
+    fn location(&mut self, offset: usize) -> Location;
+
+    fn span(&mut self, range: Range<usize>) -> Span {
+        Span {
+            start: self.location(range.start),
+            end: self.location(range.end),
+        }
+    }
+}
+
+impl Locate for LineIndex<'_> {
+    /// Returns the location of an offset, in time proportional to the distance
+    /// from the last locations looked up when it is after them, so that a long
+    /// line is not scanned again for every node on it.
+    fn location(&mut self, offset: usize) -> Location {
+        let location = self.location_after(offset, &self.cursors);
+
+        if location != self.cursors[0] {
+            self.cursors = [location, self.cursors[0]];
+        }
+
+        location
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn location_should_count_lines_and_columns() {
+        let index = LineIndex::new(b"<svg>\n  <g/>\n</svg>");
+
+        assert_eq!(
+            index.location(0),
+            Location {
+                offset: 0,
+                line: 1,
+                column: 1
+            }
+        );
+        assert_eq!(
+            index.location(8),
+            Location {
+                offset: 8,
+                line: 2,
+                column: 3
+            }
+        );
+        assert_eq!(
+            index.location(13),
+            Location {
+                offset: 13,
+                line: 3,
+                column: 1
+            }
+        );
+    }
+
+    #[test]
+    fn location_should_count_characters_not_bytes() {
+        let index = LineIndex::new("<text>žluť</text>".as_bytes());
+
+        // `<` of the closing tag is after 4 characters, but 6 bytes
+        assert_eq!(index.location(12).column, 11);
+    }
+
+    #[test]
+    fn locate_should_continue_from_earlier_lookups() {
+        let source = "<svg>\n<g a=\"ž\"/><rect x=\"1\"/>\n</svg>".as_bytes();
+        let mut index = LineIndex::new(source);
+
+        // forwards, backwards into the start tag and to another line
+        for offset in [0, 6, 17, 9, 14, 20, 33, 36, 18, 2, 100] {
+            assert_eq!(
+                Locate::location(&mut index, offset),
+                LineIndex::new(source).location(offset),
+                "{offset}"
+            );
+        }
+    }
+
+    #[test]
+    fn location_should_clamp_to_end_of_input() {
+        let index = LineIndex::new(b"<svg/>");
+
+        assert_eq!(index.location(100).offset, 6);
+        assert_eq!(index.location(100).column, 7);
+    }
+}
@@ -1,13 +1,203 @@
+//! `<angle>`.
+
+use std::{
+    f64::consts::PI,
+    fmt,
+    ops::{Add, Div, Mul, Neg, Sub},
+    str::FromStr,
+};
+
 use crate::svg::types::basic::Number;
 
+/// The unit of an [`Angle`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
 pub enum Unit {
+    /// No unit: degrees, as in `rotate(45)`.
+    #[default]
+    Number,
     Degree,
     Radian,
     Gradian,
     Turn,
 }
 
+impl Unit {
+    /// Returns the unit as written after a number, e.g. `deg`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Unit::Number => "",
+            Unit::Degree => "deg",
+            Unit::Radian => "rad",
+            Unit::Gradian => "grad",
+            Unit::Turn => "turn",
+        }
+    }
+
+    /// Returns the number of degrees in one of this unit.
+    pub fn to_degrees(&self) -> f64 {
+        match self {
+            Unit::Number | Unit::Degree => 1.0,
+            Unit::Radian => 180.0 / PI,
+            Unit::Gradian => 0.9,
+            Unit::Turn => 360.0,
+        }
+    }
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Unit {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "" => Ok(Unit::Number),
+            _ if s.eq_ignore_ascii_case("deg") => Ok(Unit::Degree),
+            _ if s.eq_ignore_ascii_case("rad") => Ok(Unit::Radian),
+            _ if s.eq_ignore_ascii_case("grad") => Ok(Unit::Gradian),
+            _ if s.eq_ignore_ascii_case("turn") => Ok(Unit::Turn),
+            _ => Err(()),
+        }
+    }
+}
+
+/// An `<angle>`, such as `90deg` or `1.5turn`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub struct Angle {
     value: Number,
     unit: Unit,
 }
+
+impl Angle {
+    pub fn new(value: f64, unit: Unit) -> Self {
+        Self {
+            value: Number(value),
+            unit,
+        }
+    }
+
+    pub fn deg(value: f64) -> Self {
+        Self::new(value, Unit::Degree)
+    }
+
+    pub fn rad(value: f64) -> Self {
+        Self::new(value, Unit::Radian)
+    }
+
+    pub fn value(&self) -> f64 {
+        self.value.0
+    }
+
+    pub fn unit(&self) -> Unit {
+        self.unit
+    }
+
+    pub fn to_degrees(&self) -> f64 {
+        self.value() * self.unit.to_degrees()
+    }
+
+    pub fn to_radians(&self) -> f64 {
+        self.to_degrees().to_radians()
+    }
+
+    /// Converts the angle to `unit`.
+    pub fn convert(&self, unit: Unit) -> Angle {
+        if unit == self.unit {
+            return *self;
+        }
+
+        Angle::new(self.to_degrees() / unit.to_degrees(), unit)
+    }
+}
+
+impl Add for Angle {
+    type Output = Self;
+
+    /// Adds `rhs` in the unit of `self`.
+    fn add(self, rhs: Self) -> Self {
+        Angle::new(self.value() + rhs.convert(self.unit).value(), self.unit)
+    }
+}
+
+impl Sub for Angle {
+    type Output = Self;
+
+    /// Subtracts `rhs` in the unit of `self`.
+    fn sub(self, rhs: Self) -> Self {
+        Angle::new(self.value() - rhs.convert(self.unit).value(), self.unit)
+    }
+}
+
+impl Mul<f64> for Angle {
+    type Output = Self;
+
+    fn mul(self, rhs: f64) -> Self {
+        Angle::new(self.value() * rhs, self.unit)
+    }
+}
+
+impl Div<f64> for Angle {
+    type Output = Self;
+
+    fn div(self, rhs: f64) -> Self {
+        Angle::new(self.value() / rhs, self.unit)
+    }
+}
+
+impl Neg for Angle {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Angle::new(-self.value(), self.unit)
+    }
+}
+
+impl fmt::Display for Angle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.value, self.unit)
+    }
+}
+
+impl FromStr for Angle {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (value, unit) = Number::parse_prefix(s.trim_ascii()).ok_or(())?;
+        Ok(Self {
+            value,
+            unit: unit.parse()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn angle_should_parse_and_convert() {
+        assert_eq!("90deg".parse(), Ok(Angle::deg(90.0)));
+        assert_eq!("45".parse(), Ok(Angle::new(45.0, Unit::Number)));
+        assert_eq!("1.5TURN".parse(), Ok(Angle::new(1.5, Unit::Turn)));
+        assert_eq!("90 deg".parse::<Angle>(), Err(()));
+
+        assert_eq!(Angle::new(100.0, Unit::Gradian).to_degrees(), 90.0);
+        assert_eq!(Angle::new(0.5, Unit::Turn).to_radians(), PI);
+        assert_eq!(Angle::rad(PI).convert(Unit::Degree), Angle::deg(180.0));
+        assert_eq!(Angle::deg(90.0).convert(Unit::Turn).to_string(), "0.25turn");
+    }
+
+    #[test]
+    fn angle_arithmetic_should_keep_the_unit() {
+        assert_eq!(
+            Angle::deg(90.0) + Angle::new(0.25, Unit::Turn),
+            Angle::deg(180.0)
+        );
+        assert_eq!(Angle::deg(90.0) - Angle::deg(30.0), Angle::deg(60.0));
+        assert_eq!(-Angle::rad(1.0) * 2.0 / 4.0, Angle::rad(-0.5));
+    }
+}
@@ -1,8 +1,418 @@
-pub struct Boolean(bool);
-pub struct Integer(i64);
-pub struct Number(f64);
-pub struct SvgString(String);
+//! `<number>`, `<integer>`, `<boolean>`, `<string>` and `<url>`.
+
+use std::{fmt, str::FromStr};
+
+/// A `<number>`, such as `100`, `-.5` or `2.5e-3`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+pub struct Number(pub f64);
+
+impl Number {
+    /// Parses the `<number>` at the start of `s`, and returns it with the rest
+    /// of `s`.
+    ///
+    /// A number too large for an `f64`, such as `1e999`, is not accepted.
+    pub fn parse_prefix(s: &str) -> Option<(Number, &str)> {
+        let len = number_len(s.as_bytes())?;
+        let value = s[..len]
+            .parse::<f64>()
+            .ok()
+            .filter(|value| value.is_finite())?;
+        Some((Number(value), &s[len..]))
+    }
+}
+
+/// Returns the length of the longest `<number>` at the start of `bytes`.
+///
+/// A `.` or an `e` that no digits follow is not part of the number, so `1.`
+/// and `1em` end after `1`. The value is not checked, so `1e999` is a number
+/// here.
+pub(crate) fn number_len(bytes: &[u8]) -> Option<usize> {
+    let digits = |from: usize| {
+        bytes[from..]
+            .iter()
+            .take_while(|byte| byte.is_ascii_digit())
+            .count()
+    };
+
+    let mut len = usize::from(matches!(bytes.first(), Some(b'+' | b'-')));
+    let integer = digits(len);
+    len += integer;
+
+    let mut fraction = 0;
+    if bytes.get(len) == Some(&b'.') {
+        fraction = digits(len + 1);
+        if fraction > 0 {
+            len += 1 + fraction;
+        }
+    }
+    if integer == 0 && fraction == 0 {
+        return None;
+    }
+
+    if matches!(bytes.get(len), Some(b'e' | b'E')) {
+        let sign = usize::from(matches!(bytes.get(len + 1), Some(b'+' | b'-')));
+        let exponent = digits(len + 1 + sign);
+        if exponent > 0 {
+            len += 1 + sign + exponent;
+        }
+    }
+
+    Some(len)
+}
+
+/// Formats `number` as short as possible: without a leading zero before the
+/// decimal point, and `-0` as `0`.
+pub(crate) fn format_number(number: f64) -> String {
+    let number = match number.to_string() {
+        zero if zero == "-0" => "0".to_string(),
+        number => number,
+    };
+
+    if let Some(fraction) = number.strip_prefix("0.") {
+        format!(".{fraction}")
+    } else if let Some(fraction) = number.strip_prefix("-0.") {
+        format!("-.{fraction}")
+    } else {
+        number
+    }
+}
+
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for Number {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match Number::parse_prefix(s.trim_ascii()) {
+            Some((number, "")) => Ok(number),
+            _ => Err(()),
+        }
+    }
+}
+
+impl From<f64> for Number {
+    fn from(value: f64) -> Self {
+        Number(value)
+    }
+}
+
+impl From<Number> for f64 {
+    fn from(number: Number) -> Self {
+        number.0
+    }
+}
+
+/// Implements `+`, `-` and negation between values of a newtype, and `*` and
+/// `/` by a scalar.
+macro_rules! impl_arithmetic {
+    ($type:ident, $scalar:ty) => {
+        impl std::ops::Add for $type {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self {
+                $type(self.0 + rhs.0)
+            }
+        }
+
+        impl std::ops::Sub for $type {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self {
+                $type(self.0 - rhs.0)
+            }
+        }
+
+        impl std::ops::Mul<$scalar> for $type {
+            type Output = Self;
+
+            fn mul(self, rhs: $scalar) -> Self {
+                $type(self.0 * rhs)
+            }
+        }
+
+        impl std::ops::Div<$scalar> for $type {
+            type Output = Self;
+
+            fn div(self, rhs: $scalar) -> Self {
+                $type(self.0 / rhs)
+            }
+        }
+
+        impl std::ops::Neg for $type {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                $type(-self.0)
+            }
+        }
+    };
+}
+
+pub(crate) use impl_arithmetic;
+
+impl_arithmetic!(Number, f64);
+impl_arithmetic!(Integer, i64);
+
+/// An `<integer>`, such as `42` or `-17`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Integer(pub i64);
+
+impl fmt::Display for Integer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for Integer {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim_ascii();
+        let digits = s.strip_prefix(['+', '-']).unwrap_or(s);
+        if digits.is_empty() || !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+            return Err(());
+        }
+
+        s.parse().map(Integer).map_err(|_| ())
+    }
+}
+
+impl From<Integer> for Number {
+    fn from(integer: Integer) -> Self {
+        Number(integer.0 as f64)
+    }
+}
+
+/// A `<boolean>`: `true` or `false`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Boolean(pub bool);
+
+impl fmt::Display for Boolean {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for Boolean {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim_ascii() {
+            "true" => Ok(Boolean(true)),
+            "false" => Ok(Boolean(false)),
+            _ => Err(()),
+        }
+    }
+}
+
+/// A `<string>`.
+///
+/// Parsed from a CSS string in quotes, with its escapes, or from the text as
+/// it is. Written as a CSS string in double quotes.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SvgString(pub String);
+
+impl SvgString {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Returns the content of the CSS string `s`, which starts and ends with the
+/// same quote, with escapes replaced.
+fn unquote(s: &str) -> Option<String> {
+    let quote = s.chars().next().filter(|&c| c == '"' || c == '\'')?;
+    let inner = s[1..].strip_suffix(quote)?;
+
+    let mut content = String::with_capacity(inner.len());
+    let mut chars = inner.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == quote {
+            return None;
+        }
+        if c != '\\' {
+            content.push(c);
+            continue;
+        }
+
+        let mut hex = String::new();
+        while let Some(&digit) = chars.peek().filter(|c| c.is_ascii_hexdigit()) {
+            if hex.len() == 6 {
+                break;
+            }
+            hex.push(digit);
+            chars.next();
+        }
+
+        if hex.is_empty() {
+            // `\` before a newline continues the string on the next line
+            match chars.next() {
+                Some('\n') | None => {}
+                Some(escaped) => content.push(escaped),
+            }
+        } else {
+            let code = u32::from_str_radix(&hex, 16).ok()?;
+            content.push(char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER));
+            chars.next_if(|c| c.is_ascii_whitespace());
+        }
+    }
+
+    Some(content)
+}
+
+/// Writes `s` as a CSS string in double quotes.
+fn write_quoted(f: &mut fmt::Formatter<'_>, s: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for c in s.chars() {
+        match c {
+            '"' | '\\' => write!(f, "\\{}", c)?,
+            '\n' => write!(f, "\\a ")?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    write!(f, "\"")
+}
+
+impl fmt::Display for SvgString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_quoted(f, &self.0)
+    }
+}
+
+impl FromStr for SvgString {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim_ascii();
+        if s.starts_with(['"', '\'']) {
+            return unquote(s).map(SvgString).ok_or(());
+        }
+
+        Ok(SvgString(s.to_string()))
+    }
+}
+
+/// A `<url>`: `url(#target)` for an element of the same document, or
+/// `url(uri)`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Url {
+    /// The `id` of the referenced element, without `#`.
     Target(String),
     Url(String),
 }
+
+impl fmt::Display for Url {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let url = match self {
+            Url::Target(target) => return write!(f, "url(#{})", target),
+            Url::Url(url) => url,
+        };
+
+        let needs_quotes = url
+            .chars()
+            .any(|c| c.is_ascii_whitespace() || matches!(c, '"' | '\'' | '(' | ')' | '\\'));
+        write!(f, "url(")?;
+        match needs_quotes {
+            true => write_quoted(f, url)?,
+            false => write!(f, "{}", url)?,
+        }
+        write!(f, ")")
+    }
+}
+
+impl FromStr for Url {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim_ascii();
+        let inner = s
+            .get(..4)
+            .filter(|function| function.eq_ignore_ascii_case("url("))
+            .and_then(|_| s[4..].strip_suffix(')'))
+            .ok_or(())?
+            .trim_ascii();
+
+        let url = match inner.starts_with(['"', '\'']) {
+            true => unquote(inner).ok_or(())?,
+            false => inner.to_string(),
+        };
+
+        match url.strip_prefix('#') {
+            Some(target) => Ok(Url::Target(target.to_string())),
+            None => Ok(Url::Url(url)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn number_should_follow_the_grammar() {
+        for (input, expected) in [
+            ("100", 100.0),
+            ("-.5", -0.5),
+            ("+3.25", 3.25),
+            ("2.5e-3", 0.0025),
+            ("1E2", 100.0),
+        ] {
+            assert_eq!(input.parse(), Ok(Number(expected)), "{input}");
+        }
+
+        for input in [
+            "", "-", ".", "1.", "1e", "e5", "1.2.3", "0x10", "inf", "1e999", "-1e999",
+        ] {
+            assert_eq!(input.parse::<Number>(), Err(()), "{input}");
+        }
+
+        assert_eq!(Number::parse_prefix("1.5em"), Some((Number(1.5), "em")));
+        assert_eq!(Number::parse_prefix("1e3.5"), Some((Number(1000.0), ".5")));
+    }
+
+    #[test]
+    fn integer_and_boolean_should_round_trip() {
+        assert_eq!("-17".parse(), Ok(Integer(-17)));
+        assert_eq!("+4".parse::<Integer>().unwrap().to_string(), "4");
+        assert_eq!("1.0".parse::<Integer>(), Err(()));
+        assert_eq!(Integer(6) - Integer(8), Integer(-2));
+
+        assert_eq!("true".parse(), Ok(Boolean(true)));
+        assert_eq!("True".parse::<Boolean>(), Err(()));
+        assert_eq!(Boolean(false).to_string(), "false");
+    }
+
+    #[test]
+    fn string_should_unescape_and_quote() {
+        assert_eq!(
+            r#"'it\'s \26  "ok"'"#.parse(),
+            Ok(SvgString(r#"it's & "ok""#.to_string()))
+        );
+        assert_eq!("plain".parse(), Ok(SvgString("plain".to_string())));
+        assert_eq!("'open".parse::<SvgString>(), Err(()));
+        assert_eq!(
+            SvgString(r#"a "b" \c"#.to_string()).to_string(),
+            r#""a \"b\" \\c""#
+        );
+    }
+
+    #[test]
+    fn url_should_parse_targets_and_uris() {
+        assert_eq!("url(#grad)".parse(), Ok(Url::Target("grad".to_string())));
+        assert_eq!(
+            "URL( 'images/a b.svg' )".parse(),
+            Ok(Url::Url("images/a b.svg".to_string()))
+        );
+        assert_eq!("#grad".parse::<Url>(), Err(()));
+
+        assert_eq!(Url::Target("grad".to_string()).to_string(), "url(#grad)");
+        assert_eq!(
+            Url::Url("a b.svg".to_string()).to_string(),
+            r#"url("a b.svg")"#
+        );
+    }
+}
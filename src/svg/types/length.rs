@@ -1,25 +1,485 @@
-use crate::svg::types::basic::Number;
+//! `<length>`, `<percentage>` and `<length-percentage>`.
 
+use std::{
+    fmt,
+    ops::{Div, Mul, Neg},
+    str::FromStr,
+};
+
+use crate::svg::types::basic::{Number, impl_arithmetic};
+
+/// The unit of a [`Length`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
 pub enum Unit {
+    /// No unit: a number in user units, which are pixels.
+    #[default]
+    Number,
     Pixel,
     Point,
     Pica,
-    Milimeter,
+    Millimeter,
     Centimeter,
+    /// `Q`, a quarter of a millimeter.
+    QuarterMillimeter,
     Inch,
-    Rem,
     Em,
+    Rem,
     Ex,
+    Rex,
     Ch,
+    Rch,
+    Cap,
+    Rcap,
+    Ic,
+    Ric,
+    Lh,
+    Rlh,
     Vw,
     Vh,
     VMin,
     VMax,
 }
 
+impl Unit {
+    /// Returns the unit as written after a number, e.g. `mm`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Unit::Number => "",
+            Unit::Pixel => "px",
+            Unit::Point => "pt",
+            Unit::Pica => "pc",
+            Unit::Millimeter => "mm",
+            Unit::Centimeter => "cm",
+            Unit::QuarterMillimeter => "Q",
+            Unit::Inch => "in",
+            Unit::Em => "em",
+            Unit::Rem => "rem",
+            Unit::Ex => "ex",
+            Unit::Rex => "rex",
+            Unit::Ch => "ch",
+            Unit::Rch => "rch",
+            Unit::Cap => "cap",
+            Unit::Rcap => "rcap",
+            Unit::Ic => "ic",
+            Unit::Ric => "ric",
+            Unit::Lh => "lh",
+            Unit::Rlh => "rlh",
+            Unit::Vw => "vw",
+            Unit::Vh => "vh",
+            Unit::VMin => "vmin",
+            Unit::VMax => "vmax",
+        }
+    }
+
+    /// Returns `true` for units with a fixed size in pixels.
+    pub fn is_absolute(&self) -> bool {
+        matches!(
+            self,
+            Unit::Number
+                | Unit::Pixel
+                | Unit::Point
+                | Unit::Pica
+                | Unit::Millimeter
+                | Unit::Centimeter
+                | Unit::QuarterMillimeter
+                | Unit::Inch
+        )
+    }
+
+    /// Returns the number of pixels in one of this unit.
+    ///
+    /// Without font metrics, font-relative units are derived from the font
+    /// size as CSS suggests: `ex`, `ch` are `0.5em`, `cap` is `0.7em`, `ic` is
+    /// `1em` and `lh` is `1.2em`.
+    pub fn to_px(&self, context: &LengthContext) -> f64 {
+        let font = context.font_size;
+        let root = context.root_font_size;
+        let (width, height) = (context.viewport_width, context.viewport_height);
+
+        match self {
+            Unit::Number | Unit::Pixel => 1.0,
+            Unit::Point => 96.0 / 72.0,
+            Unit::Pica => 16.0,
+            Unit::Millimeter => 96.0 / 25.4,
+            Unit::Centimeter => 96.0 / 2.54,
+            Unit::QuarterMillimeter => 96.0 / 101.6,
+            Unit::Inch => 96.0,
+            Unit::Em | Unit::Ic => font,
+            Unit::Rem | Unit::Ric => root,
+            Unit::Ex | Unit::Ch => font * 0.5,
+            Unit::Rex | Unit::Rch => root * 0.5,
+            Unit::Cap => font * 0.7,
+            Unit::Rcap => root * 0.7,
+            Unit::Lh => font * 1.2,
+            Unit::Rlh => root * 1.2,
+            Unit::Vw => width / 100.0,
+            Unit::Vh => height / 100.0,
+            Unit::VMin => width.min(height) / 100.0,
+            Unit::VMax => width.max(height) / 100.0,
+        }
+    }
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Unit {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        const UNITS: &[Unit] = &[
+            Unit::Number,
+            Unit::Pixel,
+            Unit::Point,
+            Unit::Pica,
+            Unit::Millimeter,
+            Unit::Centimeter,
+            Unit::QuarterMillimeter,
+            Unit::Inch,
+            Unit::Em,
+            Unit::Rem,
+            Unit::Ex,
+            Unit::Rex,
+            Unit::Ch,
+            Unit::Rch,
+            Unit::Cap,
+            Unit::Rcap,
+            Unit::Ic,
+            Unit::Ric,
+            Unit::Lh,
+            Unit::Rlh,
+            Unit::Vw,
+            Unit::Vh,
+            Unit::VMin,
+            Unit::VMax,
+        ];
+
+        UNITS
+            .iter()
+            .find(|unit| unit.as_str().eq_ignore_ascii_case(s))
+            .copied()
+            .ok_or(())
+    }
+}
+
+/// What relative lengths are resolved against.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LengthContext {
+    /// The font size of the element, for `em` and the like.
+    pub font_size: f64,
+    /// The font size of the root element, for `rem` and the like.
+    pub root_font_size: f64,
+    pub viewport_width: f64,
+    pub viewport_height: f64,
+}
+
+impl Default for LengthContext {
+    /// A `16px` font in a viewport of `300x150`, the default size of an
+    /// embedded SVG.
+    fn default() -> Self {
+        Self {
+            font_size: 16.0,
+            root_font_size: 16.0,
+            viewport_width: 300.0,
+            viewport_height: 150.0,
+        }
+    }
+}
+
+/// A `<length>`: a number with a unit, or without one in user units.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub struct Length {
     value: Number,
     unit: Unit,
 }
 
-pub struct Percentage(Number);
+impl Length {
+    pub fn new(value: f64, unit: Unit) -> Self {
+        Self {
+            value: Number(value),
+            unit,
+        }
+    }
+
+    /// A length without a unit, in user units.
+    pub fn number(value: f64) -> Self {
+        Self::new(value, Unit::Number)
+    }
+
+    pub fn px(value: f64) -> Self {
+        Self::new(value, Unit::Pixel)
+    }
+
+    pub fn value(&self) -> f64 {
+        self.value.0
+    }
+
+    pub fn unit(&self) -> Unit {
+        self.unit
+    }
+
+    /// Returns the length in pixels.
+    pub fn to_px(&self, context: &LengthContext) -> f64 {
+        self.value() * self.unit.to_px(context)
+    }
+
+    /// Returns the length in pixels if its unit is absolute.
+    pub fn to_absolute_px(&self) -> Option<f64> {
+        self.unit
+            .is_absolute()
+            .then(|| self.to_px(&LengthContext::default()))
+    }
+
+    /// Converts the length to `unit`, or returns `None` if `unit` has no size
+    /// in `context`.
+    pub fn convert(&self, unit: Unit, context: &LengthContext) -> Option<Length> {
+        if unit == self.unit {
+            return Some(*self);
+        }
+
+        let size = unit.to_px(context);
+        (size != 0.0).then(|| Length::new(self.to_px(context) / size, unit))
+    }
+
+    /// Adds `other`, in the unit of this length, if both have the same unit or
+    /// absolute units.
+    pub fn checked_add(&self, other: Length) -> Option<Length> {
+        let other = self.in_unit_of(other)?;
+        Some(Length::new(self.value() + other, self.unit))
+    }
+
+    /// Subtracts `other`, in the unit of this length, if both have the same
+    /// unit or absolute units.
+    pub fn checked_sub(&self, other: Length) -> Option<Length> {
+        let other = self.in_unit_of(other)?;
+        Some(Length::new(self.value() - other, self.unit))
+    }
+
+    /// Returns the value of `other` in the unit of this length.
+    fn in_unit_of(&self, other: Length) -> Option<f64> {
+        if other.unit == self.unit {
+            return Some(other.value());
+        }
+
+        other.to_absolute_px()?;
+        self.unit
+            .is_absolute()
+            .then(|| other.convert(self.unit, &LengthContext::default()))
+            .flatten()
+            .map(|length| length.value())
+    }
+}
+
+impl Mul<f64> for Length {
+    type Output = Self;
+
+    fn mul(self, rhs: f64) -> Self {
+        Length::new(self.value() * rhs, self.unit)
+    }
+}
+
+impl Div<f64> for Length {
+    type Output = Self;
+
+    fn div(self, rhs: f64) -> Self {
+        Length::new(self.value() / rhs, self.unit)
+    }
+}
+
+impl Neg for Length {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Length::new(-self.value(), self.unit)
+    }
+}
+
+impl fmt::Display for Length {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.value, self.unit)
+    }
+}
+
+impl FromStr for Length {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (value, unit) = Number::parse_prefix(s.trim_ascii()).ok_or(())?;
+        Ok(Self {
+            value,
+            unit: unit.parse()?,
+        })
+    }
+}
+
+/// A `<percentage>`, such as `50%`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+pub struct Percentage(pub f64);
+
+impl_arithmetic!(Percentage, f64);
+
+impl Percentage {
+    /// Returns the percentage of `base`.
+    pub fn resolve(&self, base: f64) -> f64 {
+        self.0 / 100.0 * base
+    }
+
+    /// Returns the percentage as a fraction, `0.5` for `50%`.
+    pub fn to_fraction(&self) -> f64 {
+        self.0 / 100.0
+    }
+}
+
+impl fmt::Display for Percentage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}%", self.0)
+    }
+}
+
+impl FromStr for Percentage {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match Number::parse_prefix(s.trim_ascii()) {
+            Some((value, "%")) => Ok(Percentage(value.0)),
+            _ => Err(()),
+        }
+    }
+}
+
+/// A `<length-percentage>`, which is also a `<coordinate>`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LengthOrPercentage {
+    Length(Length),
+    Percentage(Percentage),
+}
+
+impl LengthOrPercentage {
+    /// Returns the value in pixels, with percentages of `base` pixels.
+    pub fn resolve(&self, context: &LengthContext, base: f64) -> f64 {
+        match self {
+            LengthOrPercentage::Length(length) => length.to_px(context),
+            LengthOrPercentage::Percentage(percentage) => percentage.resolve(base),
+        }
+    }
+}
+
+impl Default for LengthOrPercentage {
+    fn default() -> Self {
+        LengthOrPercentage::Length(Length::default())
+    }
+}
+
+impl From<Length> for LengthOrPercentage {
+    fn from(length: Length) -> Self {
+        LengthOrPercentage::Length(length)
+    }
+}
+
+impl From<Percentage> for LengthOrPercentage {
+    fn from(percentage: Percentage) -> Self {
+        LengthOrPercentage::Percentage(percentage)
+    }
+}
+
+impl fmt::Display for LengthOrPercentage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LengthOrPercentage::Length(length) => write!(f, "{}", length),
+            LengthOrPercentage::Percentage(percentage) => write!(f, "{}", percentage),
+        }
+    }
+}
+
+impl FromStr for LengthOrPercentage {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(percentage) = s.parse() {
+            return Ok(LengthOrPercentage::Percentage(percentage));
+        }
+
+        s.parse().map(LengthOrPercentage::Length)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn length_should_parse_units() {
+        assert_eq!("10".parse(), Ok(Length::number(10.0)));
+        assert_eq!("-2.5e1MM".parse(), Ok(Length::new(-25.0, Unit::Millimeter)));
+        assert_eq!(".5rem".parse(), Ok(Length::new(0.5, Unit::Rem)));
+        assert_eq!("3Q".parse(), Ok(Length::new(3.0, Unit::QuarterMillimeter)));
+
+        for input in [
+            "px",
+            "10 px",
+            "10%",
+            "10furlong",
+            "1.px",
+            "1e999px",
+            "1e999",
+        ] {
+            assert_eq!(input.parse::<Length>(), Err(()), "{input}");
+        }
+
+        assert_eq!(Length::new(1.5, Unit::VMin).to_string(), "1.5vmin");
+        assert_eq!(Length::number(2.0).to_string(), "2");
+    }
+
+    #[test]
+    fn length_should_convert_units() {
+        let context = LengthContext::default();
+
+        assert_eq!(Length::new(1.0, Unit::Inch).to_px(&context), 96.0);
+        assert_eq!(Length::new(2.0, Unit::Em).to_px(&context), 32.0);
+        assert_eq!(Length::new(10.0, Unit::Vw).to_px(&context), 30.0);
+        assert_eq!(Length::new(1.0, Unit::Em).to_absolute_px(), None);
+
+        let inch = Length::new(72.0, Unit::Point).convert(Unit::Inch, &context);
+        assert_eq!(inch, Some(Length::new(1.0, Unit::Inch)));
+
+        let zero = LengthContext {
+            viewport_width: 0.0,
+            ..context
+        };
+        assert_eq!(Length::px(1.0).convert(Unit::Vw, &zero), None);
+    }
+
+    #[test]
+    fn length_arithmetic_should_keep_the_unit() {
+        let inch = Length::new(1.0, Unit::Inch);
+
+        assert_eq!(
+            inch.checked_add(Length::new(48.0, Unit::Pixel)),
+            Some(Length::new(1.5, Unit::Inch))
+        );
+        assert_eq!(inch.checked_sub(Length::new(1.0, Unit::Em)), None);
+        assert_eq!(
+            Length::new(1.0, Unit::Em).checked_add(Length::new(2.0, Unit::Em)),
+            Some(Length::new(3.0, Unit::Em))
+        );
+        assert_eq!(-inch * 3.0 / 2.0, Length::new(-1.5, Unit::Inch));
+    }
+
+    #[test]
+    fn length_or_percentage_should_resolve() {
+        let context = LengthContext::default();
+
+        let half: LengthOrPercentage = "50%".parse().unwrap();
+        assert_eq!(half, LengthOrPercentage::Percentage(Percentage(50.0)));
+        assert_eq!(half.resolve(&context, 300.0), 150.0);
+        assert_eq!(half.to_string(), "50%");
+
+        let length: LengthOrPercentage = "2cm".parse().unwrap();
+        assert_eq!(length.to_string(), "2cm");
+        assert_eq!("50 %".parse::<LengthOrPercentage>(), Err(()));
+        assert_eq!(Percentage(20.0) + Percentage(5.0), Percentage(25.0));
+    }
+}
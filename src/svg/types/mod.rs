@@ -1,3 +1,14 @@
+//! SVG 2 data types, parsed and written per the CSS value grammar.
+//!
+//! Attribute values that hold numbers, lengths, angles, URLs or transforms
+//! share these types.
+
 pub mod angle;
 pub mod basic;
 pub mod length;
+pub mod transform;
+
+pub use angle::Angle;
+pub use basic::{Boolean, Integer, Number, SvgString, Url};
+pub use length::{Length, LengthContext, LengthOrPercentage, Percentage};
+pub use transform::{Matrix, Transform, TransformList, TransformOrigin};
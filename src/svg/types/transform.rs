@@ -0,0 +1,815 @@
+//! `<transform-list>`, for `transform`, `gradientTransform` and
+//! `patternTransform`, and `transform-origin`.
+//!
+//! Lists follow the SVG syntax, with optional commas, unitless lengths and
+//! angles, and `rotate(angle cx cy)`, as well as the CSS syntax with units,
+//! such as `rotate(45deg) translate(10px, 2em)`.
+
+use std::{fmt, ops::Mul, str::FromStr};
+
+use crate::svg::types::{
+    angle::{self, Angle},
+    basic::{Number, format_number},
+    length::{Length, LengthContext, LengthOrPercentage, Percentage},
+};
+
+/// A 2D affine transformation matrix, as in `matrix(a b c d e f)`:
+///
+/// ```text
+/// | a c e |
+/// | b d f |
+/// | 0 0 1 |
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Matrix {
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+    pub d: f64,
+    pub e: f64,
+    pub f: f64,
+}
+
+impl Default for Matrix {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Matrix {
+    pub const IDENTITY: Matrix = Matrix::new(1.0, 0.0, 0.0, 1.0, 0.0, 0.0);
+
+    pub const fn new(a: f64, b: f64, c: f64, d: f64, e: f64, f: f64) -> Self {
+        Self { a, b, c, d, e, f }
+    }
+
+    pub fn translate(tx: f64, ty: f64) -> Self {
+        Self::new(1.0, 0.0, 0.0, 1.0, tx, ty)
+    }
+
+    pub fn scale(sx: f64, sy: f64) -> Self {
+        Self::new(sx, 0.0, 0.0, sy, 0.0, 0.0)
+    }
+
+    /// Returns a rotation by `angle` degrees around the origin.
+    pub fn rotate(angle: f64) -> Self {
+        let (sin, cos) = angle.to_radians().sin_cos();
+        Self::new(cos, sin, -sin, cos, 0.0, 0.0)
+    }
+
+    /// Returns a skew along the x axis by `angle` degrees.
+    pub fn skew_x(angle: f64) -> Self {
+        Self::new(1.0, 0.0, angle.to_radians().tan(), 1.0, 0.0, 0.0)
+    }
+
+    /// Returns a skew along the y axis by `angle` degrees.
+    pub fn skew_y(angle: f64) -> Self {
+        Self::new(1.0, angle.to_radians().tan(), 0.0, 1.0, 0.0, 0.0)
+    }
+
+    /// Returns `self × other`: the transformation that applies `other` first
+    /// and then `self`, as the list `self other` does.
+    pub fn multiply(&self, other: &Matrix) -> Matrix {
+        Matrix::new(
+            self.a * other.a + self.c * other.b,
+            self.b * other.a + self.d * other.b,
+            self.a * other.c + self.c * other.d,
+            self.b * other.c + self.d * other.d,
+            self.a * other.e + self.c * other.f + self.e,
+            self.b * other.e + self.d * other.f + self.f,
+        )
+    }
+
+    pub fn determinant(&self) -> f64 {
+        self.a * self.d - self.b * self.c
+    }
+
+    /// Returns the inverse transformation, or `None` if the matrix is
+    /// singular.
+    pub fn invert(&self) -> Option<Matrix> {
+        let determinant = self.determinant();
+        if determinant == 0.0 || !determinant.is_finite() {
+            return None;
+        }
+
+        Some(Matrix::new(
+            self.d / determinant,
+            -self.b / determinant,
+            -self.c / determinant,
+            self.a / determinant,
+            (self.c * self.f - self.d * self.e) / determinant,
+            (self.b * self.e - self.a * self.f) / determinant,
+        ))
+    }
+
+    /// Transforms the point `(x, y)`.
+    pub fn apply(&self, (x, y): (f64, f64)) -> (f64, f64) {
+        (
+            self.a * x + self.c * y + self.e,
+            self.b * x + self.d * y + self.f,
+        )
+    }
+
+    pub fn is_identity(&self) -> bool {
+        *self == Matrix::IDENTITY
+    }
+
+    /// Splits the matrix into a translation, a rotation, a skew along the x
+    /// axis and a scale, applied in the reverse of that order.
+    ///
+    /// Returns `None` for the singular matrices that no such combination
+    /// makes.
+    pub fn decompose(&self) -> Option<Decomposition> {
+        let sx = self.a.hypot(self.b);
+        if sx == 0.0 {
+            return None;
+        }
+
+        // without the rotation, what is left is `skewX(skew_x) scale(sx sy)`:
+        // | sx  tan(skew_x) * sy |
+        // | 0   sy               |
+        let skew = (self.a * self.c + self.b * self.d) / sx;
+        let sy = self.determinant() / sx;
+        let skew_x = match sy == 0.0 {
+            true if skew != 0.0 => return None,
+            true => 0.0,
+            false => (skew / sy).atan().to_degrees(),
+        };
+
+        Some(Decomposition {
+            translate: (self.e, self.f),
+            rotate: self.b.atan2(self.a).to_degrees(),
+            skew_x,
+            scale: (sx, sy),
+        })
+    }
+}
+
+impl Mul for Matrix {
+    type Output = Matrix;
+
+    fn mul(self, rhs: Matrix) -> Matrix {
+        self.multiply(&rhs)
+    }
+}
+
+impl fmt::Display for Matrix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "matrix({})",
+            join(&[self.a, self.b, self.c, self.d, self.e, self.f].map(format_number))
+        )
+    }
+}
+
+/// A [`Matrix`] as `translate(tx ty) rotate(rotate) skewX(skew_x) scale(sx sy)`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Decomposition {
+    pub translate: (f64, f64),
+    /// In degrees.
+    pub rotate: f64,
+    /// In degrees.
+    pub skew_x: f64,
+    pub scale: (f64, f64),
+}
+
+impl Decomposition {
+    pub fn to_matrix(&self) -> Matrix {
+        Matrix::translate(self.translate.0, self.translate.1)
+            * Matrix::rotate(self.rotate)
+            * Matrix::skew_x(self.skew_x)
+            * Matrix::scale(self.scale.0, self.scale.1)
+    }
+}
+
+/// A transform function.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Transform {
+    Matrix(Matrix),
+    Translate(Length, Length),
+    Scale(f64, f64),
+    /// `rotate(angle cx cy)`: a rotation around the point `(cx, cy)`.
+    Rotate(Angle, f64, f64),
+    SkewX(Angle),
+    SkewY(Angle),
+}
+
+impl Transform {
+    pub fn to_matrix(&self, context: &LengthContext) -> Matrix {
+        match *self {
+            Transform::Matrix(matrix) => matrix,
+            Transform::Translate(tx, ty) => Matrix::translate(tx.to_px(context), ty.to_px(context)),
+            Transform::Scale(sx, sy) => Matrix::scale(sx, sy),
+            Transform::Rotate(angle, cx, cy) => {
+                Matrix::translate(cx, cy)
+                    * Matrix::rotate(angle.to_degrees())
+                    * Matrix::translate(-cx, -cy)
+            }
+            Transform::SkewX(angle) => Matrix::skew_x(angle.to_degrees()),
+            Transform::SkewY(angle) => Matrix::skew_y(angle.to_degrees()),
+        }
+    }
+
+    /// Returns whether the transform leaves every point where it is.
+    fn is_identity(&self) -> bool {
+        match *self {
+            Transform::Matrix(matrix) => matrix.is_identity(),
+            Transform::Translate(tx, ty) => tx.value() == 0.0 && ty.value() == 0.0,
+            Transform::Scale(sx, sy) => sx == 1.0 && sy == 1.0,
+            Transform::Rotate(angle, ..) | Transform::SkewX(angle) | Transform::SkewY(angle) => {
+                angle.value() == 0.0
+            }
+        }
+    }
+
+    /// Returns the matrix of the transform if it does not depend on font
+    /// sizes or the viewport.
+    fn to_absolute_matrix(self) -> Option<Matrix> {
+        if let Transform::Translate(tx, ty) = self {
+            tx.to_absolute_px()?;
+            ty.to_absolute_px()?;
+        }
+
+        Some(self.to_matrix(&LengthContext::default()))
+    }
+
+    /// Parses the transform function `name` with the arguments `args`.
+    fn parse(name: &str, args: &[&str]) -> Result<Self, ()> {
+        let number = |arg: &str| arg.parse::<Number>().map(|number| number.0);
+        let length = |arg: &str| arg.parse::<Length>();
+        let angle = |arg: &str| arg.parse::<Angle>();
+        // CSS also allows percentages for scales
+        let factor = |arg: &str| match arg.parse::<Percentage>() {
+            Ok(percentage) => Ok(percentage.to_fraction()),
+            Err(()) => number(arg),
+        };
+        let zero = Length::number(0.0);
+
+        let name = name.to_ascii_lowercase();
+        let transform = match (name.as_str(), args) {
+            ("matrix", &[a, b, c, d, e, f]) => Transform::Matrix(Matrix::new(
+                number(a)?,
+                number(b)?,
+                number(c)?,
+                number(d)?,
+                number(e)?,
+                number(f)?,
+            )),
+            ("translate", &[tx]) | ("translatex", &[tx]) => Transform::Translate(length(tx)?, zero),
+            ("translate", &[tx, ty]) => Transform::Translate(length(tx)?, length(ty)?),
+            ("translatey", &[ty]) => Transform::Translate(zero, length(ty)?),
+            ("scale", &[s]) => Transform::Scale(factor(s)?, factor(s)?),
+            ("scale", &[sx, sy]) => Transform::Scale(factor(sx)?, factor(sy)?),
+            ("scalex", &[sx]) => Transform::Scale(factor(sx)?, 1.0),
+            ("scaley", &[sy]) => Transform::Scale(1.0, factor(sy)?),
+            ("rotate", &[a]) => Transform::Rotate(angle(a)?, 0.0, 0.0),
+            ("rotate", &[a, cx, cy]) => Transform::Rotate(angle(a)?, number(cx)?, number(cy)?),
+            ("skewx", &[a]) | ("skew", &[a]) => Transform::SkewX(angle(a)?),
+            ("skewy", &[a]) => Transform::SkewY(angle(a)?),
+            ("skew", &[ax, ay]) => {
+                let (ax, ay) = (angle(ax)?, angle(ay)?);
+                match ay.value() == 0.0 {
+                    true => Transform::SkewX(ax),
+                    false => Transform::Matrix(Matrix::new(
+                        1.0,
+                        ay.to_radians().tan(),
+                        ax.to_radians().tan(),
+                        1.0,
+                        0.0,
+                        0.0,
+                    )),
+                }
+            }
+            _ => return Err(()),
+        };
+
+        Ok(transform)
+    }
+}
+
+/// Returns the shorter of `angle` as it is and in degrees without a unit.
+fn shortest_angle(angle: Angle) -> String {
+    let degrees = format_number(angle.to_degrees());
+    match angle.unit() {
+        angle::Unit::Number | angle::Unit::Degree => degrees,
+        unit => {
+            let original = format!("{}{}", format_number(angle.value()), unit);
+            match degrees.len() < original.len() {
+                true => degrees,
+                false => original,
+            }
+        }
+    }
+}
+
+/// Returns the shorter of `length` as it is and in user units.
+fn shortest_length(length: Length) -> String {
+    let original = format!("{}{}", format_number(length.value()), length.unit());
+    match length.to_absolute_px().map(format_number) {
+        Some(px) if px.len() <= original.len() => px,
+        _ => original,
+    }
+}
+
+/// Joins arguments with spaces.
+fn join(args: &[String]) -> String {
+    args.join(" ")
+}
+
+impl fmt::Display for Transform {
+    /// Writes the shortest form of the transform function.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Transform::Matrix(matrix) => write!(f, "{}", matrix),
+            Transform::Translate(tx, ty) if ty.value() == 0.0 => {
+                write!(f, "translate({})", shortest_length(tx))
+            }
+            Transform::Translate(tx, ty) => write!(
+                f,
+                "translate({})",
+                join(&[shortest_length(tx), shortest_length(ty)])
+            ),
+            Transform::Scale(sx, sy) if sx == sy => write!(f, "scale({})", format_number(sx)),
+            Transform::Scale(sx, sy) => {
+                write!(f, "scale({})", join(&[sx, sy].map(format_number)))
+            }
+            Transform::Rotate(angle, cx, cy) if cx == 0.0 && cy == 0.0 => {
+                write!(f, "rotate({})", shortest_angle(angle))
+            }
+            Transform::Rotate(angle, cx, cy) => write!(
+                f,
+                "rotate({})",
+                join(&[shortest_angle(angle), format_number(cx), format_number(cy)])
+            ),
+            Transform::SkewX(angle) => write!(f, "skewX({})", shortest_angle(angle)),
+            Transform::SkewY(angle) => write!(f, "skewY({})", shortest_angle(angle)),
+        }
+    }
+}
+
+/// A `<transform-list>`: transform functions applied from right to left.
+///
+/// An empty list, written as `none` in CSS, is the identity.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TransformList(pub Vec<Transform>);
+
+impl TransformList {
+    /// Returns the single matrix of the whole list.
+    pub fn to_matrix(&self, context: &LengthContext) -> Matrix {
+        self.0.iter().fold(Matrix::IDENTITY, |matrix, transform| {
+            matrix * transform.to_matrix(context)
+        })
+    }
+
+    /// Returns the shortest single transform function for the list, if it
+    /// does not depend on font sizes or the viewport.
+    ///
+    /// Numbers are rounded to ten decimal places, which removes the rounding
+    /// errors of combining the functions.
+    fn folded(&self) -> Option<String> {
+        let matrix = self
+            .0
+            .iter()
+            .try_fold(Matrix::IDENTITY, |matrix, transform| {
+                Some(matrix * transform.to_absolute_matrix()?)
+            })?;
+
+        let round = |value: f64| {
+            let value = (value * 1e10).round() / 1e10;
+            if value == 0.0 { 0.0 } else { value }
+        };
+        let Matrix { a, b, c, d, e, f } = matrix;
+        let [a, b, c, d, e, f] = [a, b, c, d, e, f].map(round);
+
+        let transform = if [a, b, c, d] == [1.0, 0.0, 0.0, 1.0] {
+            if e == 0.0 && f == 0.0 {
+                return Some(String::new());
+            }
+            Transform::Translate(Length::number(e), Length::number(f))
+        } else if b == 0.0 && c == 0.0 && e == 0.0 && f == 0.0 {
+            Transform::Scale(a, d)
+        } else if a == d && b == -c && round(a * a + b * b) == 1.0 {
+            // a rotation around the point that it maps to itself
+            let angle = Angle::deg(round(b.atan2(a).to_degrees()));
+            let (cx, cy) = match (e, f) {
+                (0.0, 0.0) => (0.0, 0.0),
+                (e, f) => {
+                    let determinant = (1.0 - a) * (1.0 - a) + b * b;
+                    (
+                        round(((1.0 - a) * e - b * f) / determinant),
+                        round((b * e + (1.0 - a) * f) / determinant),
+                    )
+                }
+            };
+            Transform::Rotate(angle, cx, cy)
+        } else if a == 1.0 && b == 0.0 && d == 1.0 && e == 0.0 && f == 0.0 {
+            Transform::SkewX(Angle::deg(round(c.atan().to_degrees())))
+        } else if a == 1.0 && c == 0.0 && d == 1.0 && e == 0.0 && f == 0.0 {
+            Transform::SkewY(Angle::deg(round(b.atan().to_degrees())))
+        } else {
+            Transform::Matrix(Matrix::new(a, b, c, d, e, f))
+        };
+
+        // a rotation whose center was rounded might no longer be the same
+        let rounded = Matrix::new(a, b, c, d, e, f);
+        let written = transform.to_matrix(&LengthContext::default());
+        let close = |x: f64, y: f64| (x - y).abs() < 1e-9 * x.abs().max(1.0);
+        let same = close(written.a, rounded.a)
+            && close(written.b, rounded.b)
+            && close(written.c, rounded.c)
+            && close(written.d, rounded.d)
+            && close(written.e, rounded.e)
+            && close(written.f, rounded.f);
+        match same {
+            true => Some(transform.to_string()),
+            false => Some(Transform::Matrix(rounded).to_string()),
+        }
+    }
+}
+
+impl fmt::Display for TransformList {
+    /// Writes the shortest equivalent list: either the functions that change
+    /// anything in their shortest form, or a single function for the whole
+    /// list.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let list: String = self
+            .0
+            .iter()
+            .filter(|transform| !transform.is_identity())
+            .map(Transform::to_string)
+            .collect();
+
+        match self.folded() {
+            Some(folded) if folded.len() < list.len() => f.write_str(&folded),
+            _ => f.write_str(&list),
+        }
+    }
+}
+
+impl FromStr for TransformList {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut rest = s.trim_ascii();
+        if rest.eq_ignore_ascii_case("none") {
+            return Ok(TransformList::default());
+        }
+
+        let mut transforms = vec![];
+        while !rest.is_empty() {
+            let (name, after_name) = rest.split_once('(').ok_or(())?;
+            let (args, after_args) = after_name.split_once(')').ok_or(())?;
+
+            let name = name.trim_ascii();
+            if name.is_empty() || !name.bytes().all(|byte| byte.is_ascii_alphabetic()) {
+                return Err(());
+            }
+
+            let args = split_args(args)?;
+            transforms.push(Transform::parse(name, &args)?);
+
+            rest = after_args.trim_ascii_start();
+            if let Some(after_comma) = rest.strip_prefix(',') {
+                rest = after_comma.trim_ascii_start();
+                if rest.is_empty() {
+                    return Err(());
+                }
+            }
+        }
+
+        Ok(TransformList(transforms))
+    }
+}
+
+/// Splits the arguments of a transform function, which are separated by
+/// whitespace, a comma, or nothing before a sign.
+fn split_args(s: &str) -> Result<Vec<&str>, ()> {
+    let mut args = vec![];
+    let mut rest = s.trim_ascii();
+
+    while !rest.is_empty() {
+        let (_, after_number) = Number::parse_prefix(rest).ok_or(())?;
+        let unit_len = after_number
+            .bytes()
+            .take_while(|byte| byte.is_ascii_alphabetic() || *byte == b'%')
+            .count();
+        let len = rest.len() - after_number.len() + unit_len;
+        args.push(&rest[..len]);
+
+        rest = rest[len..].trim_ascii_start();
+        if let Some(after_comma) = rest.strip_prefix(',') {
+            rest = after_comma.trim_ascii_start();
+            if rest.is_empty() {
+                return Err(());
+            }
+        }
+    }
+
+    Ok(args)
+}
+
+/// A `transform-origin`: the point that transformations are relative to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TransformOrigin {
+    pub x: LengthOrPercentage,
+    pub y: LengthOrPercentage,
+    pub z: Length,
+}
+
+impl Default for TransformOrigin {
+    /// `0 0`, the origin of SVG elements other than the outermost `<svg>`.
+    fn default() -> Self {
+        Self {
+            x: LengthOrPercentage::default(),
+            y: LengthOrPercentage::default(),
+            z: Length::number(0.0),
+        }
+    }
+}
+
+impl TransformOrigin {
+    /// Returns the point within a reference box of `width` by `height`.
+    pub fn resolve(&self, context: &LengthContext, width: f64, height: f64) -> (f64, f64) {
+        (
+            self.x.resolve(context, width),
+            self.y.resolve(context, height),
+        )
+    }
+}
+
+/// A value of `transform-origin`, with keywords that only name one axis.
+enum OriginValue {
+    X(LengthOrPercentage),
+    Y(LengthOrPercentage),
+    Center,
+    Either(LengthOrPercentage),
+}
+
+impl FromStr for OriginValue {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let percentage = |value| LengthOrPercentage::Percentage(Percentage(value));
+        Ok(match s.to_ascii_lowercase().as_str() {
+            "left" => OriginValue::X(percentage(0.0)),
+            "right" => OriginValue::X(percentage(100.0)),
+            "top" => OriginValue::Y(percentage(0.0)),
+            "bottom" => OriginValue::Y(percentage(100.0)),
+            "center" => OriginValue::Center,
+            _ => OriginValue::Either(s.parse()?),
+        })
+    }
+}
+
+impl FromStr for TransformOrigin {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let center = LengthOrPercentage::Percentage(Percentage(50.0));
+        let values = s.split_ascii_whitespace().collect::<Vec<_>>();
+
+        let (x, y) = match values[..] {
+            [value] => match value.parse()? {
+                OriginValue::Y(y) => (center, y),
+                OriginValue::X(x) | OriginValue::Either(x) => (x, center),
+                OriginValue::Center => (center, center),
+            },
+            [first, second] | [first, second, _] => {
+                let (first, second) = (first.parse()?, second.parse()?);
+                match (first, second) {
+                    // keywords may name the vertical position first
+                    (OriginValue::Y(y), OriginValue::X(x)) => (x, y),
+                    (OriginValue::Y(y), OriginValue::Center) => (center, y),
+                    (OriginValue::Center, OriginValue::X(x)) => (x, center),
+                    (
+                        OriginValue::X(x) | OriginValue::Either(x),
+                        OriginValue::Y(y) | OriginValue::Either(y),
+                    ) => (x, y),
+                    (OriginValue::X(x) | OriginValue::Either(x), OriginValue::Center) => {
+                        (x, center)
+                    }
+                    (OriginValue::Center, OriginValue::Y(y) | OriginValue::Either(y)) => {
+                        (center, y)
+                    }
+                    (OriginValue::Center, OriginValue::Center) => (center, center),
+                    _ => return Err(()),
+                }
+            }
+            _ => return Err(()),
+        };
+        let z = match values.get(2) {
+            Some(z) => z.parse()?,
+            None => Length::number(0.0),
+        };
+
+        Ok(Self { x, y, z })
+    }
+}
+
+impl fmt::Display for TransformOrigin {
+    /// Writes the shortest form: `y` is left out when it is the center, and
+    /// `z` when it is zero.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let write = |value: &LengthOrPercentage| match value {
+            LengthOrPercentage::Percentage(Percentage(percentage)) if *percentage == 0.0 => {
+                "0".to_string()
+            }
+            LengthOrPercentage::Percentage(Percentage(percentage)) => {
+                format!("{}%", format_number(*percentage))
+            }
+            LengthOrPercentage::Length(length) => shortest_length(*length),
+        };
+
+        let center = LengthOrPercentage::Percentage(Percentage(50.0));
+        let has_z = self.z.value() != 0.0;
+
+        write!(f, "{}", write(&self.x))?;
+        if self.y != center || has_z {
+            write!(f, " {}", write(&self.y))?;
+        }
+        if has_z {
+            write!(f, " {}", shortest_length(self.z))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::svg::types::length;
+
+    fn list(s: &str) -> TransformList {
+        s.parse().unwrap()
+    }
+
+    fn assert_near(actual: Matrix, expected: Matrix) {
+        let (actual_values, expected_values) = (
+            [actual.a, actual.b, actual.c, actual.d, actual.e, actual.f],
+            [
+                expected.a, expected.b, expected.c, expected.d, expected.e, expected.f,
+            ],
+        );
+        for (actual_value, expected_value) in actual_values.iter().zip(expected_values) {
+            assert!(
+                (actual_value - expected_value).abs() < 1e-9,
+                "{actual:?} != {expected:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn transform_list_should_parse_svg_and_css_syntax() {
+        assert_eq!(
+            list(" translate(10,20) rotate ( 45 5 5 ),scale(2)skewX(-1e1)"),
+            TransformList(vec![
+                Transform::Translate(Length::number(10.0), Length::number(20.0)),
+                Transform::Rotate(Angle::new(45.0, angle::Unit::Number), 5.0, 5.0),
+                Transform::Scale(2.0, 2.0),
+                Transform::SkewX(Angle::new(-10.0, angle::Unit::Number)),
+            ])
+        );
+        assert_eq!(
+            list("rotate(45deg) translate(10px, 2em) scaleY(50%)"),
+            TransformList(vec![
+                Transform::Rotate(Angle::deg(45.0), 0.0, 0.0),
+                Transform::Translate(Length::px(10.0), Length::new(2.0, length::Unit::Em)),
+                Transform::Scale(1.0, 0.5),
+            ])
+        );
+        assert_eq!(
+            list("translate(10-5)"),
+            TransformList(vec![Transform::Translate(
+                Length::number(10.0),
+                Length::number(-5.0)
+            )])
+        );
+        assert_eq!(list("none"), TransformList::default());
+        assert_eq!(list(""), TransformList::default());
+
+        for input in [
+            "rotate(45 5)",
+            "translate(10",
+            "translate(1,)",
+            "scale(1),",
+            "spin(1)",
+            "scale(1px)",
+            "rotate(1px)",
+            "matrix(1 2 3)",
+        ] {
+            assert_eq!(input.parse::<TransformList>(), Err(()), "{input}");
+        }
+    }
+
+    #[test]
+    fn matrix_should_compose_invert_and_decompose() {
+        let context = LengthContext::default();
+        let matrix = list("translate(10 20) rotate(30) skewX(10) scale(2 3)").to_matrix(&context);
+
+        assert_near(
+            matrix,
+            Matrix::translate(10.0, 20.0)
+                * Matrix::rotate(30.0)
+                * Matrix::skew_x(10.0)
+                * Matrix::scale(2.0, 3.0),
+        );
+        assert_near(matrix * matrix.invert().unwrap(), Matrix::IDENTITY);
+        assert_eq!(Matrix::scale(0.0, 1.0).invert(), None);
+
+        let decomposition = matrix.decompose().unwrap();
+        assert!((decomposition.rotate - 30.0).abs() < 1e-9);
+        assert!((decomposition.skew_x - 10.0).abs() < 1e-9);
+        assert_near(decomposition.to_matrix(), matrix);
+        assert_near(
+            Matrix::scale(-1.0, 2.0).decompose().unwrap().to_matrix(),
+            Matrix::scale(-1.0, 2.0),
+        );
+
+        let rotate = list("rotate(90 50 50)").to_matrix(&context);
+        let (x, y) = rotate.apply((100.0, 50.0));
+        assert!((x - 50.0).abs() < 1e-9 && (y - 100.0).abs() < 1e-9);
+
+        let em = list("translate(1em)").to_matrix(&LengthContext {
+            font_size: 10.0,
+            ..context
+        });
+        assert_eq!(em, Matrix::translate(10.0, 0.0));
+    }
+
+    #[test]
+    fn transform_list_should_write_the_shortest_form() {
+        for (input, expected) in [
+            ("translate(10, 0)", "translate(10)"),
+            ("scale(2, 2)", "scale(2)"),
+            ("rotate(90deg)", "rotate(90)"),
+            ("rotate(0.5turn)", "scale(-1)"),
+            ("rotate(1rad)", "rotate(1rad)"),
+            ("translate(0.5px, 1in)", "translate(.5 96)"),
+            ("translate(10 20) translate(5 5)", "translate(15 25)"),
+            (
+                "translate(50 50) rotate(90) translate(-50 -50)",
+                "rotate(90 50 50)",
+            ),
+            ("rotate(45) rotate(-45)", ""),
+            ("matrix(1 0 0 1 0 0) scale(1)", ""),
+            ("translate(1em) scale(1)", "translate(1em)"),
+            ("translate(1em) rotate(30)", "translate(1em)rotate(30)"),
+            ("scale(2) translate(3 4)", "matrix(2 0 0 2 6 8)"),
+        ] {
+            assert_eq!(list(input).to_string(), expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn transform_origin_should_parse_keywords() {
+        let percentage = |x| LengthOrPercentage::Percentage(Percentage(x));
+        let origin = |s: &str| s.parse::<TransformOrigin>();
+
+        assert_eq!(
+            origin("top right"),
+            Ok(TransformOrigin {
+                x: percentage(100.0),
+                y: percentage(0.0),
+                z: Length::number(0.0),
+            })
+        );
+        assert_eq!(
+            origin("bottom"),
+            Ok(TransformOrigin {
+                x: percentage(50.0),
+                y: percentage(100.0),
+                z: Length::number(0.0),
+            })
+        );
+        assert_eq!(
+            origin("left 10px 5px"),
+            Ok(TransformOrigin {
+                x: percentage(0.0),
+                y: Length::px(10.0).into(),
+                z: Length::px(5.0),
+            })
+        );
+        for input in [
+            "top bottom",
+            "10px left",
+            "top 10px",
+            "",
+            "1 2 3 4",
+            "1 2 50%",
+        ] {
+            assert_eq!(origin(input), Err(()), "{input}");
+        }
+
+        assert_eq!(origin("left top").unwrap().to_string(), "0 0");
+        assert_eq!(origin("center").unwrap().to_string(), "50%");
+        assert_eq!(origin("10px center").unwrap().to_string(), "10");
+        assert_eq!(origin("1em 2em 3px").unwrap().to_string(), "1em 2em 3");
+        assert_eq!(
+            origin("25% 75%")
+                .unwrap()
+                .resolve(&LengthContext::default(), 200.0, 100.0),
+            (50.0, 75.0)
+        );
+    }
+}
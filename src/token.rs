@@ -1,9 +1,9 @@
 //! Token types emitted by the lexer.
 
-use std::ops::Range;
+use std::{borrow::Cow, fmt, ops::Range};
 
 /// The kind of a [`Token`].
-#[derive(Clone, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum TokenKind {
     /// A `<` delimiter.
     LessThan,
@@ -17,27 +17,59 @@ pub enum TokenKind {
     Identifier,
     /// A literal value inside quotes (attribute value).
     Literal,
-    /// The opening quote `"` of an attribute value.
+    /// The opening or closing quote (`"` or `'`) of an attribute value.
     Quote,
     /// An `=` sign (attribute equals).
     Equals,
     /// A complete comment (`<!-- ... -->`).
     Comment,
+    /// A complete CDATA section (`<![CDATA[ ... ]]>`).
+    CData,
+    /// A complete XML declaration (`<?xml ... ?>`).
+    XmlDeclaration,
+    /// A complete document type declaration (`<!DOCTYPE ... >`), including any internal subset.
+    Doctype,
+    /// A complete processing instruction (`<?target ... ?>`).
+    ProcessingInstruction,
     /// Text content between tags.
     Text,
 }
 
+impl fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenKind::LessThan => write!(f, "`<`"),
+            TokenKind::LessThanSlash => write!(f, "`</`"),
+            TokenKind::GreaterThan => write!(f, "`>`"),
+            TokenKind::SlashGreaterThan => write!(f, "`/>`"),
+            TokenKind::Identifier => write!(f, "identifier"),
+            TokenKind::Literal => write!(f, "literal"),
+            TokenKind::Quote => write!(f, "quote"),
+            TokenKind::Equals => write!(f, "`=`"),
+            TokenKind::Comment => write!(f, "comment"),
+            TokenKind::CData => write!(f, "CDATA section"),
+            TokenKind::XmlDeclaration => write!(f, "XML declaration"),
+            TokenKind::Doctype => write!(f, "DOCTYPE"),
+            TokenKind::ProcessingInstruction => write!(f, "processing instruction"),
+            TokenKind::Text => write!(f, "text"),
+        }
+    }
+}
+
 /// A single token emitted by the lexer.
 ///
-/// Contains the token type, its string value, and the byte span
-/// within the original source.
+/// Contains the token type, its string value, and the byte span within the
+/// original source. The value is borrowed from the source by a [`Lexer`] and
+/// owned when read by a [`StreamLexer`].
+///
+/// [`Lexer`]: crate::lexer::Lexer
+/// [`StreamLexer`]: crate::lexer::StreamLexer
 #[derive(Clone, Debug)]
-pub struct Token {
+pub struct Token<'input> {
     /// The kind of this token.
     pub kind: TokenKind,
     /// The token's text value.
-    pub value: String,
+    pub value: Cow<'input, str>,
     /// Byte range in the original source (inclusive start, exclusive end).
-    #[allow(dead_code)]
     pub span: Range<usize>,
 }